@@ -0,0 +1,63 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Batch validation throughput, serial vs parallel
+//!
+//! Run with `cargo bench --bench batch`. The corpus is the example contracts
+//! copied into a temporary archive of a few thousand files.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const COPIES: usize = 500;
+
+fn corpus() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples");
+
+    for entry in std::fs::read_dir(&examples).expect("examples directory") {
+        let path = entry.expect("example entry").path();
+        if path.extension().is_some_and(|ext| ext == "a2ml") {
+            let source = std::fs::read_to_string(&path).expect("example contract");
+            let stem = path.file_stem().unwrap().to_string_lossy().to_string();
+            for i in 0..COPIES {
+                std::fs::write(dir.path().join(format!("{}-{:04}.a2ml", stem, i)), &source).unwrap();
+            }
+        }
+    }
+
+    dir
+}
+
+fn run_batch(dir: &Path, output: &Path, jobs: Option<usize>) {
+    let schema: PathBuf = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../schemas/iww-freelancer-rights.a2ml");
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_union-policy-parser"));
+    cmd.arg("batch")
+        .arg(dir)
+        .arg("--schema")
+        .arg(schema)
+        .arg("--union")
+        .arg("iww")
+        .arg("--output")
+        .arg(output)
+        .env("RUST_LOG", "error");
+    if let Some(jobs) = jobs {
+        cmd.arg("--jobs").arg(jobs.to_string());
+    }
+
+    let status = cmd.output().expect("run batch").status;
+    assert!(status.success(), "batch failed");
+}
+
+fn bench_batch(c: &mut Criterion) {
+    let dir = corpus();
+    let output = dir.path().join("report.json");
+
+    let mut group = c.benchmark_group("batch");
+    group.sample_size(10);
+    group.bench_function("serial", |b| b.iter(|| run_batch(dir.path(), &output, Some(1))));
+    group.bench_function("parallel", |b| b.iter(|| run_batch(dir.path(), &output, None)));
+    group.finish();
+}
+
+criterion_group!(benches, bench_batch);
+criterion_main!(benches);
@@ -0,0 +1,18 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Generates `include/union_policy_parser.h` for the C ABI (`ffi` feature)
+
+fn main() {
+    #[cfg(feature = "ffi")]
+    {
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", dir)).unwrap();
+        cbindgen::Builder::new()
+            .with_crate(&dir)
+            .with_config(config)
+            .generate()
+            .expect("Unable to generate C header")
+            .write_to_file(format!("{}/include/union_policy_parser.h", dir));
+    }
+}
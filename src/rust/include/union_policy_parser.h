@@ -0,0 +1,97 @@
+/* SPDX-License-Identifier: PMPL-1.0-or-later */
+
+#ifndef UNION_POLICY_PARSER_H
+#define UNION_POLICY_PARSER_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Parse syntax only
+#define UNION_POLICY_PARSER_MODE_LAX 0
+
+// Validate structure (required fields)
+#define UNION_POLICY_PARSER_MODE_CHECKED 1
+
+// Verify legal compliance
+#define UNION_POLICY_PARSER_MODE_ATTESTED 2
+
+// Result codes, as in `ffi/zig/src/main.zig`
+typedef enum union_policy_parser_result {
+  UNION_POLICY_PARSER_RESULT_OK = 0,
+  UNION_POLICY_PARSER_RESULT_ERROR = 1,
+  UNION_POLICY_PARSER_RESULT_INVALID_PARAM = 2,
+  UNION_POLICY_PARSER_RESULT_OUT_OF_MEMORY = 3,
+  UNION_POLICY_PARSER_RESULT_NULL_POINTER = 4,
+} union_policy_parser_result;
+
+// Opaque handle: a parsed contract and the last report
+typedef struct union_policy_parser_handle union_policy_parser_handle;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Create a handle; free it with `union_policy_parser_free`
+struct union_policy_parser_handle *union_policy_parser_init(void);
+
+// Free a handle; null is ignored
+//
+// # Safety
+// `handle` must come from `union_policy_parser_init` and not be used afterwards.
+void union_policy_parser_free(struct union_policy_parser_handle *handle);
+
+// Parse an A2ML contract from `len` bytes of UTF-8; replaces any earlier
+// contract and clears the last report
+//
+// # Safety
+// `handle` must be a live handle and `buffer` valid for `len` bytes.
+enum union_policy_parser_result union_policy_parser_parse(struct union_policy_parser_handle *handle,
+                                                          const uint8_t *buffer,
+                                                          size_t len);
+
+// Validate the parsed contract against an A2ML schema (`schema_len` bytes of
+// UTF-8) in `mode`; `union_name` ("nuj", "iww", "ucu") may be null. With
+// a union, a null `schema` means the union's built-in schema. The report is
+// kept on the handle, and `valid` (if not null) is set to 1 or 0.
+//
+// # Safety
+// `handle` must be a live handle, `schema` null or valid for `schema_len`
+// bytes, `union_name` null or a NUL-terminated string, and `valid` null or
+// writable.
+enum union_policy_parser_result union_policy_parser_validate(struct union_policy_parser_handle *handle,
+                                                             const uint8_t *schema,
+                                                             size_t schema_len,
+                                                             const char *union_name,
+                                                             int mode,
+                                                             int *valid);
+
+// The last report as JSON; null on failure. Free with
+// `union_policy_parser_free_string`.
+//
+// # Safety
+// `handle` must be a live handle.
+char *union_policy_parser_render_json(const struct union_policy_parser_handle *handle);
+
+// Free a string returned by this library; null is ignored
+//
+// # Safety
+// `string` must come from this library and not be used afterwards.
+void union_policy_parser_free_string(char *string);
+
+// Message for the last failure on this thread, or null. Owned by the
+// library and valid until the next call on this thread; do not free.
+const char *union_policy_parser_last_error(void);
+
+// Library version (static string; do not free)
+const char *union_policy_parser_version(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* UNION_POLICY_PARSER_H */
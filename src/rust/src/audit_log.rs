@@ -0,0 +1,303 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Tamper-evident audit log (JSON Lines)
+//!
+//! Each validation run appends one line recording what was checked (the
+//! contract's and schema's SHA-256), under which rules (union, mode), what
+//! was found, by which tool version and when. Every entry carries the
+//! SHA-256 of the line before it, and entries are numbered, so editing or
+//! deleting an entry breaks the chain at that point:
+//!
+//! ```json
+//! {"seq":2,"time":"2025-06-01T09:00:00Z","prev":"9f86d0…","command":"audit",…}
+//! ```
+//!
+//! Removing entries from the end leaves a shorter but intact chain; record
+//! the head hash that `verify` reports (in a grievance file, or signed) and
+//! pass it back with [`AuditLog::verify_head`] to catch that too.
+//!
+//! Appends hold an exclusive lock on the file, so two runs writing at once
+//! cannot both chain onto the same line.
+
+use crate::error::{PolicyError, Result};
+use crate::validator::{DocumentInfo, Severity, ValidationReport};
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Log file used when none is configured
+pub const DEFAULT_LOG_FILE: &str = "union-policy-audit.jsonl";
+
+/// `prev` of the first entry
+pub const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// One validation run
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LogEntry {
+    /// Position in the log, from 1
+    pub seq: u64,
+
+    /// When the validation ran (RFC 3339, UTC)
+    pub time: String,
+
+    /// SHA-256 of the previous line, or [`GENESIS`]
+    pub prev: String,
+
+    /// Command that ran the validation (`audit`, `validate`)
+    pub command: String,
+
+    pub contract: DocumentInfo,
+
+    pub schema: DocumentInfo,
+
+    pub union: Option<String>,
+
+    pub mode: String,
+
+    pub valid: bool,
+
+    pub findings: FindingsSummary,
+
+    pub tool_version: String,
+}
+
+/// Counts and rule IDs of a run's findings
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FindingsSummary {
+    pub must: usize,
+    pub should: usize,
+    /// Distinct rule IDs, sorted
+    pub rules: Vec<String>,
+}
+
+impl LogEntry {
+    /// An entry for a report; `seq` and `prev` are set when appended
+    pub fn of_report(report: &ValidationReport, command: &str, mode: &str) -> Self {
+        let findings = report.findings();
+        let mut rules: Vec<String> = findings.iter().map(|f| f.rule.clone()).collect();
+        rules.sort();
+        rules.dedup();
+        Self {
+            seq: 0,
+            time: report.timestamp.clone(),
+            prev: String::new(),
+            command: command.to_string(),
+            contract: report.contract.clone(),
+            schema: report.schema.clone(),
+            union: report.union.clone(),
+            mode: mode.to_string(),
+            valid: report.valid,
+            findings: FindingsSummary {
+                must: findings.iter().filter(|f| f.severity == Severity::Must).count(),
+                should: findings.iter().filter(|f| f.severity == Severity::Should).count(),
+                rules,
+            },
+            tool_version: report.tool_version.clone(),
+        }
+    }
+}
+
+/// Where the chain is broken
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ChainBreak {
+    /// Line number in the file, from 1
+    pub line: usize,
+    pub problem: String,
+}
+
+/// Result of checking a log
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Verification {
+    pub entries: usize,
+    /// SHA-256 of the last line; record it to detect truncation later
+    pub head: String,
+    pub breaks: Vec<ChainBreak>,
+}
+
+impl Verification {
+    pub fn is_intact(&self) -> bool {
+        self.breaks.is_empty()
+    }
+}
+
+/// An append-only log file
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn lines(&self) -> Result<Vec<String>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        Ok(split_lines(&fs::read_to_string(&self.path)?))
+    }
+
+    /// Chain an entry onto the log and write it; the entry as written
+    pub fn append(&self, mut entry: LogEntry) -> Result<LogEntry> {
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new().create(true).read(true).append(true).open(&self.path)?;
+        // Held until `file` is dropped, across reading the last line and writing ours
+        file.lock()?;
+
+        let mut source = String::new();
+        file.read_to_string(&mut source)?;
+        let lines = split_lines(&source);
+        entry.seq = lines.len() as u64 + 1;
+        entry.prev = lines.last().map_or(GENESIS.to_string(), |line| line_hash(line));
+
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(entry)
+    }
+
+    /// Every entry, in order
+    pub fn entries(&self) -> Result<Vec<LogEntry>> {
+        self.lines()?
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                serde_json::from_str(line).map_err(|e| {
+                    PolicyError::ParseError(format!("{} line {}: {}", self.path.display(), i + 1, e))
+                })
+            })
+            .collect()
+    }
+
+    /// Check the chain: each entry numbered in turn and holding the hash of
+    /// the line before it
+    pub fn verify(&self) -> Result<Verification> {
+        let lines = self.lines()?;
+        let mut breaks = Vec::new();
+        let mut prev = GENESIS.to_string();
+        let mut expected_seq = 1;
+
+        for (i, line) in lines.iter().enumerate() {
+            let problem = match serde_json::from_str::<LogEntry>(line) {
+                Err(e) => Some(format!("not a log entry: {}", e)),
+                Ok(entry) if entry.prev != prev => Some(format!(
+                    "previous entry's hash is {}, but this entry records {}; an earlier line was edited or removed",
+                    short(&prev),
+                    short(&entry.prev)
+                )),
+                Ok(entry) if entry.seq != expected_seq => {
+                    Some(format!("entry {} where {} was expected", entry.seq, expected_seq))
+                }
+                Ok(entry) => {
+                    expected_seq = entry.seq + 1;
+                    None
+                }
+            };
+            if let Some(problem) = problem {
+                breaks.push(ChainBreak { line: i + 1, problem });
+                expected_seq += 1;
+            }
+            prev = line_hash(line);
+        }
+
+        Ok(Verification { entries: lines.len(), head: prev, breaks })
+    }
+
+    /// [`verify`](Self::verify), and check that a head recorded earlier is
+    /// still in the log; if it is not, entries were removed from the end
+    pub fn verify_head(&self, recorded: &str) -> Result<Verification> {
+        let mut verification = self.verify()?;
+        let recorded = recorded.trim().to_lowercase();
+        if recorded != GENESIS && !self.lines()?.iter().any(|line| line_hash(line) == recorded) {
+            verification.breaks.push(ChainBreak {
+                line: verification.entries + 1,
+                problem: format!(
+                    "recorded head {} is no longer in the log; it was cut back past that entry",
+                    short(&recorded)
+                ),
+            });
+        }
+        Ok(verification)
+    }
+}
+
+fn split_lines(source: &str) -> Vec<String> {
+    source.lines().filter(|l| !l.trim().is_empty()).map(str::to_string).collect()
+}
+
+/// SHA-256 of a log line, without its newline (hex)
+pub fn line_hash(line: &str) -> String {
+    hex::encode(Sha256::digest(line.as_bytes()))
+}
+
+fn short(hash: &str) -> &str {
+    &hash[..hash.len().min(12)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str) -> LogEntry {
+        let mut report = ValidationReport::new(path.to_string(), "schema.a2ml".to_string());
+        report.union = Some("nuj".to_string());
+        report.add_warning("Contract has no @refs section".to_string(), None);
+        LogEntry::of_report(&report, "audit", "attested")
+    }
+
+    #[test]
+    fn test_chain_detects_edits_and_deletions() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::new(dir.path().join("audit.jsonl"));
+        for path in ["a.a2ml", "b.a2ml", "a.a2ml"] {
+            log.append(entry(path)).unwrap();
+        }
+        let entries = log.entries().unwrap();
+        assert_eq!(entries[2].seq, 3);
+        assert_eq!(entries[0].prev, GENESIS);
+        assert_eq!(entries[1].findings.rules, ["a2ml/structure"]);
+        let verification = log.verify().unwrap();
+        assert!(verification.is_intact());
+        assert_eq!(verification.head, line_hash(fs::read_to_string(log.path()).unwrap().lines().last().unwrap()));
+
+        let source = fs::read_to_string(log.path()).unwrap();
+        let lines: Vec<&str> = source.lines().collect();
+
+        // Editing an entry breaks the link from the next one
+        fs::write(log.path(), source.replacen("\"valid\":true", "\"valid\":false", 1)).unwrap();
+        let breaks = log.verify().unwrap().breaks;
+        assert_eq!(breaks.iter().map(|b| b.line).collect::<Vec<_>>(), [2]);
+
+        // So does deleting one
+        fs::write(log.path(), format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        assert_eq!(log.verify().unwrap().breaks[0].line, 2);
+    }
+
+    #[test]
+    fn test_recorded_head_detects_truncation() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::new(dir.path().join("audit.jsonl"));
+        log.append(entry("a.a2ml")).unwrap();
+        let head = log.verify().unwrap().head;
+
+        // Later runs keep the recorded head in the chain
+        log.append(entry("b.a2ml")).unwrap();
+        assert!(log.verify_head(&head).unwrap().is_intact());
+
+        // Cutting the log back past it does not
+        let source = fs::read_to_string(log.path()).unwrap();
+        fs::write(log.path(), "").unwrap();
+        assert!(log.verify().unwrap().is_intact());
+        let verification = log.verify_head(&head).unwrap();
+        assert_eq!(verification.breaks.len(), 1);
+        assert!(verification.breaks[0].problem.contains("cut back"));
+
+        fs::write(log.path(), source).unwrap();
+        assert!(log.verify_head(&head.to_uppercase()).unwrap().is_intact());
+    }
+}
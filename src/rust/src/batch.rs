@@ -0,0 +1,596 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Batch results and their output formats (JSON, CSV, JUnit XML, Markdown)
+//!
+//! Every discovered file yields one [`BatchEntry`], including files that fail
+//! to parse, so a batch report accounts for the whole directory. Each format
+//! lists per-contract findings and ends with totals by severity and clause.
+
+use crate::cache::Cache;
+use crate::error::{PolicyError, Result};
+use crate::parser::parse_a2ml_string;
+use crate::validator::{Finding, Severity, ValidationReport, Validator};
+use rayon::prelude::*;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Rule ID recorded for files that could not be parsed
+pub const PARSE_ERROR_RULE: &str = "a2ml/parse-error";
+
+/// Result of processing one file
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum BatchOutcome {
+    Validated(Box<ValidationReport>),
+    ParseError(String),
+}
+
+/// One file in a batch run
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BatchEntry {
+    pub path: String,
+    pub outcome: BatchOutcome,
+}
+
+impl BatchEntry {
+    /// "valid", "invalid" or "parse-error"
+    pub fn status(&self) -> &'static str {
+        match &self.outcome {
+            BatchOutcome::Validated(report) if report.valid => "valid",
+            BatchOutcome::Validated(_) => "invalid",
+            BatchOutcome::ParseError(_) => "parse-error",
+        }
+    }
+
+    /// Findings for this file; a parse failure is a single MUST finding
+    pub fn findings(&self) -> Vec<Finding> {
+        match &self.outcome {
+            BatchOutcome::Validated(report) => report.findings(),
+            BatchOutcome::ParseError(message) => vec![Finding {
+                rule: PARSE_ERROR_RULE.to_string(),
+                severity: Severity::Must,
+                message: message.clone(),
+                location: None,
+                line: None,
+                help: None,
+            }],
+        }
+    }
+}
+
+/// Validate files in parallel on the current rayon pool
+///
+/// One validator is shared by every worker. Each worker parses, validates and
+/// drops its document, so memory grows with the number of reports rather than
+/// the size of the archive. Entries come back in the order of `files`,
+/// whatever order they finish in.
+///
+/// With a cache, files whose bytes are unchanged reuse their earlier outcome.
+pub fn validate_files(
+    files: &[PathBuf],
+    validator: &Validator,
+    required_clauses: &[String],
+    cache: Option<&Cache>,
+    progress: bool,
+) -> Vec<BatchEntry> {
+    let progress = Progress::new(files.len(), progress);
+
+    let entries = files
+        .par_iter()
+        .map(|file| {
+            let path = file.to_string_lossy().to_string();
+            let outcome = match std::fs::read(file) {
+                Ok(bytes) => {
+                    let key = cache.map(|c| c.key(&bytes));
+                    match (cache, &key) {
+                        (Some(cache), Some(key)) => cache.get(key).unwrap_or_else(|| {
+                            let outcome = validate_bytes(&bytes, validator, required_clauses);
+                            cache.put(key, &outcome);
+                            outcome
+                        }),
+                        _ => validate_bytes(&bytes, validator, required_clauses),
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    BatchOutcome::ParseError(PolicyError::FileNotFound(file.clone()).to_string())
+                }
+                Err(e) => BatchOutcome::ParseError(PolicyError::from(e).to_string()),
+            };
+
+            let outcome = match outcome {
+                BatchOutcome::Validated(mut report) => {
+                    // A cached report may have come from an identical file elsewhere
+                    report.contract.path = path.clone();
+                    BatchOutcome::Validated(report)
+                }
+                BatchOutcome::ParseError(message) => {
+                    log::warn!("Failed to parse {:?}: {}", file, message);
+                    BatchOutcome::ParseError(message)
+                }
+            };
+            progress.tick();
+            BatchEntry { path, outcome }
+        })
+        .collect();
+
+    progress.finish();
+    entries
+}
+
+fn validate_bytes(bytes: &[u8], validator: &Validator, required_clauses: &[String]) -> BatchOutcome {
+    let parsed = std::str::from_utf8(bytes)
+        .map_err(|e| PolicyError::ParseError(format!("Contract is not UTF-8: {}", e)))
+        .and_then(parse_a2ml_string);
+    match parsed {
+        Ok(contract) => BatchOutcome::Validated(Box::new(validator.validate(&contract, required_clauses))),
+        Err(e) => BatchOutcome::ParseError(e.to_string()),
+    }
+}
+
+/// "Validated n/total" counter on stderr
+struct Progress {
+    total: usize,
+    step: usize,
+    done: AtomicUsize,
+    enabled: bool,
+}
+
+impl Progress {
+    fn new(total: usize, enabled: bool) -> Self {
+        Self {
+            total,
+            step: (total / 100).max(1),
+            done: AtomicUsize::new(0),
+            enabled: enabled && total > 0,
+        }
+    }
+
+    fn tick(&self) {
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.enabled && (done.is_multiple_of(self.step) || done == self.total) {
+            eprint!("\rValidated {}/{} files", done, self.total);
+        }
+    }
+
+    fn finish(&self) {
+        if self.enabled {
+            eprintln!();
+        }
+    }
+}
+
+/// Totals across a batch run
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BatchSummary {
+    pub files: usize,
+    pub valid: usize,
+    pub invalid: usize,
+    pub parse_errors: usize,
+    pub by_severity: BTreeMap<Severity, usize>,
+    pub by_clause: BTreeMap<String, usize>,
+}
+
+impl BatchSummary {
+    pub fn new(entries: &[BatchEntry]) -> Self {
+        let mut summary = Self {
+            files: entries.len(),
+            ..Default::default()
+        };
+
+        for entry in entries {
+            match entry.status() {
+                "valid" => summary.valid += 1,
+                "invalid" => summary.invalid += 1,
+                _ => summary.parse_errors += 1,
+            }
+            for finding in entry.findings() {
+                *summary.by_severity.entry(finding.severity).or_default() += 1;
+                *summary.by_clause.entry(finding.rule).or_default() += 1;
+            }
+        }
+
+        summary
+    }
+}
+
+/// Validation reports of the files that parsed, for per-report formats (SARIF)
+pub fn reports(entries: &[BatchEntry]) -> Vec<ValidationReport> {
+    entries
+        .iter()
+        .filter_map(|entry| match &entry.outcome {
+            BatchOutcome::Validated(report) => Some((**report).clone()),
+            BatchOutcome::ParseError(_) => None,
+        })
+        .collect()
+}
+
+/// Render a batch run as JSON
+pub fn render_json(entries: &[BatchEntry]) -> Result<String> {
+    let summary = BatchSummary::new(entries);
+
+    let results: Vec<_> = entries
+        .iter()
+        .map(|entry| {
+            let mut result = json!({
+                "file": entry.path,
+                "status": entry.status(),
+                "findings": entry.findings().iter().map(|f| json!({
+                    "rule": f.rule,
+                    "severity": f.severity.as_str(),
+                    "message": f.message,
+                    "section": f.location,
+                    "line": f.line,
+                })).collect::<Vec<_>>(),
+            });
+            if let BatchOutcome::Validated(report) = &entry.outcome {
+                result["valid"] = json!(report.valid);
+                result["errors"] = json!(report.errors.len());
+                result["warnings"] = json!(report.warnings.len());
+            }
+            result
+        })
+        .collect();
+
+    Ok(serde_json::to_string_pretty(&json!({
+        "total_files": summary.files,
+        "valid": summary.valid,
+        "invalid": summary.invalid,
+        "parse_errors": summary.parse_errors,
+        "totals": {
+            "by_severity": summary
+                .by_severity
+                .iter()
+                .map(|(severity, count)| (severity.as_str().to_string(), json!(count)))
+                .collect::<serde_json::Map<_, _>>(),
+            "by_clause": summary.by_clause,
+        },
+        "results": results,
+    }))?)
+}
+
+/// Render a batch run as CSV, one row per finding
+///
+/// Files without findings get a single row with an empty rule, so every file
+/// appears. Totals follow as rows with status `total`. A cell starting with
+/// `=`, `+`, `-` or `@` gets a leading `'`, so a spreadsheet shows contract
+/// text instead of running it as a formula.
+pub fn render_csv(entries: &[BatchEntry]) -> String {
+    let mut out = String::from("file,status,rule,severity,line,section,message\n");
+
+    for entry in entries {
+        let findings = entry.findings();
+        if findings.is_empty() {
+            out.push_str(&csv_row(&[&entry.path, entry.status(), "", "", "", "", ""]));
+        }
+        for finding in findings {
+            let line = finding.line.map(|l| l.to_string()).unwrap_or_default();
+            out.push_str(&csv_row(&[
+                &entry.path,
+                entry.status(),
+                &finding.rule,
+                finding.severity.as_str(),
+                &line,
+                finding.location.as_deref().unwrap_or(""),
+                &finding.message,
+            ]));
+        }
+    }
+
+    let summary = BatchSummary::new(entries);
+    for (severity, count) in &summary.by_severity {
+        out.push_str(&csv_row(&["", "total", "", severity.as_str(), "", "", &count.to_string()]));
+    }
+    for (rule, count) in &summary.by_clause {
+        out.push_str(&csv_row(&["", "total", rule, "", "", "", &count.to_string()]));
+    }
+
+    out
+}
+
+fn csv_row(fields: &[&str]) -> String {
+    let quoted: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            let field = if field.starts_with(['=', '+', '-', '@']) {
+                format!("'{}", field)
+            } else {
+                field.to_string()
+            };
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field
+            }
+        })
+        .collect();
+    format!("{}\n", quoted.join(","))
+}
+
+/// Render a batch run as JUnit XML, one test case per contract
+///
+/// MUST findings fail the test case and parse failures are errors; SHOULD and
+/// COULD findings go to `system-out` so they are visible without failing CI.
+pub fn render_junit(entries: &[BatchEntry]) -> String {
+    let summary = BatchSummary::new(entries);
+    let failures = summary.invalid;
+    let errors = summary.parse_errors;
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"union-policy-parser\" tests=\"{}\" failures=\"{}\" errors=\"{}\">\n",
+        summary.files, failures, errors
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"contracts\" tests=\"{}\" failures=\"{}\" errors=\"{}\">\n",
+        summary.files, failures, errors
+    ));
+
+    xml.push_str("    <properties>\n");
+    for (severity, count) in &summary.by_severity {
+        xml.push_str(&format!(
+            "      <property name=\"severity.{}\" value=\"{}\"/>\n",
+            severity.as_str(),
+            count
+        ));
+    }
+    for (rule, count) in &summary.by_clause {
+        xml.push_str(&format!("      <property name=\"clause.{}\" value=\"{}\"/>\n", xml_attr(rule), count));
+    }
+    xml.push_str("    </properties>\n");
+
+    for entry in entries {
+        xml.push_str(&format!(
+            "    <testcase classname=\"contracts\" name=\"{}\" file=\"{}\">\n",
+            xml_attr(&entry.path),
+            xml_attr(&entry.path)
+        ));
+
+        let findings = entry.findings();
+        match &entry.outcome {
+            BatchOutcome::ParseError(message) => {
+                xml.push_str(&format!(
+                    "      <error type=\"{}\" message=\"{}\"/>\n",
+                    PARSE_ERROR_RULE,
+                    xml_attr(message)
+                ));
+            }
+            BatchOutcome::Validated(report) if !report.valid => {
+                let must: Vec<&Finding> = findings.iter().filter(|f| f.severity == Severity::Must).collect();
+                xml.push_str(&format!(
+                    "      <failure type=\"MUST\" message=\"{} MUST finding(s)\">\n",
+                    must.len()
+                ));
+                for finding in must {
+                    xml.push_str(&format!("{}\n", xml_text(&finding_line(finding))));
+                }
+                xml.push_str("      </failure>\n");
+            }
+            BatchOutcome::Validated(_) => {}
+        }
+
+        let advisory: Vec<&Finding> = findings.iter().filter(|f| f.severity != Severity::Must).collect();
+        if !advisory.is_empty() {
+            xml.push_str("      <system-out>\n");
+            for finding in advisory {
+                xml.push_str(&format!("{}\n", xml_text(&finding_line(finding))));
+            }
+            xml.push_str("      </system-out>\n");
+        }
+
+        xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// Escape XML character data, dropping characters XML 1.0 does not allow
+fn xml_text(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if c < ' ' || c == '\u{FFFE}' || c == '\u{FFFF}' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escape a double-quoted XML attribute value, keeping line breaks and tabs
+/// (which parsers would otherwise turn into spaces)
+fn xml_attr(s: &str) -> String {
+    xml_text(s)
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+        .replace('\t', "&#9;")
+        .replace('\n', "&#10;")
+        .replace('\r', "&#13;")
+}
+
+/// Render a batch run as Markdown: summary tables, then findings per contract
+pub fn render_markdown(entries: &[BatchEntry]) -> String {
+    let summary = BatchSummary::new(entries);
+    let mut md = String::from("# Batch Validation Report\n\n");
+
+    md.push_str("| Files | Valid | Invalid | Parse errors |\n");
+    md.push_str("|-------|-------|---------|--------------|\n");
+    md.push_str(&format!(
+        "| {} | {} | {} | {} |\n\n",
+        summary.files, summary.valid, summary.invalid, summary.parse_errors
+    ));
+
+    if !summary.by_severity.is_empty() {
+        md.push_str("## Findings by Severity\n\n| Severity | Count |\n|----------|-------|\n");
+        for (severity, count) in &summary.by_severity {
+            md.push_str(&format!("| {} | {} |\n", severity.as_str(), count));
+        }
+        md.push('\n');
+
+        md.push_str("## Findings by Clause\n\n| Clause | Count |\n|--------|-------|\n");
+        for (rule, count) in &summary.by_clause {
+            md.push_str(&format!("| `{}` | {} |\n", rule, count));
+        }
+        md.push('\n');
+    }
+
+    md.push_str("## Contracts\n\n");
+    for entry in entries {
+        let icon = match entry.status() {
+            "valid" => "✅",
+            "invalid" => "❌",
+            _ => "⚠️",
+        };
+        md.push_str(&format!("### {} `{}` ({})\n\n", icon, entry.path, entry.status()));
+
+        let findings = entry.findings();
+        if findings.is_empty() {
+            md.push_str("No findings.\n\n");
+            continue;
+        }
+        for finding in findings {
+            md.push_str(&format!("- {}\n", finding_line(&finding)));
+        }
+        md.push('\n');
+    }
+
+    format!("{}\n", md.trim_end())
+}
+
+/// "[MUST] nuj/source-protection: message (section, line 12)"
+fn finding_line(finding: &Finding) -> String {
+    let mut line = format!("[{}] {}: {}", finding.severity.as_str(), finding.rule, finding.message);
+    match (&finding.location, finding.line) {
+        (Some(section), Some(n)) => line.push_str(&format!(" ({}, line {})", section, n)),
+        (Some(section), None) => line.push_str(&format!(" ({})", section)),
+        (None, Some(n)) => line.push_str(&format!(" (line {})", n)),
+        (None, None) => {}
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_a2ml_string;
+    use crate::schemas::Union;
+    use crate::validator::{ValidationMode, Validator};
+
+    fn entries() -> Vec<BatchEntry> {
+        let validator = Validator::new(Default::default(), ValidationMode::Checked).with_union(Union::Iww);
+        let required = vec!["payment-terms".to_string()];
+
+        let good = parse_a2ml_string("@abstract:\nGood.\n@end\n\n## Payment Terms\n\nNET 30.\n").unwrap();
+        let bad = parse_a2ml_string("@abstract:\nBad.\n@end\n\n## Fees\n\nPaid NET 90, \"unpaid\" trial.\n").unwrap();
+
+        vec![
+            BatchEntry {
+                path: "good.a2ml".to_string(),
+                outcome: BatchOutcome::Validated(Box::new(validator.validate(&good, &required))),
+            },
+            BatchEntry {
+                path: "bad.a2ml".to_string(),
+                outcome: BatchOutcome::Validated(Box::new(validator.validate(&bad, &required))),
+            },
+            BatchEntry {
+                path: "broken.a2ml".to_string(),
+                outcome: BatchOutcome::ParseError("Unexpected content at line 3".to_string()),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_parallel_results_keep_input_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut files = Vec::new();
+        for i in 0..40 {
+            let path = dir.path().join(format!("{:02}.a2ml", i));
+            let source = if i % 7 == 0 {
+                format!("## Fees {}\n\n| broken |\nnot a table\n", i)
+            } else {
+                format!("@abstract:\nContract {}.\n@end\n\n## Payment Terms\n\nNET 30.\n", i)
+            };
+            std::fs::write(&path, source).unwrap();
+            files.push(path);
+        }
+
+        let validator = Validator::new(Default::default(), ValidationMode::Checked).with_union(Union::Iww);
+        let required = ["payment-terms".to_string()];
+        let entries = validate_files(&files, &validator, &required, None, false);
+
+        let paths: Vec<String> = files.iter().map(|f| f.to_string_lossy().to_string()).collect();
+        assert_eq!(entries.iter().map(|e| e.path.clone()).collect::<Vec<_>>(), paths);
+        assert_eq!(entries.iter().filter(|e| e.status() == "parse-error").count(), 6);
+        assert_eq!(entries.iter().filter(|e| e.status() == "valid").count(), 34);
+
+        // A second run reuses every result; a changed file is re-validated
+        let cache_dir = tempfile::tempdir().unwrap();
+        let cache = Cache::open(cache_dir.path(), &validator.fingerprint(&required)).unwrap();
+        validate_files(&files, &validator, &required, Some(&cache), false);
+        std::fs::write(&files[1], "## Fees\n\nPaid NET 90.\n").unwrap();
+        let cached = validate_files(&files, &validator, &required, Some(&cache), false);
+        assert_eq!(cache.stats(), (39, 41));
+        assert_eq!(cached[1].status(), "invalid");
+        assert_eq!(cached[2].status(), "valid");
+    }
+
+    #[test]
+    fn test_summary_totals() {
+        let summary = BatchSummary::new(&entries());
+        assert_eq!((summary.files, summary.valid, summary.invalid, summary.parse_errors), (3, 1, 1, 1));
+        assert_eq!(summary.by_clause["iww/payment-terms"], 1);
+        assert_eq!(summary.by_clause["iww/red-flag/net-90"], 1);
+        assert_eq!(summary.by_clause[PARSE_ERROR_RULE], 1);
+        // Three recommended clauses and the @refs warning, in both parsed contracts
+        assert_eq!(summary.by_severity[&Severity::Should], 8);
+    }
+
+    #[test]
+    fn test_csv_quotes_and_totals() {
+        let csv = render_csv(&entries());
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("file,status,rule,severity,line,section,message"));
+        assert!(csv.contains("bad.a2ml,invalid,iww/red-flag/unpaid,MUST,7,Fees,\"Red flag: \"\"unpaid\"\"\"\n"));
+        assert!(csv.contains("broken.a2ml,parse-error,a2ml/parse-error,MUST,,,Unexpected content at line 3\n"));
+        assert!(csv.contains(",total,iww/payment-terms,,,,1\n"));
+
+        let entry = BatchEntry {
+            path: "=HYPERLINK(\"x\").a2ml".to_string(),
+            outcome: BatchOutcome::ParseError("@SUM(1)".to_string()),
+        };
+        let csv = render_csv(&[entry]);
+        assert!(csv.contains("\"'=HYPERLINK(\"\"x\"\").a2ml\",parse-error,a2ml/parse-error,MUST,,,'@SUM(1)\n"), "{}", csv);
+    }
+
+    #[test]
+    fn test_junit_failures_and_errors() {
+        let xml = render_junit(&entries());
+        assert!(xml.contains("<testsuites name=\"union-policy-parser\" tests=\"3\" failures=\"1\" errors=\"1\">"));
+        assert!(xml.contains("<testcase classname=\"contracts\" name=\"bad.a2ml\" file=\"bad.a2ml\">\n      <failure type=\"MUST\""));
+        assert!(xml.contains("<error type=\"a2ml/parse-error\" message=\"Unexpected content at line 3\"/>"));
+        assert!(xml.contains("<property name=\"severity.MUST\""));
+
+        let entry = BatchEntry {
+            path: "o'brien.a2ml".to_string(),
+            outcome: BatchOutcome::ParseError("Bad \u{1b}[0m <tag>\nat line 3".to_string()),
+        };
+        let xml = render_junit(&[entry]);
+        assert!(xml.contains("name=\"o&apos;brien.a2ml\""));
+        assert!(xml.contains("message=\"Bad [0m &lt;tag&gt;&#10;at line 3\""), "{}", xml);
+    }
+
+    #[test]
+    fn test_json_and_markdown_list_findings() {
+        let json: serde_json::Value = serde_json::from_str(&render_json(&entries()).unwrap()).unwrap();
+        assert_eq!(json["parse_errors"], 1);
+        assert_eq!(json["results"][2]["status"], "parse-error");
+        assert_eq!(json["totals"]["by_severity"]["SHOULD"], 8);
+
+        let md = render_markdown(&entries());
+        assert!(md.contains("### ❌ `bad.a2ml` (invalid)"));
+        assert!(md.contains("- [MUST] iww/red-flag/net-90: Red flag: \"NET 90\" (Fees, line 7)"));
+    }
+}
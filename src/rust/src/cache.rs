@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! On-disk cache of batch results
+//!
+//! Entries are keyed by the SHA-256 of the contract bytes, the validator's
+//! fingerprint (schema and rules) and the tool version, so any change to one
+//! of them misses the cache. Parse failures are cached too: an unchanged file
+//! fails the same way. A reused report keeps the timestamp of the run that
+//! produced it. Each entry is a small JSON file, written atomically so
+//! parallel workers never see a partial entry.
+
+use crate::batch::BatchOutcome;
+use crate::error::Result;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Default cache directory: `union-policy-parser` under the user's cache
+/// home (`$XDG_CACHE_HOME`, else `~/.cache`; `%LOCALAPPDATA%` on Windows).
+/// Entries are keyed by content, so every batch directory can share it.
+pub fn default_dir() -> Option<PathBuf> {
+    let home = |var: &str| std::env::var_os(var).filter(|v| !v.is_empty()).map(PathBuf::from);
+    let base = if cfg!(windows) {
+        home("LOCALAPPDATA")
+    } else {
+        home("XDG_CACHE_HOME").or_else(|| home("HOME").map(|h| h.join(".cache")))
+    };
+    base.map(|base| base.join("union-policy-parser"))
+}
+
+/// Cache of batch outcomes for one validator configuration
+pub struct Cache {
+    dir: PathBuf,
+    namespace: String,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl Cache {
+    /// Open (creating if needed) a cache for results produced under `fingerprint`
+    pub fn open(dir: &Path, fingerprint: &str) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            namespace: format!("{}|{}", fingerprint, env!("CARGO_PKG_VERSION")),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        })
+    }
+
+    /// Cache key for a contract's bytes
+    pub fn key(&self, contract: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(Sha256::digest(contract));
+        hasher.update(self.namespace.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    /// Cached outcome, if any; unreadable entries count as misses
+    pub fn get(&self, key: &str) -> Option<BatchOutcome> {
+        let outcome = fs::read(self.entry_path(key))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+        match outcome {
+            Some(_) => self.hits.fetch_add(1, Ordering::Relaxed),
+            None => self.misses.fetch_add(1, Ordering::Relaxed),
+        };
+        outcome
+    }
+
+    /// Store an outcome; failures are logged, since the cache is only an optimisation
+    pub fn put(&self, key: &str, outcome: &BatchOutcome) {
+        if let Err(e) = self.write(key, outcome) {
+            log::warn!("Could not write cache entry {}: {}", key, e);
+        }
+    }
+
+    /// (hits, misses) so far
+    pub fn stats(&self) -> (usize, usize) {
+        (self.hits.load(Ordering::Relaxed), self.misses.load(Ordering::Relaxed))
+    }
+
+    fn write(&self, key: &str, outcome: &BatchOutcome) -> Result<()> {
+        let path = self.entry_path(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Unique per write, so two workers storing the same key cannot interleave
+        static WRITES: AtomicUsize = AtomicUsize::new(0);
+        let temp = path.with_extension(format!(
+            "{}-{}.tmp",
+            std::process::id(),
+            WRITES.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temp, serde_json::to_vec(outcome)?)?;
+        fs::rename(&temp, &path)?;
+        Ok(())
+    }
+
+    /// `<dir>/ab/abcdef....json`, so no directory grows too large
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(&key[..2]).join(format!("{}.json", key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator::ValidationReport;
+
+    #[test]
+    fn test_round_trip_and_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::open(dir.path(), "schema-a").unwrap();
+
+        let key = cache.key(b"## Pay\n\nNET 30.\n");
+        assert_eq!(key, cache.key(b"## Pay\n\nNET 30.\n"));
+        assert_ne!(key, cache.key(b"## Pay\n\nNET 90.\n"));
+        assert_ne!(key, Cache::open(dir.path(), "schema-b").unwrap().key(b"## Pay\n\nNET 30.\n"));
+
+        assert!(cache.get(&key).is_none());
+        let mut report = ValidationReport::new("a.a2ml".to_string(), "s.a2ml".to_string());
+        report.add_warning("No references".to_string(), None);
+        cache.put(&key, &BatchOutcome::Validated(Box::new(report)));
+
+        match cache.get(&key) {
+            Some(BatchOutcome::Validated(cached)) => assert_eq!(cached.warnings[0].message, "No references"),
+            other => panic!("unexpected cache entry: {:?}", other),
+        }
+        assert_eq!(cache.stats(), (1, 1));
+    }
+}
@@ -0,0 +1,278 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Project configuration (`union-policy.toml`)
+//!
+//! The nearest `union-policy.toml`, found by walking up from the working
+//! directory, supplies defaults so a team's contract repository does not
+//! repeat `--schema`, `--union` and friends on every command:
+//!
+//! ```toml
+//! schema = "schemas/nuj-code-of-ethics.a2ml"
+//! union = "nuj"
+//! mode = "attested"
+//! audit_log = "audit/union-policy-audit.jsonl"
+//! trusted_keys = ["keys/nuj-union.pub"]
+//! require_signatures = true
+//! required_clauses = ["kill-fee-provision"]
+//! red_flags = ["exclusive in perpetuity"]
+//!
+//! [formats]
+//! audit = "html"
+//! batch = "junit"
+//!
+//! [[overrides]]
+//! path = "freelance"
+//! union = "iww"
+//! schema = "schemas/iww-freelancer-rights.a2ml"
+//! ```
+//!
+//! Paths are relative to the file. Overrides apply to contracts under their
+//! `path`, in file order. Command-line flags always win.
+
+use crate::error::{PolicyError, Result};
+use crate::signing::PublicKey;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Configuration file name
+pub const CONFIG_FILE: &str = "union-policy.toml";
+
+/// Settings that can be set at the top level or per directory
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize)]
+pub struct Settings {
+    /// Schema to validate against
+    pub schema: Option<PathBuf>,
+
+    /// Union whose rules apply (nuj, iww, ucu)
+    pub union: Option<String>,
+
+    /// Validation mode (lax, checked, attested)
+    pub mode: Option<String>,
+
+    /// Clauses required on top of the union's own
+    #[serde(default)]
+    pub required_clauses: Vec<String>,
+
+    /// Red-flag phrases scanned for on top of the union's own
+    #[serde(default)]
+    pub red_flags: Vec<String>,
+}
+
+impl Settings {
+    /// Layer `other` on top: its values replace ours, its lists extend ours
+    fn merge(&mut self, other: &Settings) {
+        if other.schema.is_some() {
+            self.schema = other.schema.clone();
+        }
+        if other.union.is_some() {
+            self.union = other.union.clone();
+        }
+        if other.mode.is_some() {
+            self.mode = other.mode.clone();
+        }
+        self.required_clauses.extend(other.required_clauses.iter().cloned());
+        self.red_flags.extend(other.red_flags.iter().cloned());
+    }
+}
+
+/// Default output format per command
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Formats {
+    pub audit: Option<String>,
+    pub batch: Option<String>,
+    pub render: Option<String>,
+}
+
+/// Settings for contracts under one directory
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Override {
+    /// Directory, relative to the configuration file
+    pub path: PathBuf,
+
+    #[serde(flatten)]
+    pub settings: Settings,
+
+    /// Keys not consumed above; any is a typo
+    #[serde(flatten)]
+    unknown: BTreeMap<String, toml::Value>,
+}
+
+/// Contents of a `union-policy.toml`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PolicyConfig {
+    #[serde(flatten)]
+    pub settings: Settings,
+
+    /// Audit log that every `audit` run appends to; unset, runs are only
+    /// logged with `--log`
+    pub audit_log: Option<PathBuf>,
+
+    /// Keys whose signatures `verify` and 'Hunt wrappers accept: a public
+    /// key (`ed25519 <base64>`) or a key file
+    #[serde(default)]
+    pub trusted_keys: Vec<String>,
+
+    /// Require a trusted signature on every K9 wrapper's payload, whatever
+    /// its trust level says
+    #[serde(default)]
+    pub require_signatures: bool,
+
+    #[serde(default)]
+    pub formats: Formats,
+
+    #[serde(default)]
+    pub overrides: Vec<Override>,
+
+    /// Keys not consumed above; any is a typo
+    #[serde(flatten)]
+    unknown: BTreeMap<String, toml::Value>,
+
+    /// Directory containing the file; relative paths resolve against it
+    #[serde(skip)]
+    pub root: PathBuf,
+}
+
+impl PolicyConfig {
+    /// Nearest `union-policy.toml` in `start` or one of its ancestors
+    pub fn discover(start: &Path) -> Result<Option<Self>> {
+        let start = fs::canonicalize(start)?;
+        for dir in start.ancestors() {
+            let candidate = dir.join(CONFIG_FILE);
+            if candidate.is_file() {
+                return Self::load(&candidate).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Load a configuration file
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = fs::read_to_string(path)?;
+        let mut config: PolicyConfig = toml::from_str(&source)
+            .map_err(|e| PolicyError::SchemaError(format!("{}: {}", path.display(), e)))?;
+
+        // `flatten` rules out deny_unknown_fields, so reject leftovers by hand
+        let unknown = config
+            .unknown
+            .keys()
+            .chain(config.overrides.iter().flat_map(|o| o.unknown.keys()))
+            .next();
+        if let Some(key) = unknown {
+            return Err(PolicyError::SchemaError(format!("{}: unknown key `{}`", path.display(), key)));
+        }
+
+        let path = fs::canonicalize(path)?;
+        config.root = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(config)
+    }
+
+    /// Audit log path, resolved against the configuration's directory (the
+    /// default file name there when `audit_log` is unset, for `log show`)
+    pub fn audit_log_path(&self) -> PathBuf {
+        self.root.join(self.audit_log.as_deref().unwrap_or(Path::new(crate::audit_log::DEFAULT_LOG_FILE)))
+    }
+
+    /// The trusted signer keys, reading key files relative to this file
+    pub fn trusted_keys(&self) -> Result<Vec<PublicKey>> {
+        self.trusted_keys
+            .iter()
+            .map(|key| {
+                let parsed = if key.trim_start().starts_with("ed25519 ") {
+                    PublicKey::from_text(key)
+                } else {
+                    PublicKey::load(&self.root.join(key))
+                };
+                parsed.map_err(|e| PolicyError::SchemaError(format!("{}: trusted key `{}`: {}", CONFIG_FILE, key, e)))
+            })
+            .collect()
+    }
+
+    /// Effective settings for a contract (or directory) at `target`
+    ///
+    /// Paths in the result are resolved against the configuration's directory.
+    pub fn settings_for(&self, target: &Path) -> Settings {
+        let target = fs::canonicalize(target).unwrap_or_else(|_| target.to_path_buf());
+
+        let mut settings = self.settings.clone();
+        for over in &self.overrides {
+            if target.starts_with(self.root.join(&over.path)) {
+                settings.merge(&over.settings);
+            }
+        }
+
+        settings.schema = settings.schema.map(|schema| self.root.join(schema));
+        settings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+schema = "schemas/nuj.a2ml"
+union = "nuj"
+required_clauses = ["kill-fee-provision"]
+red_flags = ["exclusive in perpetuity"]
+
+[formats]
+batch = "junit"
+
+[[overrides]]
+path = "freelance"
+union = "iww"
+schema = "schemas/iww.a2ml"
+red_flags = ["unpaid trial"]
+"#;
+
+    #[test]
+    fn test_discover_and_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = fs::canonicalize(dir.path()).unwrap();
+        fs::write(root.join(CONFIG_FILE), CONFIG).unwrap();
+        fs::create_dir_all(root.join("freelance/2025")).unwrap();
+        fs::create_dir_all(root.join("staff")).unwrap();
+
+        let config = PolicyConfig::discover(&root.join("freelance/2025")).unwrap().unwrap();
+        assert_eq!(config.root, root);
+        assert_eq!(config.formats.batch.as_deref(), Some("junit"));
+
+        let staff = config.settings_for(&root.join("staff"));
+        assert_eq!(staff.union.as_deref(), Some("nuj"));
+        assert_eq!(staff.schema, Some(root.join("schemas/nuj.a2ml")));
+
+        let freelance = config.settings_for(&root.join("freelance/2025"));
+        assert_eq!(freelance.union.as_deref(), Some("iww"));
+        assert_eq!(freelance.schema, Some(root.join("schemas/iww.a2ml")));
+        assert_eq!(freelance.required_clauses, ["kill-fee-provision"]);
+        assert_eq!(freelance.red_flags, ["exclusive in perpetuity", "unpaid trial"]);
+    }
+
+    #[test]
+    fn test_trusted_keys_inline_and_from_files() {
+        use crate::signing::SecretKey;
+        let dir = tempfile::tempdir().unwrap();
+        let (inline, file) = (SecretKey::from_seed([1; 32]).public_key(), SecretKey::from_seed([2; 32]).public_key());
+        fs::create_dir_all(dir.path().join("keys")).unwrap();
+        fs::write(dir.path().join("keys/union.pub"), format!("{}\n", file)).unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+        fs::write(&path, format!("trusted_keys = [\"{}\", \"keys/union.pub\"]\nrequire_signatures = true\n", inline)).unwrap();
+
+        let config = PolicyConfig::load(&path).unwrap();
+        assert!(config.require_signatures);
+        assert_eq!(config.trusted_keys().unwrap(), [inline, file]);
+
+        fs::write(&path, "trusted_keys = [\"keys/missing.pub\"]\n").unwrap();
+        assert!(PolicyConfig::load(&path).unwrap().trusted_keys().is_err());
+    }
+
+    #[test]
+    fn test_unknown_keys_are_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+        fs::write(&path, "unoin = \"nuj\"\n").unwrap();
+        assert!(PolicyConfig::load(&path).is_err());
+    }
+}
@@ -0,0 +1,329 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Lossless concrete syntax tree for A2ML
+//!
+//! The AST in [`crate::parser`] normalises whitespace and drops layout. The
+//! CST instead splits the source into a flat sequence of nodes whose text,
+//! concatenated, is the original input byte-for-byte: blank lines, trailing
+//! spaces, `\r\n` endings and `<!-- comments -->` included.
+//!
+//! Edits replace the text of individual nodes and re-lex the result, so every
+//! region that was not edited reprints exactly as it was read. Redlining and
+//! auto-fix tools work on this layer and hand the result back to the parser.
+
+use crate::error::Result;
+use crate::parser::{self, A2mlDocument};
+use std::fmt;
+use std::ops::Range;
+
+/// Kind of a CST node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeKind {
+    /// `#`..`######` heading line
+    Heading { level: u8 },
+    /// `@name:` through the matching `@end`
+    Directive { name: String },
+    Paragraph,
+    BulletList,
+    OrderedList,
+    Table,
+    CodeBlock,
+    HorizontalRule,
+    /// `<!-- ... -->` starting at the beginning of a line
+    Comment,
+    /// Blank lines and other whitespace between blocks
+    Whitespace,
+}
+
+/// A node of the syntax tree, owning its exact source text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    pub kind: NodeKind,
+
+    /// Exact source text, including the trailing line ending
+    pub text: String,
+
+    /// Byte offset of the node in the printed tree
+    pub offset: usize,
+
+    /// 1-based line where the node starts
+    pub line: usize,
+}
+
+impl Node {
+    /// Byte range of the node in the printed tree
+    pub fn span(&self) -> Range<usize> {
+        self.offset..self.offset + self.text.len()
+    }
+
+    /// Heading text without the `#` markers, for heading nodes
+    pub fn heading(&self) -> Option<&str> {
+        match self.kind {
+            NodeKind::Heading { .. } => Some(self.text.trim_start_matches('#').trim()),
+            _ => None,
+        }
+    }
+}
+
+/// Lossless syntax tree over A2ML source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxTree {
+    nodes: Vec<Node>,
+}
+
+impl SyntaxTree {
+    /// Split source into nodes. Never fails: anything unrecognised is kept as
+    /// a paragraph so that no byte is lost.
+    pub fn parse(source: &str) -> Self {
+        let lines: Vec<&str> = source.split_inclusive('\n').collect();
+        let mut nodes = Vec::new();
+        let mut i = 0;
+        let mut offset = 0;
+
+        while i < lines.len() {
+            let (kind, count) = classify(&lines[i..]);
+            let text: String = lines[i..i + count].concat();
+            nodes.push(Node {
+                kind,
+                offset,
+                line: i + 1,
+                text,
+            });
+            offset += nodes.last().map(|n| n.text.len()).unwrap_or(0);
+            i += count;
+        }
+
+        Self { nodes }
+    }
+
+    /// All nodes in source order
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Parse the current text into an AST
+    pub fn to_document(&self) -> Result<A2mlDocument> {
+        parser::parse_a2ml_string(&self.to_string())
+    }
+
+    /// Index of the first heading node whose text equals `heading`
+    pub fn find_heading(&self, heading: &str) -> Option<usize> {
+        self.nodes.iter().position(|n| n.heading() == Some(heading))
+    }
+
+    /// Node indices making up the body of the section headed at `index`:
+    /// everything up to the next heading of the same or a higher level, or
+    /// the next directive
+    pub fn section_body(&self, index: usize) -> Range<usize> {
+        let level = match self.nodes.get(index).map(|n| &n.kind) {
+            Some(NodeKind::Heading { level }) => *level,
+            _ => return index..index,
+        };
+        let end = self.nodes[index + 1..]
+            .iter()
+            .position(|n| match n.kind {
+                NodeKind::Heading { level: l } => l <= level,
+                NodeKind::Directive { .. } => true,
+                _ => false,
+            })
+            .map(|p| index + 1 + p)
+            .unwrap_or(self.nodes.len());
+        index + 1..end
+    }
+
+    /// Replace the text of one node
+    pub fn replace(&mut self, index: usize, text: &str) {
+        self.splice(index..index + 1, text);
+    }
+
+    /// Insert text before the node at `index` (or at the end)
+    pub fn insert(&mut self, index: usize, text: &str) {
+        self.splice(index..index, text);
+    }
+
+    /// Remove one node
+    pub fn remove(&mut self, index: usize) {
+        self.splice(index..index + 1, "");
+    }
+
+    /// Replace the body of a section, keeping its heading line and the
+    /// whitespace that separates it from the next section
+    pub fn replace_section_body(&mut self, heading: &str, body: &str) -> bool {
+        let Some(index) = self.find_heading(heading) else {
+            return false;
+        };
+        let mut range = self.section_body(index);
+        while range.end > range.start && self.nodes[range.end - 1].kind == NodeKind::Whitespace {
+            range.end -= 1;
+        }
+        self.splice(range, body);
+        true
+    }
+
+    /// Replace a range of nodes with new text and re-lex
+    ///
+    /// Nodes outside the range keep their text, so they print unchanged.
+    pub fn splice(&mut self, range: Range<usize>, text: &str) {
+        let mut source = String::new();
+        for node in &self.nodes[..range.start] {
+            source.push_str(&node.text);
+        }
+        source.push_str(text);
+        for node in &self.nodes[range.end..] {
+            source.push_str(&node.text);
+        }
+        *self = Self::parse(&source);
+    }
+}
+
+impl fmt::Display for SyntaxTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for node in &self.nodes {
+            f.write_str(&node.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl A2mlDocument {
+    /// Lossless syntax tree of the source this document was parsed from
+    pub fn syntax_tree(&self) -> SyntaxTree {
+        SyntaxTree::parse(&self.raw)
+    }
+}
+
+/// Classify the node starting at `lines[0]`; returns its kind and line count
+fn classify(lines: &[&str]) -> (NodeKind, usize) {
+    let first = lines[0];
+    let content = first.trim_end_matches(['\r', '\n']);
+
+    if content.trim().is_empty() {
+        let count = lines.iter().take_while(|l| l.trim().is_empty()).count();
+        return (NodeKind::Whitespace, count);
+    }
+
+    if content.starts_with("<!--") {
+        return (NodeKind::Comment, lines_until(lines, "-->", 4));
+    }
+
+    if parser::is_directive_line(content) {
+        let name: String = content[1..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+            .collect();
+        if name == "end" {
+            return (NodeKind::Directive { name }, 1);
+        }
+        return (NodeKind::Directive { name }, lines_until(lines, "@end", name_len(content)));
+    }
+
+    if let Ok((_, (level, _))) = parser::heading(&format!("{}\n", content)) {
+        return (NodeKind::Heading { level }, 1);
+    }
+
+    if content.starts_with("```") {
+        return (NodeKind::CodeBlock, lines_until(lines, "```", 3));
+    }
+
+    if content.starts_with("---") {
+        return (NodeKind::HorizontalRule, 1);
+    }
+
+    let run = |pred: &dyn Fn(&str) -> bool| lines.iter().take_while(|l| pred(l)).count();
+
+    if parser::bullet_marker(content).is_some() {
+        return (NodeKind::BulletList, run(&|l| parser::bullet_marker(l).is_some()));
+    }
+    if parser::ordered_marker(content).is_some() {
+        return (NodeKind::OrderedList, run(&|l| parser::ordered_marker(l).is_some()));
+    }
+    if content.starts_with('|') {
+        return (NodeKind::Table, run(&|l| l.starts_with('|')));
+    }
+
+    let count = 1 + lines[1..]
+        .iter()
+        .take_while(|l| {
+            let l = l.trim_end_matches(['\r', '\n']);
+            !l.trim().is_empty() && {
+                let (kind, _) = classify(&[l]);
+                kind == NodeKind::Paragraph
+            }
+        })
+        .count();
+    (NodeKind::Paragraph, count)
+}
+
+/// Byte length of `@name:` at the start of a directive line
+fn name_len(line: &str) -> usize {
+    line.find(':').map(|i| i + 1).unwrap_or(line.len())
+}
+
+/// Number of lines up to and including the one containing `terminator`,
+/// searching from byte `skip` of the first line (or all lines if unterminated)
+fn lines_until(lines: &[&str], terminator: &str, skip: usize) -> usize {
+    for (i, line) in lines.iter().enumerate() {
+        let haystack = if i == 0 { line.get(skip..).unwrap_or("") } else { line };
+        if haystack.contains(terminator) {
+            return i + 1;
+        }
+    }
+    lines.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTRACT: &str = "# Contract  \r\n\r\n<!-- drafting note:\r\n   remove before signing -->\r\n\
+        @abstract: A contract.\r\n@end\r\n\r\n\r\n## 1. Pay\r\n\r\nNET 60 days.\r\nNo penalty.   \r\n\
+        - one\r\n*   two\r\n\r\n## 2. Rights\r\n\r\nAll rights.\r\n\r\n@refs:\r\n[1]  Act\r\n@end";
+
+    #[test]
+    fn test_prints_source_byte_identically() {
+        let tree = SyntaxTree::parse(CONTRACT);
+        assert_eq!(tree.to_string(), CONTRACT);
+
+        for source in [
+            include_str!("../../../examples/freelance-journalist-bad.a2ml"),
+            include_str!("../../../schemas/nuj-code-of-ethics.a2ml"),
+            "",
+            "\n\n",
+            "text without newline",
+        ] {
+            assert_eq!(SyntaxTree::parse(source).to_string(), source);
+        }
+    }
+
+    #[test]
+    fn test_classifies_nodes() {
+        let tree = SyntaxTree::parse(CONTRACT);
+        let kinds: Vec<&NodeKind> = tree.nodes().iter().map(|n| &n.kind).collect();
+
+        assert_eq!(kinds[0], &NodeKind::Heading { level: 1 });
+        assert_eq!(kinds[2], &NodeKind::Comment);
+        assert_eq!(kinds[3], &NodeKind::Directive { name: "abstract".to_string() });
+        assert_eq!(tree.nodes()[5].heading(), Some("1. Pay"));
+        assert_eq!(tree.nodes()[5].line, 9);
+        assert!(kinds.contains(&&NodeKind::BulletList));
+    }
+
+    #[test]
+    fn test_edit_keeps_other_regions() {
+        let mut tree = SyntaxTree::parse(CONTRACT);
+        assert!(tree.replace_section_body("1. Pay", "\r\nNET 30 days.\r\n"));
+
+        let printed = tree.to_string();
+        let expected = CONTRACT.replace("\r\nNET 60 days.\r\nNo penalty.   \r\n- one\r\n*   two\r\n", "\r\nNET 30 days.\r\n");
+        assert_eq!(printed, expected);
+
+        let doc = tree.to_document().unwrap();
+        assert!(doc.sections[1].text().contains("NET 30"));
+        assert_eq!(doc.raw, printed);
+    }
+
+    #[test]
+    fn test_syntax_tree_from_document() {
+        let doc = parser::parse_a2ml_string(CONTRACT).unwrap();
+        assert_eq!(doc.syntax_tree().to_string(), CONTRACT);
+    }
+}
@@ -0,0 +1,408 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Clause-by-clause comparison of two contract versions
+//!
+//! Sections are aligned by heading first, ignoring any clause number, so a
+//! clause renumbered in a later round still lines up ("3. Kill Fee" with
+//! "4. Kill Fee"); sections left over are then aligned by clause number, so
+//! a renamed clause does too ("2. Payment" with "2. Payment Terms").
+//! Anything still unmatched was added or removed. The title, the
+//! `@abstract` and any text before the first heading (`@preamble`) are
+//! compared as clauses of their own.
+//!
+//! Bodies are compared word by word in their canonical layout (as `fmt`
+//! writes it), so reflowing text is not a change.
+
+use crate::formatter::{format_blocks, format_body};
+use crate::text::slugify;
+use crate::parser::A2mlDocument;
+
+/// Largest word grid compared exactly; bigger rewrites show as replaced
+const MAX_CELLS: usize = 4_000_000;
+
+/// How a clause changed between versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Change {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// Whether a run of words is in both versions or only one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Edit {
+    Same,
+    Removed,
+    Added,
+}
+
+/// A run of words; line breaks are kept as `\n`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Span {
+    pub edit: Edit,
+    pub text: String,
+}
+
+/// One clause that differs
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ClauseChange {
+    pub change: Change,
+
+    /// Clause number from the heading (e.g., "2.1"), if any
+    pub id: Option<String>,
+
+    /// Heading in the new version (the old one, for a removed clause)
+    pub heading: String,
+
+    /// Heading in the old version, when it was renamed
+    pub old_heading: Option<String>,
+
+    /// Line (1-based) of the heading, in the version `heading` is from
+    pub line: Option<usize>,
+
+    /// The body word by word; all added or all removed unless modified
+    pub words: Vec<Span>,
+}
+
+impl ClauseChange {
+    /// Lines with a change, marked up as `[-removed-]` and `{+added+}`
+    pub fn changed_lines(&self) -> Vec<String> {
+        let mut lines = vec![(String::new(), false)];
+        for span in &self.words {
+            let (open, close) = match span.edit {
+                Edit::Same => ("", ""),
+                Edit::Removed => ("[-", "-]"),
+                Edit::Added => ("{+", "+}"),
+            };
+            for (i, part) in span.text.split('\n').enumerate() {
+                if i > 0 {
+                    lines.push((String::new(), false));
+                }
+                if part.is_empty() {
+                    continue;
+                }
+                if let Some((line, changed)) = lines.last_mut() {
+                    if !line.is_empty() {
+                        line.push(' ');
+                    }
+                    line.push_str(&format!("{}{}{}", open, part, close));
+                    *changed |= span.edit != Edit::Same;
+                }
+            }
+        }
+        lines.into_iter().filter(|(_, changed)| *changed).map(|(line, _)| line).collect()
+    }
+}
+
+/// Differences between two versions of a contract
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ContractDiff {
+    /// Changed clauses, in the new version's order; removed ones where they were
+    pub clauses: Vec<ClauseChange>,
+
+    /// Clauses identical in both
+    pub unchanged: usize,
+}
+
+impl ContractDiff {
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+
+    pub fn count(&self, change: Change) -> usize {
+        self.clauses.iter().filter(|c| c.change == change).count()
+    }
+}
+
+struct Clause {
+    id: Option<String>,
+    /// Heading less any clause number, as a slug
+    name: String,
+    /// What clauses are aligned on: the name, or `@title`
+    key: String,
+    heading: String,
+    line: Option<usize>,
+    body: String,
+}
+
+fn clauses(doc: &A2mlDocument) -> Vec<Clause> {
+    let mut clauses = Vec::new();
+    if !doc.preamble.is_empty() {
+        clauses.push(Clause {
+            id: None,
+            name: "@preamble".to_string(),
+            key: "@preamble".to_string(),
+            heading: "@preamble".to_string(),
+            line: Some(1),
+            body: format_blocks(&doc.preamble),
+        });
+    }
+    if let Some(text) = &doc.abstract_text {
+        clauses.push(Clause {
+            id: None,
+            name: "@abstract".to_string(),
+            key: "@abstract".to_string(),
+            heading: "@abstract".to_string(),
+            line: None,
+            body: text.clone(),
+        });
+    }
+    for (i, section) in doc.sections.iter().enumerate() {
+        let (id, name) = heading_key(&section.heading);
+        // The title is compared with the title, whatever it says
+        let key = if i == 0 && section.level == 1 { "@title".to_string() } else { name.clone() };
+        clauses.push(Clause {
+            id,
+            name,
+            key,
+            heading: section.heading.clone(),
+            line: Some(section.line_number),
+            body: format_body(section),
+        });
+    }
+    clauses
+}
+
+/// "2.1. Payment Terms" -> (Some("2.1"), "payment-terms")
+fn heading_key(heading: &str) -> (Option<String>, String) {
+    let trimmed = heading.trim().trim_start_matches('§').trim_start();
+    let number: String = trimmed.chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect();
+    let id = number.trim_end_matches('.');
+    if !id.starts_with(|c: char| c.is_ascii_digit()) {
+        return (None, slugify(heading));
+    }
+    let rest = trimmed[number.len()..].trim_start_matches([')', ':']);
+    (Some(id.to_string()), slugify(rest))
+}
+
+/// Align the clauses of two versions and compare each pair
+pub fn diff_contracts(old: &A2mlDocument, new: &A2mlDocument) -> ContractDiff {
+    let old = clauses(old);
+    let new = clauses(new);
+
+    // For each new clause, the old clause it continues
+    let mut matched: Vec<Option<usize>> = vec![None; new.len()];
+    let mut taken = vec![false; old.len()];
+    let mut align = |same: &dyn Fn(&Clause, &Clause) -> bool, matched: &mut Vec<Option<usize>>| {
+        for (n, clause) in new.iter().enumerate() {
+            if matched[n].is_some() {
+                continue;
+            }
+            if let Some(o) = (0..old.len()).find(|&o| !taken[o] && same(&old[o], clause)) {
+                taken[o] = true;
+                matched[n] = Some(o);
+            }
+        }
+    };
+    align(&|a, b| a.key == b.key, &mut matched);
+    align(&|a, b| a.id.is_some() && a.id == b.id, &mut matched);
+
+    let mut diff = ContractDiff::default();
+    let mut reported = vec![false; old.len()];
+    let removed = |o: usize| ClauseChange {
+        change: Change::Removed,
+        id: old[o].id.clone(),
+        heading: old[o].heading.clone(),
+        old_heading: None,
+        line: old[o].line,
+        words: spans(&[(Edit::Removed, tokens(&old[o].body))]),
+    };
+
+    for (n, clause) in new.iter().enumerate() {
+        let Some(o) = matched[n] else {
+            diff.clauses.push(ClauseChange {
+                change: Change::Added,
+                id: clause.id.clone(),
+                heading: clause.heading.clone(),
+                old_heading: None,
+                line: clause.line,
+                words: spans(&[(Edit::Added, tokens(&clause.body))]),
+            });
+            continue;
+        };
+
+        // Removed clauses go before the first survivor that followed them
+        for earlier in 0..o {
+            if !taken[earlier] && !reported[earlier] {
+                reported[earlier] = true;
+                diff.clauses.push(removed(earlier));
+            }
+        }
+
+        let before = tokens(&old[o].body);
+        let after = tokens(&clause.body);
+        // Renumbering alone is not a change
+        let renamed = old[o].name != clause.name;
+        if before == after && !renamed {
+            diff.unchanged += 1;
+            continue;
+        }
+        diff.clauses.push(ClauseChange {
+            change: Change::Modified,
+            id: clause.id.clone().or_else(|| old[o].id.clone()),
+            heading: clause.heading.clone(),
+            old_heading: renamed.then(|| old[o].heading.clone()),
+            line: clause.line,
+            words: spans(&diff_words(&before, &after)),
+        });
+    }
+    for o in 0..old.len() {
+        if !taken[o] && !reported[o] {
+            diff.clauses.push(removed(o));
+        }
+    }
+    diff
+}
+
+/// Words of a body, with each line break as a `\n` token
+fn tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            tokens.push("\n");
+        }
+        tokens.extend(line.split_whitespace());
+    }
+    tokens
+}
+
+/// Longest-common-subsequence diff of two word lists
+fn diff_words<'a>(before: &[&'a str], after: &[&'a str]) -> Vec<(Edit, Vec<&'a str>)> {
+    let prefix = before.iter().zip(after).take_while(|(a, b)| a == b).count();
+    let suffix = before[prefix..]
+        .iter()
+        .rev()
+        .zip(after[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old = &before[prefix..before.len() - suffix];
+    let new = &after[prefix..after.len() - suffix];
+
+    let mut edits = vec![(Edit::Same, before[..prefix].to_vec())];
+    if old.len() * new.len() > MAX_CELLS {
+        edits.push((Edit::Removed, old.to_vec()));
+        edits.push((Edit::Added, new.to_vec()));
+    } else {
+        // lcs[i][j]: common words of old[i..] and new[j..]
+        let mut lcs = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lcs[i][j] = if old[i] == new[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() && old[i] == new[j] {
+                edits.push((Edit::Same, vec![old[i]]));
+                i += 1;
+                j += 1;
+            } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+                edits.push((Edit::Removed, vec![old[i]]));
+                i += 1;
+            } else {
+                edits.push((Edit::Added, vec![new[j]]));
+                j += 1;
+            }
+        }
+    }
+    edits.push((Edit::Same, before[before.len() - suffix..].to_vec()));
+    edits
+}
+
+/// Merge runs of the same edit into spans of text
+fn spans(edits: &[(Edit, Vec<&str>)]) -> Vec<Span> {
+    let mut spans: Vec<(Edit, Vec<&str>)> = Vec::new();
+    for (edit, words) in edits.iter().filter(|(_, words)| !words.is_empty()) {
+        match spans.last_mut() {
+            Some((last, run)) if last == edit => run.extend(words),
+            _ => spans.push((*edit, words.clone())),
+        }
+    }
+    spans.into_iter().map(|(edit, words)| Span { edit, text: join(&words) }).collect()
+}
+
+fn join(words: &[&str]) -> String {
+    let mut text = String::new();
+    for word in words {
+        if *word != "\n" && !text.is_empty() && !text.ends_with('\n') {
+            text.push(' ');
+        }
+        text.push_str(word);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aligns_renumbered_and_renamed_clauses() {
+        let old = crate::parse(
+            "# Commission\n\n## 1. Rights\n\nFirst British serial rights only.\n\n\
+             ## 2. Payment\n\nNET 30 from submission.\n\n## 3. Kill Fee\n\n**Kill Fee:** 50%\n",
+        )
+        .unwrap();
+        let new = crate::parse(
+            "# Commission (revised)\n\n## 1. Rights\n\nAll rights, in perpetuity.\n\n\
+             ## 2. Payment Terms\n\nNET 30 from submission.\n\n## 3. Expenses\n\nNone.\n\n\
+             ## 4. Kill Fee\n\n**Kill Fee:** 50%\n",
+        )
+        .unwrap();
+
+        let diff = diff_contracts(&old, &new);
+        let summary: Vec<(Change, &str)> = diff.clauses.iter().map(|c| (c.change, c.heading.as_str())).collect();
+        assert_eq!(
+            summary,
+            [
+                (Change::Modified, "Commission (revised)"),
+                (Change::Modified, "1. Rights"),
+                (Change::Modified, "2. Payment Terms"),
+                (Change::Added, "3. Expenses"),
+            ]
+        );
+        assert_eq!(diff.clauses[2].old_heading.as_deref(), Some("2. Payment"));
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.clauses[1].changed_lines(), ["[-First British serial rights only.-] {+All rights, in perpetuity.+}"]);
+    }
+
+    #[test]
+    fn test_word_diff_keeps_unchanged_words() {
+        let old = crate::parse("# T\n\n## Payment\n\nPayment due NET 30 days from submission.\n\nNo penalties.\n").unwrap();
+        let new = crate::parse("# T\n\n## Payment\n\nPayment due NET 90 days from publication.\n\nNo penalties.\n").unwrap();
+
+        let diff = diff_contracts(&old, &new);
+        assert_eq!(diff.clauses.len(), 1);
+        let edits: Vec<(Edit, &str)> = diff.clauses[0].words.iter().map(|s| (s.edit, s.text.as_str())).collect();
+        assert_eq!(
+            edits,
+            [
+                (Edit::Same, "Payment due NET"),
+                (Edit::Removed, "30"),
+                (Edit::Added, "90"),
+                (Edit::Same, "days from"),
+                (Edit::Removed, "submission."),
+                (Edit::Added, "publication."),
+                (Edit::Same, "\n\nNo penalties."),
+            ]
+        );
+        assert_eq!(diff.clauses[0].changed_lines(), ["Payment due NET [-30-] {+90+} days from [-submission.-] {+publication.+}"]);
+        assert!(diff_contracts(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_preamble_and_list_numbering_are_compared() {
+        let old = crate::parse("Grants first rights.\n\n# T\n\n## Duties\n\n1. File copy.\n2. Attend briefings.\n").unwrap();
+        let new = crate::parse(
+            "Grants all rights in perpetuity.\n\n# T\n\n## Duties\n\n1. File copy.\n3. Attend briefings.\n",
+        )
+        .unwrap();
+
+        let diff = diff_contracts(&old, &new);
+        let headings: Vec<&str> = diff.clauses.iter().map(|c| c.heading.as_str()).collect();
+        assert_eq!(headings, ["@preamble", "Duties"]);
+        assert_eq!(diff.clauses[0].changed_lines(), ["Grants [-first rights.-] {+all rights in perpetuity.+}"]);
+        assert_eq!(diff.clauses[1].changed_lines(), ["[-2.-] {+3.+} Attend briefings."]);
+    }
+}
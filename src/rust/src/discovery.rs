@@ -0,0 +1,303 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Contract discovery for batch runs
+//!
+//! Walks a directory with the `ignore` crate, so `.gitignore` and
+//! `.uppignore` files (same syntax) keep drafts and archived contracts out of
+//! compliance stats. Symlinks are followed, but loops are detected and
+//! skipped. Optionally, K9 wrappers (`*.k9.ncl`) contribute the A2ML payload
+//! they point at, validated with the wrapper's settings. A payload is held to
+//! the same rules as a file found directly: it must lie under the root and
+//! not be ignored or excluded.
+
+use crate::error::{PolicyError, Result};
+use crate::k9::{is_k9, K9Wrapper};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::gitignore::GitignoreBuilder;
+use ignore::{Match, WalkBuilder};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Per-directory ignore file, in `.gitignore` syntax
+pub const IGNORE_FILE: &str = ".uppignore";
+
+/// Which files a batch run picks up
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveryOptions {
+    /// Globs a file must match (default: `*.a2ml`)
+    pub include: Vec<String>,
+
+    /// Globs that exclude files and prune directories
+    pub exclude: Vec<String>,
+
+    /// Also validate the payloads of `*.k9.ncl` wrappers
+    pub k9: bool,
+}
+
+/// Contracts to validate, and the K9 wrappers some were reached through
+#[derive(Debug, Default)]
+pub struct Discovered {
+    /// Contract files, sorted and without duplicates
+    pub files: Vec<PathBuf>,
+
+    /// Wrapper for each payload in `files` that has one
+    pub wrappers: BTreeMap<PathBuf, K9Wrapper>,
+}
+
+impl Discovered {
+    /// Files as given (a list on stdin), with K9 wrappers replaced by their payloads
+    pub fn from_list(paths: Vec<PathBuf>) -> Result<Self> {
+        let mut discovered = Self::default();
+        for path in paths {
+            if is_k9(&path) {
+                let wrapper = K9Wrapper::load(&path)?;
+                let payload = normalize(&wrapper.payload);
+                discovered.files.push(payload.clone());
+                discovered.wrappers.insert(payload, wrapper);
+            } else {
+                discovered.files.push(path);
+            }
+        }
+        Ok(discovered)
+    }
+}
+
+/// Find contracts under `root`
+pub fn discover(root: &Path, options: &DiscoveryOptions) -> Result<Discovered> {
+    let include = if options.include.is_empty() {
+        glob_set(&["*.a2ml".to_string()])?
+    } else {
+        glob_set(&options.include)?
+    };
+    let exclude = glob_set(&options.exclude)?;
+
+    let mut walker = WalkBuilder::new(root);
+    walker
+        .follow_links(true)
+        .require_git(false)
+        .add_custom_ignore_filename(IGNORE_FILE);
+
+    let prune_root = root.to_path_buf();
+    let prune = exclude.clone();
+    walker.filter_entry(move |entry| !matches(&prune, &prune_root, entry.path()));
+
+    let mut found = BTreeSet::new();
+    let mut wrappers = BTreeMap::new();
+    for entry in walker.build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                // Symlink loops and unreadable directories are skipped, not fatal
+                log::warn!("Skipping: {}", e);
+                continue;
+            }
+        };
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+
+        let path = entry.path();
+        if options.k9 && is_k9(path) {
+            match K9Wrapper::load(path) {
+                Ok(wrapper) => {
+                    let payload = normalize(&wrapper.payload);
+                    if !within(root, &payload) {
+                        log::warn!("Skipping K9 payload {:?} outside {:?}", payload, root);
+                    } else if ignored(root, &payload) || matches(&exclude, root, &payload) {
+                        log::debug!("Skipping ignored K9 payload {:?}", payload);
+                    } else {
+                        found.insert(payload.clone());
+                        wrappers.insert(payload, wrapper);
+                    }
+                }
+                Err(e) => log::warn!("Skipping K9 wrapper {:?}: {}", path, e),
+            }
+        } else if matches(&include, root, path) {
+            found.insert(normalize(path));
+        }
+    }
+
+    Ok(Discovered {
+        files: found.into_iter().collect(),
+        wrappers,
+    })
+}
+
+fn glob_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern)
+            .map_err(|e| PolicyError::ValidationError(format!("Invalid glob '{}': {}", pattern, e)))?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map_err(|e| PolicyError::ValidationError(e.to_string()))
+}
+
+/// Match a glob against the path relative to `root`, or against the file
+/// name alone so that `*.a2ml` matches at any depth
+fn matches(set: &GlobSet, root: &Path, path: &Path) -> bool {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    set.is_match(relative) || path.file_name().is_some_and(|name| set.is_match(name))
+}
+
+/// Whether `path` lies under `root`, compared lexically like the walk itself
+fn within(root: &Path, path: &Path) -> bool {
+    path.strip_prefix(normalize(root))
+        .is_ok_and(|relative| !relative.components().any(|c| c == std::path::Component::ParentDir))
+}
+
+/// Whether the `.gitignore` and `.uppignore` files between `root` and `path`
+/// ignore it; as in the walk, the deepest file that mentions it decides
+fn ignored(root: &Path, path: &Path) -> bool {
+    let root = normalize(root);
+    let mut dirs: Vec<&Path> = path.ancestors().skip(1).take_while(|dir| dir.starts_with(&root)).collect();
+    dirs.reverse();
+
+    let mut verdict = false;
+    for dir in dirs {
+        for name in [".gitignore", IGNORE_FILE] {
+            let file = dir.join(name);
+            if !file.is_file() {
+                continue;
+            }
+            let mut builder = GitignoreBuilder::new(dir);
+            if let Some(e) = builder.add(&file) {
+                log::warn!("Reading {:?}: {}", file, e);
+            }
+            let rules = match builder.build() {
+                Ok(rules) => rules,
+                Err(e) => {
+                    log::warn!("Reading {:?}: {}", file, e);
+                    continue;
+                }
+            };
+            match rules.matched_path_or_any_parents(path, false) {
+                Match::Ignore(_) => verdict = true,
+                Match::Whitelist(_) => verdict = false,
+                Match::None => {}
+            }
+        }
+    }
+    verdict
+}
+
+/// Resolve `.` and `..` components lexically, so a payload reached through
+/// a wrapper and found directly is only validated once
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir
+                if normalized.file_name().is_some_and(|name| name != "..") =>
+            {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn names(root: &Path, files: &[PathBuf]) -> Vec<String> {
+        files
+            .iter()
+            .map(|f| f.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"))
+            .collect()
+    }
+
+    #[test]
+    fn test_ignore_files_and_globs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(&root.join("a.a2ml"), "");
+        write(&root.join("notes.md"), "");
+        write(&root.join("nested/b.a2ml"), "");
+        write(&root.join("drafts/c.a2ml"), "");
+        write(&root.join("archive/2019/d.a2ml"), "");
+        write(&root.join("scratch/e.a2ml"), "");
+        write(&root.join(".gitignore"), "drafts/\n");
+        write(&root.join(IGNORE_FILE), "archive/\n");
+
+        let files = discover(root, &DiscoveryOptions::default()).unwrap().files;
+        assert_eq!(names(root, &files), ["a.a2ml", "nested/b.a2ml", "scratch/e.a2ml"]);
+
+        let options = DiscoveryOptions {
+            include: vec!["nested/*".to_string(), "*.md".to_string()],
+            exclude: vec!["scratch".to_string()],
+            k9: false,
+        };
+        let files = discover(root, &options).unwrap().files;
+        assert_eq!(names(root, &files), ["nested/b.a2ml", "notes.md"]);
+    }
+
+    #[test]
+    fn test_k9_payloads() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(&root.join("contracts/x.a2ml"), "");
+        write(&root.join("k9/z.a2ml"), "");
+        write(
+            &root.join("k9/x.k9.ncl"),
+            "{\n  pedigree = { name = \"x\" },\n  payload_file = \"../contracts/x.a2ml\",\n}\n",
+        );
+        write(&root.join("k9/y.k9.ncl"), "{\n  payload_file = \"../contracts/missing.a2ml\",\n}\n");
+
+        let options = DiscoveryOptions {
+            k9: true,
+            ..Default::default()
+        };
+        let discovered = discover(root, &options).unwrap();
+        assert_eq!(names(root, &discovered.files), ["contracts/missing.a2ml", "contracts/x.a2ml", "k9/z.a2ml"]);
+        let wrapper = &discovered.wrappers[&root.join("contracts/x.a2ml")];
+        assert_eq!(wrapper.name.as_deref(), Some("x"));
+    }
+
+    #[test]
+    fn test_k9_payloads_follow_ignore_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("repo");
+        write(&root.join("drafts/x.a2ml"), "");
+        write(&root.join("archive/y.a2ml"), "");
+        write(&dir.path().join("elsewhere.a2ml"), "");
+        write(&root.join(".gitignore"), "drafts/\n");
+        write(&root.join("archive").join(IGNORE_FILE), "*.a2ml\n");
+        for (name, payload) in [("x", "../drafts/x.a2ml"), ("y", "../archive/y.a2ml"), ("z", "../../elsewhere.a2ml")] {
+            write(
+                &root.join(format!("k9/{}.k9.ncl", name)),
+                &format!("{{\n  payload_file = \"{}\",\n}}\n", payload),
+            );
+        }
+
+        let options = DiscoveryOptions {
+            k9: true,
+            ..Default::default()
+        };
+        let discovered = discover(&root, &options).unwrap();
+        assert!(discovered.files.is_empty(), "{:?}", discovered.files);
+        assert!(discovered.wrappers.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_loop_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(&root.join("sub/a.a2ml"), "");
+        std::os::unix::fs::symlink(root, root.join("sub/loop")).unwrap();
+
+        let files = discover(root, &DiscoveryOptions::default()).unwrap().files;
+        assert_eq!(names(root, &files), ["sub/a.a2ml"]);
+    }
+}
@@ -4,6 +4,7 @@
 use thiserror::Error;
 use std::path::PathBuf;
 
+/// Everything that can go wrong parsing, validating or rendering
 #[derive(Error, Debug)]
 pub enum PolicyError {
     #[error("Failed to parse A2ML file: {0}")]
@@ -39,6 +40,10 @@ pub enum PolicyError {
 
     #[error("Unknown union: {0}")]
     UnknownUnion(String),
+
+    #[error("Signature error: {0}")]
+    SignatureError(String),
 }
 
+/// Result of library operations
 pub type Result<T> = std::result::Result<T, PolicyError>;
@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Process exit codes
+//!
+//! Scripts and K9 recipes branch on these, so they are part of the interface:
+//!
+//! | Code | Meaning                                     |
+//! |------|---------------------------------------------|
+//! | 0    | Clean (or nothing at the `--fail-on` level) |
+//! | 1    | MUST violations, including red flags        |
+//! | 2    | SHOULD-level findings only                  |
+//! | 3    | A contract or schema could not be parsed    |
+//! | 4    | Usage or IO error                           |
+//!
+//! When a batch has several kinds of problem the most serious wins: parse
+//! errors, then MUST violations, then SHOULD findings.
+
+use crate::batch::{BatchEntry, BatchOutcome};
+use crate::error::PolicyError;
+use crate::validator::{Finding, Severity, ValidationReport};
+
+/// Exit code for bad arguments, unreadable files and other IO failures
+pub const USAGE: u8 = 4;
+
+/// Exit code for input that is not valid A2ML
+pub const PARSE_ERROR: u8 = 3;
+
+/// Which findings make the process fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum FailOn {
+    /// MUST violations and parse errors
+    Error,
+    /// SHOULD findings as well
+    Warning,
+    /// Never fail because of findings; errors that stop the command still do
+    Never,
+}
+
+/// What a command found, ordered from least to most serious
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Status {
+    Clean,
+    Warnings,
+    Violations,
+    ParseErrors,
+}
+
+impl Status {
+    /// Status of one validation report
+    pub fn of_report(report: &ValidationReport) -> Self {
+        let worst = report.findings().iter().map(|f| f.severity).min();
+        match worst {
+            _ if !report.valid => Status::Violations,
+            Some(Severity::Must) => Status::Violations,
+            Some(Severity::Should) => Status::Warnings,
+            _ => Status::Clean,
+        }
+    }
+
+    /// Status of findings alone, such as those a new version introduced
+    pub fn of_findings(findings: &[Finding]) -> Self {
+        match findings.iter().map(|f| f.severity).min() {
+            Some(Severity::Must) => Status::Violations,
+            Some(Severity::Should) => Status::Warnings,
+            _ => Status::Clean,
+        }
+    }
+
+    /// Most serious status across a batch
+    pub fn of_batch(entries: &[BatchEntry]) -> Self {
+        entries
+            .iter()
+            .map(|entry| match &entry.outcome {
+                BatchOutcome::Validated(report) => Status::of_report(report),
+                BatchOutcome::ParseError(_) => Status::ParseErrors,
+            })
+            .max()
+            .unwrap_or(Status::Clean)
+    }
+
+    /// Name used in machine-readable output
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Status::Clean => "clean",
+            Status::Warnings => "warnings",
+            Status::Violations => "violations",
+            Status::ParseErrors => "parse-errors",
+        }
+    }
+
+    /// Exit code for this status under `fail_on`
+    pub fn code(self, fail_on: FailOn) -> u8 {
+        match (self, fail_on) {
+            (_, FailOn::Never) | (Status::Clean, _) => 0,
+            (Status::Warnings, FailOn::Error) => 0,
+            (Status::Warnings, FailOn::Warning) => 2,
+            (Status::Violations, _) => 1,
+            (Status::ParseErrors, _) => PARSE_ERROR,
+        }
+    }
+}
+
+/// Exit code for an error that stopped a command
+pub fn error_code(error: &anyhow::Error) -> u8 {
+    let parse_error = error
+        .chain()
+        .any(|cause| matches!(cause.downcast_ref::<PolicyError>(), Some(PolicyError::ParseError(_))));
+    if parse_error {
+        PARSE_ERROR
+    } else {
+        USAGE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator::ClauseCheck;
+
+    fn report(severity: Severity) -> ValidationReport {
+        let mut report = ValidationReport::new("c.a2ml".to_string(), "s.a2ml".to_string());
+        report.add_clause_check(ClauseCheck {
+            clause: "kill-fee".to_string(),
+            present: false,
+            value: None,
+            expected: None,
+            section: None,
+            severity,
+            help: None,
+        });
+        report
+    }
+
+    #[test]
+    fn test_codes_follow_fail_on() {
+        let should = Status::of_report(&report(Severity::Should));
+        let must = Status::of_report(&report(Severity::Must));
+        assert_eq!(should, Status::Warnings);
+        assert_eq!(must, Status::Violations);
+
+        assert_eq!(should.code(FailOn::Error), 0);
+        assert_eq!(should.code(FailOn::Warning), 2);
+        assert_eq!(must.code(FailOn::Error), 1);
+        assert_eq!(must.code(FailOn::Never), 0);
+        assert_eq!(Status::ParseErrors.code(FailOn::Warning), 3);
+
+        let batch = vec![
+            BatchEntry { path: "a.a2ml".into(), outcome: BatchOutcome::Validated(Box::new(report(Severity::Must))) },
+            BatchEntry { path: "b.a2ml".into(), outcome: BatchOutcome::ParseError("bad".to_string()) },
+        ];
+        assert_eq!(Status::of_batch(&batch), Status::ParseErrors);
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let parse = anyhow::Error::new(PolicyError::ParseError("line 3".to_string())).context("contract.a2ml");
+        assert_eq!(error_code(&parse), PARSE_ERROR);
+        assert_eq!(error_code(&anyhow::anyhow!("No schema given")), USAGE);
+    }
+}
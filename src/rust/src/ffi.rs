@@ -0,0 +1,311 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! C ABI (`ffi` feature)
+//!
+//! Exposes the engine to C and anything that can call C (the Zig and Idris2
+//! layers under `ffi/` and `src/abi/`). A handle holds a parsed contract and
+//! the last report; functions return the result codes of the Zig template,
+//! and the message behind the last failure on the calling thread is kept for
+//! `union_policy_parser_last_error`.
+//!
+//! The header is generated by cbindgen when building with `--features ffi`
+//! and written to `include/union_policy_parser.h`.
+
+use crate::error::PolicyError;
+use crate::parser::A2mlDocument;
+use crate::reporter::ReportRenderer;
+use crate::schemas::Union;
+use crate::validator::{ValidationMode, ValidationReport, Validator};
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Result codes, as in `ffi/zig/src/main.zig`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnionPolicyParserResult {
+    Ok = 0,
+    Error = 1,
+    InvalidParam = 2,
+    OutOfMemory = 3,
+    NullPointer = 4,
+}
+
+/// Parse syntax only
+pub const UNION_POLICY_PARSER_MODE_LAX: c_int = 0;
+/// Validate structure (required fields)
+pub const UNION_POLICY_PARSER_MODE_CHECKED: c_int = 1;
+/// Verify legal compliance
+pub const UNION_POLICY_PARSER_MODE_ATTESTED: c_int = 2;
+
+/// Opaque handle: a parsed contract and the last report
+pub struct UnionPolicyParserHandle {
+    contract: Option<A2mlDocument>,
+    report: Option<ValidationReport>,
+}
+
+use UnionPolicyParserResult as Code;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Record the message for `union_policy_parser_last_error` and pass the code on
+fn fail(code: Code, message: impl Into<String>) -> Code {
+    // Interior NULs would truncate the message; drop them
+    let message = message.into().replace('\0', "");
+    LAST_ERROR.with(|last| *last.borrow_mut() = CString::new(message).ok());
+    code
+}
+
+fn clear_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+}
+
+/// Run `f`, turning a panic into `Error` rather than unwinding into C
+fn guard(f: impl FnOnce() -> Code) -> Code {
+    clear_error();
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| fail(Code::Error, "internal error (panic)"))
+}
+
+/// UTF-8 text of a caller's buffer
+///
+/// # Safety
+/// `buffer` must be valid for `len` bytes.
+unsafe fn text<'a>(buffer: *const u8, len: usize) -> Result<&'a str, Code> {
+    if buffer.is_null() {
+        return Err(fail(Code::NullPointer, "buffer is null"));
+    }
+    let bytes = std::slice::from_raw_parts(buffer, len);
+    std::str::from_utf8(bytes).map_err(|e| fail(Code::InvalidParam, format!("buffer is not UTF-8: {}", e)))
+}
+
+fn parse(source: &str) -> Result<A2mlDocument, Code> {
+    crate::parse(source).map_err(|e| fail(Code::Error, e.to_string()))
+}
+
+/// Create a handle; free it with `union_policy_parser_free`
+#[no_mangle]
+pub extern "C" fn union_policy_parser_init() -> *mut UnionPolicyParserHandle {
+    Box::into_raw(Box::new(UnionPolicyParserHandle { contract: None, report: None }))
+}
+
+/// Free a handle; null is ignored
+///
+/// # Safety
+/// `handle` must come from `union_policy_parser_init` and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn union_policy_parser_free(handle: *mut UnionPolicyParserHandle) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+/// Parse an A2ML contract from `len` bytes of UTF-8; replaces any earlier
+/// contract and clears the last report
+///
+/// # Safety
+/// `handle` must be a live handle and `buffer` valid for `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn union_policy_parser_parse(
+    handle: *mut UnionPolicyParserHandle,
+    buffer: *const u8,
+    len: usize,
+) -> UnionPolicyParserResult {
+    guard(|| {
+        let Some(handle) = handle.as_mut() else {
+            return fail(Code::NullPointer, "handle is null");
+        };
+        let contract = match text(buffer, len).and_then(parse) {
+            Ok(contract) => contract,
+            Err(code) => return code,
+        };
+        handle.contract = Some(contract);
+        handle.report = None;
+        Code::Ok
+    })
+}
+
+/// Validate the parsed contract against an A2ML schema (`schema_len` bytes of
+/// UTF-8) in `mode`; `union_name` ("nuj", "iww", "ucu") may be null. With
+/// a union, a null `schema` means the union's built-in schema. The report is
+/// kept on the handle, and `valid` (if not null) is set to 1 or 0.
+///
+/// # Safety
+/// `handle` must be a live handle, `schema` null or valid for `schema_len`
+/// bytes, `union_name` null or a NUL-terminated string, and `valid` null or
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn union_policy_parser_validate(
+    handle: *mut UnionPolicyParserHandle,
+    schema: *const u8,
+    schema_len: usize,
+    union_name: *const c_char,
+    mode: c_int,
+    valid: *mut c_int,
+) -> UnionPolicyParserResult {
+    guard(|| {
+        let Some(handle) = handle.as_mut() else {
+            return fail(Code::NullPointer, "handle is null");
+        };
+        let Some(contract) = &handle.contract else {
+            return fail(Code::Error, "no contract parsed");
+        };
+        let mode = match mode {
+            UNION_POLICY_PARSER_MODE_LAX => ValidationMode::Lax,
+            UNION_POLICY_PARSER_MODE_CHECKED => ValidationMode::Checked,
+            UNION_POLICY_PARSER_MODE_ATTESTED => ValidationMode::Attested,
+            other => return fail(Code::InvalidParam, format!("unknown mode {}", other)),
+        };
+        let union = if union_name.is_null() {
+            None
+        } else {
+            let parsed = CStr::from_ptr(union_name)
+                .to_str()
+                .map_err(|_| PolicyError::UnknownUnion("(not UTF-8)".to_string()))
+                .and_then(str::parse::<Union>);
+            match parsed {
+                Ok(union) => Some(union),
+                Err(e) => return fail(Code::InvalidParam, e.to_string()),
+            }
+        };
+        let schema = match (schema.is_null(), union) {
+            (true, Some(union)) => union.embedded_schema().parse().map_err(|e| fail(Code::Error, e.to_string())),
+            _ => text(schema, schema_len).and_then(parse),
+        };
+        let schema = match schema {
+            Ok(schema) => schema,
+            Err(code) => return code,
+        };
+
+        let mut validator = Validator::new(schema, mode);
+        let mut required_clauses = Vec::new();
+        if let Some(union) = union {
+            validator = validator.with_union(union);
+            required_clauses.extend(union.required_clauses().iter().map(|s| s.to_string()));
+        }
+        let report = validator.validate(contract, &required_clauses);
+
+        if let Some(valid) = valid.as_mut() {
+            *valid = report.valid as c_int;
+        }
+        handle.report = Some(report);
+        Code::Ok
+    })
+}
+
+/// The last report as JSON; null on failure. Free with
+/// `union_policy_parser_free_string`.
+///
+/// # Safety
+/// `handle` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn union_policy_parser_render_json(handle: *const UnionPolicyParserHandle) -> *mut c_char {
+    let mut json = std::ptr::null_mut();
+    guard(|| {
+        let Some(handle) = handle.as_ref() else {
+            return fail(Code::NullPointer, "handle is null");
+        };
+        let Some(report) = &handle.report else {
+            return fail(Code::Error, "no report; call union_policy_parser_validate first");
+        };
+        match ReportRenderer::render_json(report).map(CString::new) {
+            Ok(Ok(rendered)) => {
+                json = rendered.into_raw();
+                Code::Ok
+            }
+            Ok(Err(e)) => fail(Code::Error, e.to_string()),
+            Err(e) => fail(Code::Error, e.to_string()),
+        }
+    });
+    json
+}
+
+/// Free a string returned by this library; null is ignored
+///
+/// # Safety
+/// `string` must come from this library and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn union_policy_parser_free_string(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Message for the last failure on this thread, or null. Owned by the
+/// library and valid until the next call on this thread; do not free.
+#[no_mangle]
+pub extern "C" fn union_policy_parser_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(std::ptr::null(), |message| message.as_ptr()))
+}
+
+/// Library version (static string; do not free)
+#[no_mangle]
+pub extern "C" fn union_policy_parser_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = "# Schema\n\n## Source Protection\n\n**Attestation:** *Must* protect sources.\n";
+    const CONTRACT: &str = "# Commission\n\n## Payment\n\nAll rights assigned.\n";
+
+    fn last_error() -> String {
+        let message = union_policy_parser_last_error();
+        assert!(!message.is_null());
+        unsafe { CStr::from_ptr(message) }.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_parse_validate_render() {
+        unsafe {
+            let handle = union_policy_parser_init();
+            assert_eq!(union_policy_parser_parse(handle, CONTRACT.as_ptr(), CONTRACT.len()), Code::Ok);
+
+            let mut valid = -1;
+            let code = union_policy_parser_validate(
+                handle,
+                SCHEMA.as_ptr(),
+                SCHEMA.len(),
+                c"nuj".as_ptr(),
+                UNION_POLICY_PARSER_MODE_CHECKED,
+                &mut valid,
+            );
+            assert_eq!(code, Code::Ok);
+            assert_eq!(valid, 0);
+            assert!(union_policy_parser_last_error().is_null());
+
+            let code = union_policy_parser_validate(handle, std::ptr::null(), 0, c"nuj".as_ptr(), UNION_POLICY_PARSER_MODE_LAX, &mut valid);
+            assert_eq!(code, Code::Ok);
+
+            let json = union_policy_parser_render_json(handle);
+            let report: serde_json::Value = serde_json::from_str(CStr::from_ptr(json).to_str().unwrap()).unwrap();
+            assert_eq!(report["valid"], false);
+            union_policy_parser_free_string(json);
+            union_policy_parser_free(handle);
+        }
+    }
+
+    #[test]
+    fn test_failures_set_codes_and_last_error() {
+        unsafe {
+            let handle = union_policy_parser_init();
+            assert_eq!(union_policy_parser_parse(std::ptr::null_mut(), CONTRACT.as_ptr(), 1), Code::NullPointer);
+            assert_eq!(union_policy_parser_parse(handle, [0xff].as_ptr(), 1), Code::InvalidParam);
+            assert!(last_error().contains("UTF-8"));
+
+            let validate = |union_name: *const c_char, mode| {
+                union_policy_parser_validate(handle, SCHEMA.as_ptr(), SCHEMA.len(), union_name, mode, std::ptr::null_mut())
+            };
+            assert_eq!(validate(std::ptr::null(), UNION_POLICY_PARSER_MODE_LAX), Code::Error);
+            assert_eq!(last_error(), "no contract parsed");
+
+            union_policy_parser_parse(handle, CONTRACT.as_ptr(), CONTRACT.len());
+            assert_eq!(validate(c"teamsters".as_ptr(), UNION_POLICY_PARSER_MODE_LAX), Code::InvalidParam);
+            assert_eq!(validate(std::ptr::null(), 7), Code::InvalidParam);
+            assert!(union_policy_parser_render_json(handle).is_null());
+            union_policy_parser_free(handle);
+        }
+    }
+}
@@ -0,0 +1,242 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Canonical A2ML formatter
+//!
+//! Layout rules:
+//! - Any text before the first heading, a leading `#` title, then
+//!   `@abstract`, `@requires`, the sections, and `@refs` last
+//! - One blank line between blocks, and around headings and directives
+//! - `-` for bullets, ordered lists numbered as written, padded pipe tables
+//! - One `[n] text url` line per reference
+//!
+//! Formatting never changes meaning: `parse(format_a2ml(doc)) == doc`, and
+//! formatting formatted output is a no-op.
+
+use crate::error::{PolicyError, Result};
+use crate::parser::{parse_a2ml_string, A2mlDocument, ContentBlock, Reference, Section};
+
+/// Pretty-print a document as canonical A2ML
+pub fn format_a2ml(doc: &A2mlDocument) -> String {
+    let mut blocks: Vec<String> = doc.preamble.iter().map(format_block).collect();
+
+    let (title, sections) = match doc.sections.split_first() {
+        Some((first, rest)) if first.level == 1 => (Some(first), rest),
+        _ => (None, doc.sections.as_slice()),
+    };
+
+    if let Some(title) = title {
+        blocks.push(format_heading(title));
+    }
+
+    if let Some(abstract_text) = &doc.abstract_text {
+        blocks.push(format!("@abstract:\n{}\n@end", abstract_text));
+    }
+
+    if !doc.requirements.is_empty() {
+        let items: Vec<String> = doc
+            .requirements
+            .iter()
+            .map(|r| format!("- {}", r).trim_end().to_string())
+            .collect();
+        blocks.push(format!("@requires:\n{}\n@end", items.join("\n")));
+    }
+
+    if let Some(title) = title {
+        blocks.extend(title.content.iter().map(format_block));
+    }
+
+    for section in sections {
+        blocks.push(format_heading(section));
+        blocks.extend(section.content.iter().map(format_block));
+    }
+
+    if !doc.references.is_empty() {
+        let lines: Vec<String> = doc.references.iter().map(format_reference).collect();
+        blocks.push(format!("@refs:\n{}\n@end", lines.join("\n")));
+    }
+
+    if blocks.is_empty() {
+        return String::new();
+    }
+
+    format!("{}\n", blocks.join("\n\n"))
+}
+
+/// Format A2ML source, refusing output that would not parse back to the
+/// same document or that loses any of the source's words
+pub fn format_source(source: &str) -> Result<String> {
+    let doc = parse_a2ml_string(source)?;
+    let formatted = format_a2ml(&doc);
+
+    if parse_a2ml_string(&formatted)? != doc {
+        return Err(PolicyError::ParseError(
+            "Formatting would change the document; leaving it untouched".to_string(),
+        ));
+    }
+
+    // The AST does not hold everything (comments, stray text), so check
+    // that nothing in the source went missing on the way through it
+    if words(source) != words(&formatted) {
+        return Err(PolicyError::ParseError(
+            "Formatting would drop text the parser does not keep; leaving it untouched".to_string(),
+        ));
+    }
+
+    Ok(formatted)
+}
+
+/// Every word in the text, sorted; layout and punctuation don't count
+fn words(text: &str) -> Vec<&str> {
+    let mut words: Vec<&str> = text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+    words.sort_unstable();
+    words
+}
+
+/// A section's content in canonical layout, without its heading
+pub fn format_body(section: &Section) -> String {
+    format_blocks(&section.content)
+}
+
+/// Blocks in canonical layout, one blank line apart
+pub fn format_blocks(blocks: &[ContentBlock]) -> String {
+    blocks.iter().map(format_block).collect::<Vec<_>>().join("\n\n")
+}
+
+fn format_heading(section: &Section) -> String {
+    format!("{} {}", "#".repeat(section.level.clamp(1, 6) as usize), section.heading)
+}
+
+fn format_block(block: &ContentBlock) -> String {
+    match block {
+        ContentBlock::Paragraph(text) => text.clone(),
+        ContentBlock::BulletList(items) => items
+            .iter()
+            .map(|item| format!("- {}", item))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        ContentBlock::OrderedList(items) => items
+            .iter()
+            .map(|item| format!("{} {}", item.marker, item.text))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        ContentBlock::Table { headers, rows } => format_table(headers, rows),
+        ContentBlock::CodeBlock { language, code } => {
+            // Code is verbatim, including whether it ends with a newline
+            format!("```{}\n{}```", language.as_deref().unwrap_or(""), code)
+        }
+        ContentBlock::HorizontalRule => "---".to_string(),
+    }
+}
+
+fn format_table(headers: &[String], rows: &[Vec<String>]) -> String {
+    let columns = rows.iter().map(Vec::len).chain([headers.len()]).max().unwrap_or(0);
+    let mut widths = vec![3; columns];
+    for row in rows.iter().chain([&headers.to_vec()]) {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: &[String]| {
+        let padded: Vec<String> = (0..columns)
+            .map(|i| {
+                let cell = cells.get(i).map(String::as_str).unwrap_or("");
+                format!("{}{}", cell, " ".repeat(widths[i] - cell.chars().count()))
+            })
+            .collect();
+        format!("| {} |", padded.join(" | "))
+    };
+
+    let mut lines = vec![line(headers)];
+    lines.push(format!(
+        "| {} |",
+        widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>().join(" | ")
+    ));
+    lines.extend(rows.iter().map(|row| line(row)));
+    lines.join("\n")
+}
+
+fn format_reference(reference: &Reference) -> String {
+    let mut line = if reference.id.is_empty() {
+        reference.text.clone()
+    } else {
+        format!("[{}] {}", reference.id, reference.text)
+    };
+    if let Some(url) = &reference.url {
+        line.push(' ');
+        line.push_str(url);
+    }
+    line.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CORPUS: &[(&str, &str)] = &[
+        ("academic-lecturer", include_str!("../../../examples/academic-lecturer-contract.a2ml")),
+        ("freelance-bad", include_str!("../../../examples/freelance-journalist-bad.a2ml")),
+        ("freelance-good", include_str!("../../../examples/freelance-journalist-good.a2ml")),
+        ("ou-day-employee", include_str!("../../../examples/ou-day-employee-contract.a2ml")),
+        ("bectu", include_str!("../../../schemas/bectu-media-rights.a2ml")),
+        ("equity", include_str!("../../../schemas/equity-performers-rights.a2ml")),
+        ("gmb", include_str!("../../../schemas/gmb-general-workers-rights.a2ml")),
+        ("iww", include_str!("../../../schemas/iww-freelancer-rights.a2ml")),
+        ("nuj", include_str!("../../../schemas/nuj-code-of-ethics.a2ml")),
+        ("nuj-pr", include_str!("../../../schemas/nuj-pr-comms-guidance.a2ml")),
+        ("ucu", include_str!("../../../schemas/ucu-academic-standards.a2ml")),
+    ];
+
+    #[test]
+    fn test_round_trip_preserves_ast() {
+        for (name, source) in CORPUS {
+            let doc = parse_a2ml_string(source).unwrap();
+            let reparsed = parse_a2ml_string(&format_a2ml(&doc)).unwrap();
+            assert_eq!(doc, reparsed, "round trip changed {}", name);
+        }
+    }
+
+    #[test]
+    fn test_format_is_idempotent() {
+        for (name, source) in CORPUS {
+            let once = format_source(source).unwrap();
+            let twice = format_source(&once).unwrap();
+            assert_eq!(once, twice, "formatting {} is not idempotent", name);
+        }
+    }
+
+    #[test]
+    fn test_normalizes_drift() {
+        let source = "#   Contract  \n@abstract:   A contract.   @end\n\n\
+            ## Pay\n* one\n+ two\n3) three\n|a|bb|\n|-|-|\n|long cell|x|\n\
+            @refs:\n[1]    Act   (https://example.org/act)\n@end";
+
+        let formatted = format_source(source).unwrap();
+        assert_eq!(
+            formatted,
+            "# Contract\n\n@abstract:\nA contract.\n@end\n\n## Pay\n\n- one\n- two\n\n3) three\n\n\
+            | a         | bb  |\n| --------- | --- |\n| long cell | x   |\n\n\
+            @refs:\n[1] Act https://example.org/act\n@end\n"
+        );
+    }
+
+    #[test]
+    fn test_preamble_survives_and_lost_text_is_refused() {
+        let source = "Between the Publisher and the Writer.\n\n# Contract\n\n## Pay\n\nFee on delivery.\n";
+        let doc = parse_a2ml_string(source).unwrap();
+        let formatted = format_source(source).unwrap();
+        assert!(formatted.starts_with("Between the Publisher and the Writer.\n\n# Contract\n"));
+        assert_eq!(parse_a2ml_string(&formatted).unwrap(), doc);
+
+        // A second @abstract replaces the first in the AST
+        let source = "@abstract:\nFirst rights only.\n@end\n\n@abstract:\nSummary.\n@end\n\n## Pay\n\nFee.\n";
+        assert!(format_source(source).is_err());
+    }
+
+    #[test]
+    fn test_unnumbered_references_survive() {
+        let source = "## Terms\n\nText.\n\n@refs:\nNone - no references apply\n@end\n";
+        let doc = parse_a2ml_string(source).unwrap();
+        assert_eq!(doc.references[0].id, "");
+        assert!(format_a2ml(&doc).contains("@refs:\nNone - no references apply\n@end"));
+    }
+}
@@ -0,0 +1,694 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Self-contained HTML rendering of contracts and validation reports
+//!
+//! Output is a single file with inline CSS (no scripts, no external assets) so
+//! it can be emailed or printed for members who never touch a terminal.
+
+use crate::parser::{A2mlDocument, ContentBlock, Section};
+use crate::text::slugify;
+use crate::validator::ValidationReport;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+
+lazy_static! {
+    static ref CODE: Regex = Regex::new(r"`([^`]+)`").unwrap();
+    static ref LINK: Regex = Regex::new(r"\[([^\]]+)\]\(([^)\s]+)\)").unwrap();
+    static ref STRONG: Regex = Regex::new(r"\*\*([^*]+)\*\*").unwrap();
+    static ref EMPHASIS: Regex = Regex::new(r"\*([^*]+)\*").unwrap();
+    static ref CITATION: Regex = Regex::new(r"\[(\d+)\]").unwrap();
+    static ref TAG: Regex = Regex::new(r"<[^>]*>").unwrap();
+    /// Generated elements whose text is not scanned for citations
+    static ref NO_CITATIONS: Regex = Regex::new(r"(?s)<a\b[^>]*>.*?</a>|<code>.*?</code>").unwrap();
+}
+
+const STYLE: &str = r#"
+:root { --ok: #1a7f37; --error: #cf222e; --warn: #9a6700; --muted: #57606a; --line: #d0d7de; }
+* { box-sizing: border-box; }
+body { font: 16px/1.6 system-ui, -apple-system, "Segoe UI", sans-serif; color: #1f2328; max-width: 52rem; margin: 0 auto; padding: 1.5rem; }
+header { border-bottom: 2px solid var(--line); margin-bottom: 1.5rem; }
+h1, h2, h3, h4, h5, h6 { line-height: 1.25; }
+a { color: #0969da; }
+nav.toc { background: #f6f8fa; border: 1px solid var(--line); border-radius: 6px; padding: 0.75rem 1.25rem; margin-bottom: 2rem; }
+nav.toc ol { list-style: none; padding-left: 0; margin: 0; }
+li.unnumbered { list-style: none; }
+nav.toc li.toc-l3 { padding-left: 1.25rem; }
+nav.toc li.toc-l4, nav.toc li.toc-l5, nav.toc li.toc-l6 { padding-left: 2.5rem; }
+.badge { display: inline-block; font-size: 0.75rem; font-weight: 600; padding: 0.1rem 0.5rem; border-radius: 1rem; color: #fff; vertical-align: middle; margin-left: 0.25rem; }
+.badge-ok { background: var(--ok); }
+.badge-error { background: var(--error); }
+.badge-warning { background: var(--warn); }
+.badge-muted { background: var(--muted); }
+mark.finding { background: #ffebe9; border-bottom: 2px solid var(--error); padding: 0 0.1rem; }
+aside.findings { border-left: 4px solid var(--error); background: #fff8f8; padding: 0.5rem 1rem; margin: 0.5rem 0 1rem; }
+aside.findings.warning { border-color: var(--warn); background: #fff8e5; }
+aside.findings ul { margin: 0; padding-left: 1.25rem; }
+section.clause { border-top: 1px solid var(--line); padding-top: 0.5rem; }
+.abstract { font-style: italic; color: var(--muted); }
+table { border-collapse: collapse; width: 100%; margin: 1rem 0; }
+th, td { border: 1px solid var(--line); padding: 0.35rem 0.6rem; text-align: left; vertical-align: top; }
+th { background: #f6f8fa; }
+pre { background: #f6f8fa; padding: 0.75rem; overflow-x: auto; border-radius: 6px; }
+dl.summary { display: grid; grid-template-columns: max-content auto; gap: 0.25rem 1rem; }
+dl.summary dt { font-weight: 600; }
+dl.summary dd { margin: 0; }
+@media print {
+  body { max-width: none; padding: 0; font-size: 11pt; }
+  nav.toc { display: none; }
+  a { color: inherit; text-decoration: none; }
+  .badge { color: #000; border: 1px solid #000; background: none; }
+  mark.finding { background: none; text-decoration: underline wavy; }
+  section.clause, aside.findings, table { break-inside: avoid; }
+  h1, h2, h3, h4, h5, h6 { break-after: avoid; }
+}
+"#;
+
+/// Render a contract, optionally annotated with a validation report
+pub fn render_contract(doc: &A2mlDocument, report: Option<&ValidationReport>) -> String {
+    let anchors = section_anchors(&doc.sections);
+    let title = doc
+        .sections
+        .iter()
+        .find(|s| s.level == 1)
+        .map(|s| s.heading.as_str())
+        .unwrap_or("Contract");
+
+    let mut body = String::new();
+
+    body.push_str("<header>\n");
+    body.push_str(&format!("<h1>{}</h1>\n", escape(title)));
+    if let Some(report) = report {
+        body.push_str(&format!("<p>{}</p>\n", status_badge(report)));
+        body.push_str(&clause_checklist(report, &doc.sections, &anchors));
+    }
+    body.push_str("</header>\n");
+
+    // Table of contents
+    body.push_str("<nav class=\"toc\" aria-label=\"Contents\">\n<h2>Contents</h2>\n<ol>\n");
+    if doc.abstract_text.is_some() {
+        body.push_str("<li class=\"toc-l2\"><a href=\"#abstract\">Abstract</a></li>\n");
+    }
+    if !doc.requirements.is_empty() {
+        body.push_str("<li class=\"toc-l2\"><a href=\"#requires\">Requirements</a></li>\n");
+    }
+    for (i, section) in doc.sections.iter().enumerate() {
+        if section.level == 1 && section.heading == title {
+            continue;
+        }
+        body.push_str(&format!(
+            "<li class=\"toc-l{}\"><a href=\"#{}\">{}</a>{}</li>\n",
+            section.level.max(2),
+            anchors[i],
+            inline(&section.heading),
+            report.map(|r| section_badges(r, section)).unwrap_or_default(),
+        ));
+    }
+    if !doc.references.is_empty() {
+        body.push_str("<li class=\"toc-l2\"><a href=\"#references\">References</a></li>\n");
+    }
+    body.push_str("</ol>\n</nav>\n");
+
+    body.push_str("<main>\n");
+
+    if !doc.preamble.is_empty() {
+        body.push_str("<section class=\"preamble\">\n");
+        for block in &doc.preamble {
+            body.push_str(&render_block(block, &[]));
+        }
+        body.push_str("</section>\n");
+    }
+
+    if let Some(abstract_text) = &doc.abstract_text {
+        body.push_str("<section id=\"abstract\" class=\"abstract\">\n<h2>Abstract</h2>\n");
+        body.push_str(&paragraph(abstract_text, &[]));
+        body.push_str("</section>\n");
+    }
+
+    if !doc.requirements.is_empty() {
+        body.push_str("<section id=\"requires\">\n<h2>Requirements</h2>\n<ul>\n");
+        for requirement in &doc.requirements {
+            body.push_str(&format!("<li>{}</li>\n", inline(requirement)));
+        }
+        body.push_str("</ul>\n</section>\n");
+    }
+
+    for (i, section) in doc.sections.iter().enumerate() {
+        body.push_str(&render_section(section, &anchors[i], title, report));
+    }
+
+    if !doc.references.is_empty() {
+        body.push_str("<section id=\"references\">\n<h2>References</h2>\n<ol>\n");
+        for reference in &doc.references {
+            if reference.id.is_empty() {
+                body.push_str(&format!("<li class=\"unnumbered\">{}", inline(&reference.text)));
+            } else {
+                body.push_str(&format!(
+                    "<li id=\"ref-{}\" value=\"{}\">{}",
+                    escape(&reference.id),
+                    escape(&reference.id),
+                    inline(&reference.text),
+                ));
+            }
+            match &reference.url {
+                Some(url) if safe_url(url) => body.push_str(&format!(" <a href=\"{0}\">{0}</a>", escape(url))),
+                Some(url) => body.push_str(&format!(" {}", escape(url))),
+                None => {}
+            }
+            body.push_str("</li>\n");
+        }
+        body.push_str("</ol>\n</section>\n");
+    }
+
+    body.push_str("</main>\n");
+
+    page(title, &body)
+}
+
+/// Render a validation report as a standalone page
+pub fn render_report(report: &ValidationReport) -> String {
+    let mut body = String::new();
+
+    body.push_str("<header>\n<h1>Validation Report</h1>\n<dl class=\"summary\">\n");
+    body.push_str(&format!("<dt>Contract</dt><dd><code>{}</code></dd>\n", escape(&report.contract.path)));
+    if let Some(sha256) = &report.contract.sha256 {
+        body.push_str(&format!("<dt>SHA-256</dt><dd><code>{}</code></dd>\n", sha256));
+    }
+    let schema_version = report.schema.version.as_deref().map(|v| format!(", version {}", v)).unwrap_or_default();
+    let schema_name = report.schema.name.as_deref().map(|n| format!(" ({}{})", escape(n), escape(&schema_version))).unwrap_or_default();
+    body.push_str(&format!("<dt>Schema</dt><dd><code>{}</code>{}</dd>\n", escape(&report.schema.path), schema_name));
+    if let Some(union) = &report.union {
+        body.push_str(&format!("<dt>Union</dt><dd>{}</dd>\n", escape(&union.to_uppercase())));
+    }
+    body.push_str(&format!(
+        "<dt>Checked</dt><dd><time datetime=\"{0}\">{0}</time> by union-policy-parser {1}</dd>\n",
+        escape(&report.timestamp),
+        escape(&report.tool_version)
+    ));
+    body.push_str(&format!("<dt>Status</dt><dd>{}</dd>\n", status_badge(report)));
+    body.push_str("</dl>\n</header>\n");
+
+    body.push_str("<nav class=\"toc\" aria-label=\"Contents\">\n<h2>Contents</h2>\n<ol>\n");
+    for (id, label, count) in [
+        ("errors", "Errors", report.errors.len()),
+        ("warnings", "Warnings", report.warnings.len()),
+        ("red-flags", "Red Flags", report.red_flags.len()),
+        ("required-clauses", "Required Clauses", report.required_clauses.len()),
+    ] {
+        if count > 0 {
+            body.push_str(&format!(
+                "<li class=\"toc-l2\"><a href=\"#{}\">{}</a> <span class=\"badge badge-muted\">{}</span></li>\n",
+                id, label, count
+            ));
+        }
+    }
+    body.push_str("</ol>\n</nav>\n<main>\n");
+
+    if !report.errors.is_empty() {
+        body.push_str("<section id=\"errors\">\n<h2>Errors</h2>\n<table>\n");
+        body.push_str("<thead><tr><th>Kind</th><th>Message</th><th>Location</th></tr></thead>\n<tbody>\n");
+        for error in &report.errors {
+            body.push_str(&format!(
+                "<tr><td><span class=\"badge badge-error\">{:?}</span></td><td>{}</td><td>{}</td></tr>\n",
+                error.kind,
+                escape(&error.message),
+                escape(error.location.as_deref().unwrap_or("")),
+            ));
+        }
+        body.push_str("</tbody>\n</table>\n</section>\n");
+    }
+
+    if !report.warnings.is_empty() {
+        body.push_str("<section id=\"warnings\">\n<h2>Warnings</h2>\n<table>\n");
+        body.push_str("<thead><tr><th>Message</th><th>Location</th></tr></thead>\n<tbody>\n");
+        for warning in &report.warnings {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td></tr>\n",
+                escape(&warning.message),
+                escape(warning.location.as_deref().unwrap_or("")),
+            ));
+        }
+        body.push_str("</tbody>\n</table>\n</section>\n");
+    }
+
+    if !report.red_flags.is_empty() {
+        body.push_str("<section id=\"red-flags\">\n<h2>Red Flags</h2>\n<table>\n");
+        body.push_str("<thead><tr><th>Pattern</th><th>Text</th><th>Location</th></tr></thead>\n<tbody>\n");
+        for flag in &report.red_flags {
+            body.push_str(&format!(
+                "<tr><td><span class=\"badge badge-error\">{}</span></td><td><mark class=\"finding\">{}</mark></td><td>{}</td></tr>\n",
+                escape(&flag.pattern),
+                escape(&flag.excerpt),
+                escape(flag.location.as_deref().unwrap_or("")),
+            ));
+        }
+        body.push_str("</tbody>\n</table>\n</section>\n");
+    }
+
+    if !report.required_clauses.is_empty() {
+        body.push_str("<section id=\"required-clauses\">\n<h2>Required Clauses</h2>\n<table>\n");
+        body.push_str("<thead><tr><th>Clause</th><th>Status</th><th>Section</th></tr></thead>\n<tbody>\n");
+        for check in &report.required_clauses {
+            let status = if check.present {
+                "<span class=\"badge badge-ok\">present</span>"
+            } else {
+                "<span class=\"badge badge-error\">missing</span>"
+            };
+            body.push_str(&format!(
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td></tr>\n",
+                escape(&check.clause),
+                status,
+                escape(check.section.as_deref().unwrap_or("")),
+            ));
+        }
+        body.push_str("</tbody>\n</table>\n</section>\n");
+    }
+
+    body.push_str("</main>\n");
+
+    page("Validation Report", &body)
+}
+
+/// Escape text for HTML element content and attribute values
+pub fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+        <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+        <meta name=\"generator\" content=\"union-policy-parser {}\">\n\
+        <title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        env!("CARGO_PKG_VERSION"),
+        escape(title),
+        STYLE,
+        body,
+    )
+}
+
+/// Unique anchor per section, in document order
+fn section_anchors(sections: &[Section]) -> Vec<String> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    sections
+        .iter()
+        .map(|section| {
+            let base = slugify(&section.heading);
+            let count = seen.entry(base.clone()).or_insert(0);
+            *count += 1;
+            if *count == 1 {
+                base
+            } else {
+                format!("{}-{}", base, count)
+            }
+        })
+        .collect()
+}
+
+fn render_section(section: &Section, anchor: &str, title: &str, report: Option<&ValidationReport>) -> String {
+    let mut html = String::new();
+    let level = section.level.clamp(1, 6);
+
+    html.push_str(&format!("<section id=\"{}\" class=\"clause\">\n", anchor));
+    if !(level == 1 && section.heading == title) {
+        html.push_str(&format!(
+            "<h{0}><a href=\"#{1}\">{2}</a>{3}</h{0}>\n",
+            level,
+            anchor,
+            inline(&section.heading),
+            report.map(|r| section_badges(r, section)).unwrap_or_default(),
+        ));
+    }
+
+    let mut highlights: Vec<String> = Vec::new();
+    if let Some(report) = report {
+        for flag in report.red_flags_at(&section.heading) {
+            if !highlights.contains(&flag.pattern) {
+                highlights.push(flag.pattern.clone());
+            }
+        }
+    }
+
+    let body: String = section.content.iter().map(|block| render_block(block, &highlights)).collect();
+    if let Some(report) = report {
+        // A phrase in a heading or code block is named in the findings, not marked
+        let unmarked: Vec<&String> = highlights
+            .iter()
+            .filter(|p| !body.contains(&format!("title=\"Red flag: {}\"", escape(p))))
+            .collect();
+        html.push_str(&section_findings(report, section, &unmarked));
+    }
+    html.push_str(&body);
+
+    html.push_str("</section>\n");
+    html
+}
+
+fn render_block(block: &ContentBlock, highlights: &[String]) -> String {
+    match block {
+        ContentBlock::Paragraph(text) => paragraph(text, highlights),
+        ContentBlock::BulletList(items) => {
+            let mut html = String::from("<ul>\n");
+            for item in items {
+                html.push_str(&format!("<li>{}</li>\n", highlight(&inline(item), highlights)));
+            }
+            html.push_str("</ul>\n");
+            html
+        }
+        ContentBlock::OrderedList(items) => {
+            let mut html = String::from("<ol>\n");
+            for item in items {
+                html.push_str(&format!(
+                    "<li value=\"{}\">{}</li>\n",
+                    item.number(),
+                    highlight(&inline(&item.text), highlights)
+                ));
+            }
+            html.push_str("</ol>\n");
+            html
+        }
+        ContentBlock::Table { headers, rows } => {
+            let mut html = String::from("<table>\n<thead><tr>");
+            for header in headers {
+                html.push_str(&format!("<th>{}</th>", inline(header)));
+            }
+            html.push_str("</tr></thead>\n<tbody>\n");
+            for row in rows {
+                html.push_str("<tr>");
+                for cell in row {
+                    html.push_str(&format!("<td>{}</td>", highlight(&inline(cell), highlights)));
+                }
+                html.push_str("</tr>\n");
+            }
+            html.push_str("</tbody>\n</table>\n");
+            html
+        }
+        ContentBlock::CodeBlock { language, code } => {
+            let class = language
+                .as_deref()
+                .filter(|l| !l.is_empty())
+                .map(|l| format!(" class=\"language-{}\"", escape(l)))
+                .unwrap_or_default();
+            format!("<pre><code{}>{}</code></pre>\n", class, escape(code))
+        }
+        ContentBlock::HorizontalRule => String::new(),
+    }
+}
+
+fn paragraph(text: &str, highlights: &[String]) -> String {
+    // Highlight the whole paragraph, so a phrase can run over a line break
+    let lines: Vec<String> = text.lines().map(|line| inline(line.trim())).collect();
+    format!("<p>{}</p>\n", highlight(&lines.join("<br>\n"), highlights))
+}
+
+/// Render inline markup: `code`, [links](url), **strong**, *emphasis*, [n] citations
+fn inline(text: &str) -> String {
+    let html = escape(text);
+    let html = CODE.replace_all(&html, "<code>$1</code>");
+    let html = LINK.replace_all(&html, |caps: &Captures| {
+        if safe_url(&caps[2]) {
+            format!("<a href=\"{}\">{}</a>", &caps[2], &caps[1])
+        } else {
+            caps[0].to_string()
+        }
+    });
+    let html = STRONG.replace_all(&html, "<strong>$1</strong>");
+    let html = EMPHASIS.replace_all(&html, "<em>$1</em>");
+    citations(&html)
+}
+
+/// Link `[n]` to reference n, except inside links and code spans
+fn citations(html: &str) -> String {
+    let link = |text: &str| CITATION.replace_all(text, "<a class=\"citation\" href=\"#ref-$1\">[$1]</a>").into_owned();
+    let mut out = String::with_capacity(html.len());
+    let mut last = 0;
+    for element in NO_CITATIONS.find_iter(html) {
+        out.push_str(&link(&html[last..element.start()]));
+        out.push_str(element.as_str());
+        last = element.end();
+    }
+    out.push_str(&link(&html[last..]));
+    out
+}
+
+/// Link targets that are safe to put in an `href`: web, mail and in-page
+fn safe_url(url: &str) -> bool {
+    let lower = url.trim_start().to_ascii_lowercase();
+    ["http://", "https://", "mailto:", "#"].iter().any(|scheme| lower.starts_with(scheme))
+}
+
+/// Wrap case-insensitive matches of `patterns` in `<mark>`, outside of tags
+///
+/// Matching runs on the text with tags removed and any run of whitespace
+/// (including a line break) standing for a space, the way the scanner found
+/// the phrase; a match crossing a tag is marked in pieces.
+fn highlight(html: &str, patterns: &[String]) -> String {
+    let matchers: Vec<(String, Regex)> = patterns
+        .iter()
+        .filter_map(|p| {
+            let escaped = escape(p);
+            let words: Vec<String> = escaped.split_whitespace().map(regex::escape).collect();
+            if words.is_empty() {
+                return None;
+            }
+            Regex::new(&format!(r"(?i){}", words.join(r"\s+"))).ok().map(|re| (escaped, re))
+        })
+        .collect();
+    if matchers.is_empty() {
+        return html.to_string();
+    }
+
+    // Text between tags, and where each piece starts in the joined text
+    let mut pieces = Vec::new();
+    let mut last = 0;
+    for tag in TAG.find_iter(html) {
+        pieces.push(last..tag.start());
+        last = tag.end();
+    }
+    pieces.push(last..html.len());
+    let text: String = pieces.iter().map(|piece| &html[piece.clone()]).collect();
+
+    // Leftmost match first; a later pattern cannot overlap an earlier mark
+    let mut found: Vec<(usize, usize, &str)> = matchers
+        .iter()
+        .flat_map(|(title, re)| re.find_iter(&text).map(move |m| (m.start(), m.end(), title.as_str())))
+        .collect();
+    found.sort_by_key(|&(start, end, _)| (start, std::cmp::Reverse(end)));
+    let mut marks: Vec<(usize, usize, &str)> = Vec::new();
+    for mark in found {
+        if marks.last().is_none_or(|last| mark.0 >= last.1) {
+            marks.push(mark);
+        }
+    }
+
+    let mut out = String::with_capacity(html.len());
+    let mut offset = 0;
+    for (i, piece) in pieces.iter().enumerate() {
+        let chunk = &html[piece.clone()];
+        let mut pos = 0;
+        for &(start, end, title) in &marks {
+            let from = start.max(offset).saturating_sub(offset);
+            let to = end.min(offset + chunk.len()).saturating_sub(offset);
+            if from >= to {
+                continue;
+            }
+            let span = &chunk[from..to];
+            let marked = span.trim();
+            if marked.is_empty() {
+                continue;
+            }
+            let from = from + (span.len() - span.trim_start().len());
+            out.push_str(&chunk[pos..from]);
+            out.push_str(&format!("<mark class=\"finding\" title=\"Red flag: {}\">{}</mark>", title, marked));
+            pos = from + marked.len();
+        }
+        out.push_str(&chunk[pos..]);
+        offset += chunk.len();
+        if let Some(next) = pieces.get(i + 1) {
+            out.push_str(&html[piece.end..next.start]);
+        }
+    }
+    out
+}
+
+fn status_badge(report: &ValidationReport) -> String {
+    if report.valid {
+        "<span class=\"badge badge-ok\">VALID</span>".to_string()
+    } else {
+        format!(
+            "<span class=\"badge badge-error\">INVALID</span> {} error(s), {} warning(s), {} red flag(s)",
+            report.errors.len(),
+            report.warnings.len(),
+            report.red_flags.len(),
+        )
+    }
+}
+
+fn clause_checklist(report: &ValidationReport, sections: &[Section], anchors: &[String]) -> String {
+    if report.required_clauses.is_empty() {
+        return String::new();
+    }
+    let mut html = String::from("<h2>Required Clauses</h2>\n<ul class=\"checklist\">\n");
+    for check in &report.required_clauses {
+        let anchor = check
+            .section
+            .as_ref()
+            .and_then(|heading| sections.iter().position(|s| &s.heading == heading))
+            .map(|i| &anchors[i]);
+        match anchor {
+            Some(anchor) if check.present => html.push_str(&format!(
+                "<li><span class=\"badge badge-ok\">present</span> <a href=\"#{}\"><code>{}</code></a></li>\n",
+                anchor,
+                escape(&check.clause),
+            )),
+            _ => html.push_str(&format!(
+                "<li><span class=\"badge badge-error\">missing</span> <code>{}</code></li>\n",
+                escape(&check.clause),
+            )),
+        }
+    }
+    html.push_str("</ul>\n");
+    html
+}
+
+fn section_badges(report: &ValidationReport, section: &Section) -> String {
+    let mut badges = String::new();
+    // Red flags are MUST-level findings, so they count as errors
+    let errors = report.errors_at(&section.heading).count() + report.red_flags_at(&section.heading).count();
+    let warnings = report.warnings_at(&section.heading).count();
+    if errors > 0 {
+        badges.push_str(&format!(" <span class=\"badge badge-error\">{} error(s)</span>", errors));
+    }
+    if warnings > 0 {
+        badges.push_str(&format!(" <span class=\"badge badge-warning\">{} warning(s)</span>", warnings));
+    }
+    for check in &report.required_clauses {
+        if check.section.as_deref() == Some(section.heading.as_str()) {
+            badges.push_str(&format!(" <span class=\"badge badge-ok\">{}</span>", escape(&check.clause)));
+        }
+    }
+    badges
+}
+
+fn section_findings(report: &ValidationReport, section: &Section, unmarked: &[&String]) -> String {
+    let errors: Vec<String> = report
+        .errors_at(&section.heading)
+        .map(|e| format!("<li><strong>{:?}:</strong> {}</li>\n", e.kind, escape(&e.message)))
+        .chain(report.red_flags_at(&section.heading).map(|f| {
+            let note = if unmarked.contains(&&f.pattern) { " (not highlighted in the text below)" } else { "" };
+            format!("<li>Red flag: <mark class=\"finding\">{}</mark>{}</li>\n", escape(&f.excerpt), note)
+        }))
+        .collect();
+    let warnings: Vec<String> = report
+        .warnings_at(&section.heading)
+        .map(|w| format!("<li>{}</li>\n", escape(&w.message)))
+        .collect();
+
+    let mut html = String::new();
+    if !errors.is_empty() {
+        html.push_str("<aside class=\"findings error\" role=\"note\">\n<ul>\n");
+        html.push_str(&errors.concat());
+        html.push_str("</ul>\n</aside>\n");
+    }
+    if !warnings.is_empty() {
+        html.push_str("<aside class=\"findings warning\" role=\"note\">\n<ul>\n");
+        html.push_str(&warnings.concat());
+        html.push_str("</ul>\n</aside>\n");
+    }
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_a2ml_string;
+    use crate::validator::RedFlag;
+
+    #[test]
+    fn test_render_contract_anchors_and_citations() {
+        let doc = parse_a2ml_string(
+            "# Contract\n\n## Payment\n\nPaid per **NET 30** [1].\n\n## Payment\n\nAgain.\n\n@refs:\n[1] Late Payment Act\n@end\n",
+        )
+        .unwrap();
+        let html = render_contract(&doc, None);
+
+        assert!(html.contains("<section id=\"payment\" class=\"clause\">"));
+        assert!(html.contains("<section id=\"payment-2\" class=\"clause\">"));
+        assert!(html.contains("<strong>NET 30</strong>"));
+        assert!(html.contains("href=\"#ref-1\""));
+        assert!(html.contains("<li id=\"ref-1\""));
+    }
+
+    #[test]
+    fn test_render_contract_highlights_red_flags() {
+        let doc = parse_a2ml_string("## Copyright\n\nClient takes **All Rights** forever.\n").unwrap();
+        let mut report = ValidationReport::new("c.a2ml".to_string(), "s.a2ml".to_string());
+        report.add_red_flag(RedFlag {
+            pattern: "all rights".to_string(),
+            excerpt: "All Rights".to_string(),
+            location: Some("Copyright".to_string()),
+            line: Some(3),
+        });
+
+        let html = render_contract(&doc, Some(&report));
+        assert!(html.contains("<strong><mark class=\"finding\" title=\"Red flag: all rights\">All Rights</mark></strong>"));
+        assert!(html.contains("badge-error\">1 error(s)"));
+    }
+
+    #[test]
+    fn test_render_contract_highlights_across_lines_and_markup() {
+        let doc = parse_a2ml_string("## Copyright\n\nClient takes all\nrights, and **work** for hire.\n\n```\nbuyout\n```\n").unwrap();
+        let mut report = ValidationReport::new("c.a2ml".to_string(), "s.a2ml".to_string());
+        for (pattern, excerpt) in [("all rights", "all rights"), ("work for hire", "work for hire"), ("buyout", "buyout")] {
+            report.add_red_flag(RedFlag {
+                pattern: pattern.to_string(),
+                excerpt: excerpt.to_string(),
+                location: Some("Copyright".to_string()),
+                line: Some(3),
+            });
+        }
+
+        let html = render_contract(&doc, Some(&report));
+        assert!(html.contains("<mark class=\"finding\" title=\"Red flag: all rights\">all</mark><br>\n<mark class=\"finding\" title=\"Red flag: all rights\">rights</mark>,"));
+        assert!(html.contains("<strong><mark class=\"finding\" title=\"Red flag: work for hire\">work</mark></strong> <mark class=\"finding\" title=\"Red flag: work for hire\">for hire</mark>"));
+        assert!(html.contains("<mark class=\"finding\">buyout</mark> (not highlighted in the text below)"));
+        assert!(!html.contains("<mark class=\"finding\">all rights</mark> (not"));
+    }
+
+    #[test]
+    fn test_render_contract_links_only_safe_schemes() {
+        let doc = parse_a2ml_string(
+            "## Terms\n\nSee [the code](https://nuj.org.uk/code), [us](mailto:a@b.org), [fees](#fees), \
+            [click](JavaScript:alert(1)) and [file](data:text/html,x).\n",
+        )
+        .unwrap();
+        let html = render_contract(&doc, None);
+        assert!(html.contains("<a href=\"https://nuj.org.uk/code\">the code</a>"));
+        assert!(html.contains("<a href=\"mailto:a@b.org\">us</a>"));
+        assert!(html.contains("<a href=\"#fees\">fees</a>"));
+        assert!(!html.to_lowercase().contains("href=\"javascript"));
+        assert!(!html.contains("href=\"data:"));
+        assert!(html.contains("[file](data:text/html,x)"));
+    }
+
+    #[test]
+    fn test_citations_not_nested_in_links_or_code() {
+        let doc = parse_a2ml_string("## Terms\n\nSee [the Act](https://a.org/?q[1]=x), `x[3]` and [4].\n").unwrap();
+        let html = render_contract(&doc, None);
+        assert!(html.contains("<a href=\"https://a.org/?q[1]=x\">the Act</a>"));
+        assert!(html.contains("<code>x[3]</code>"));
+        assert!(html.contains("<a class=\"citation\" href=\"#ref-4\">[4]</a>"));
+        assert_eq!(html.matches("class=\"citation\"").count(), 1);
+    }
+
+    #[test]
+    fn test_render_report_escapes() {
+        let mut report = ValidationReport::new("<c>.a2ml".to_string(), "s.a2ml".to_string());
+        report.add_warning("Uses <script>".to_string(), None);
+        let html = render_report(&report);
+        assert!(html.contains("&lt;c&gt;.a2ml"));
+        assert!(!html.contains("<script>"));
+    }
+}
@@ -0,0 +1,600 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! K9 wrapper reader (`*.k9.ncl`)
+//!
+//! A K9 wrapper is a Nickel record around an A2ML payload: its `pedigree`,
+//! the `payload_file`, and a `validation` block naming the schema and the
+//! clauses to require. This reads the subset of Nickel the wrappers use —
+//! records (with dotted fields and `| optional` style annotations), lists,
+//! strings including `m%"…"%` blocks, numbers, booleans, `null` and enum
+//! tags — without evaluating anything. Anything else (functions, `let`
+//! bindings, arithmetic) is kept as source text, so a wrapper's `contracts`
+//! and `recipes` do not stop the rest from being read.
+
+use crate::error::{PolicyError, Result};
+use crate::signing::{Signature, SignedKind};
+use nom::{
+    IResult,
+    branch::alt,
+    bytes::complete::{tag, take_while, take_while1},
+    character::complete::{char, multispace1, not_line_ending, satisfy},
+    combinator::{map, opt, peek, recognize},
+    multi::many0,
+    number::complete::double,
+    sequence::{pair, preceded},
+};
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// A Nickel value, as far as the wrapper subset goes
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    /// Enum tag, without the quote (`'Hunt` is `Tag("Hunt")`)
+    Tag(String),
+    List(Vec<Value>),
+    /// Fields in source order
+    Record(Vec<(String, Value)>),
+    /// Anything outside the subset, as written
+    Expr(String),
+}
+
+impl Value {
+    /// Field at a dotted path ("validation.schema")
+    pub fn get(&self, path: &str) -> Option<&Value> {
+        path.split('.').try_fold(self, |value, key| match value {
+            Value::Record(fields) => fields.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None,
+        })
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) | Value::Tag(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_record(&self) -> Option<&[(String, Value)]> {
+        match self {
+            Value::Record(fields) => Some(fields),
+            _ => None,
+        }
+    }
+
+    /// The strings in a list; other items are skipped
+    pub fn strings(&self) -> Vec<String> {
+        match self {
+            Value::List(items) => items.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Whether a path names a K9 wrapper
+pub fn is_k9(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().ends_with(".k9.ncl"))
+}
+
+/// A K9 wrapper's settings, with paths resolved against the wrapper's directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct K9Wrapper {
+    /// The wrapper file
+    pub path: PathBuf,
+
+    /// `pedigree.name`
+    pub name: Option<String>,
+
+    /// `payload_file`: the A2ML contract
+    pub payload: PathBuf,
+
+    /// `validation.schema`
+    pub schema: Option<PathBuf>,
+
+    /// `validation.required_clauses`
+    pub required_clauses: Vec<String>,
+
+    /// `validation.recommended_clauses`
+    pub recommended_clauses: Vec<String>,
+
+    /// `validation.union_clauses`: extra required clauses per union
+    pub union_clauses: Vec<(String, Vec<String>)>,
+
+    /// `contracts`: predicate source by name
+    pub contracts: Vec<(String, String)>,
+
+    /// `security.trust_level` (`Kennel`, `Yard` or `Hunt`)
+    pub trust_level: Option<String>,
+
+    /// `validation.checksum`, `signature` and `signed_by`, when all are set
+    pub signature: Option<Signature>,
+
+    /// The whole record
+    pub record: Value,
+}
+
+impl K9Wrapper {
+    /// Read a wrapper file
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Err(PolicyError::FileNotFound(path.to_path_buf()));
+        }
+        Self::from_source(&fs::read_to_string(path)?, path)
+    }
+
+    /// Read a wrapper's source; `path` is where it lives, for resolving paths
+    pub fn from_source(source: &str, path: &Path) -> Result<Self> {
+        let record = parse(source)?;
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+        let text = |field: &str| record.get(field).and_then(Value::as_str).map(str::to_string);
+        let list = |field: &str| record.get(field).map(Value::strings).unwrap_or_default();
+
+        let payload = text("payload_file")
+            .ok_or_else(|| PolicyError::ParseError("K9 wrapper has no payload_file".to_string()))?;
+        let union_clauses = record
+            .get("validation.union_clauses")
+            .and_then(Value::as_record)
+            .unwrap_or_default()
+            .iter()
+            .map(|(union, clauses)| (union.clone(), clauses.strings()))
+            .collect();
+        let contracts = record
+            .get("contracts")
+            .and_then(Value::as_record)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|(name, value)| match value {
+                Value::Expr(source) => Some((name.clone(), source.clone())),
+                _ => None,
+            })
+            .collect();
+        let signature = match (text("validation.checksum"), text("validation.signature"), text("validation.signed_by")) {
+            (Some(checksum), Some(signature), Some(signed_by)) => Some(Signature {
+                kind: SignedKind::Contract,
+                checksum,
+                signature,
+                signed_by,
+            }),
+            _ => None,
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            name: text("pedigree.name"),
+            payload: base.join(payload),
+            schema: text("validation.schema").map(|schema| base.join(schema)),
+            required_clauses: list("validation.required_clauses"),
+            recommended_clauses: list("validation.recommended_clauses"),
+            union_clauses,
+            contracts,
+            trust_level: text("security.trust_level"),
+            signature,
+            record,
+        })
+    }
+
+    /// Whether the payload must be signed (`'Hunt` trust level)
+    pub fn requires_signature(&self) -> bool {
+        self.trust_level.as_deref() == Some("Hunt")
+    }
+
+    /// Extra required clauses for a union ("nuj"), if the wrapper lists any
+    pub fn clauses_for_union(&self, union: &str) -> &[String] {
+        self.union_clauses
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(union))
+            .map_or(&[], |(_, clauses)| clauses)
+    }
+}
+
+/// Set string fields of the wrapper's `validation` block, leaving the rest
+/// of the source (comments, layout, annotations) as written. A field that
+/// is there gets the new value; others are added at the end of the block.
+pub fn set_validation_fields(source: &str, fields: &[(&str, &str)]) -> Result<String> {
+    let no_block = || PolicyError::ParseError("K9 wrapper has no `validation` block".to_string());
+    let start = source.len() - ws(source).map_or(source, |(rest, _)| rest).len();
+    let (_, top) = field_spans(&source[start..]).map_err(|_| no_block())?;
+    let block = top
+        .into_iter()
+        .find(|(path, span)| path == &["validation"] && source[start + span.start..].starts_with('{'))
+        .map(|(_, span)| start + span.start..start + span.end)
+        .ok_or_else(no_block)?;
+    let (_, existing) = field_spans(&source[block.clone()]).map_err(|_| no_block())?;
+
+    let quote = |value: &str| format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+    let mut added = Vec::new();
+    for (name, value) in fields {
+        match existing.iter().find(|(path, _)| path == &[*name]) {
+            Some((_, span)) => edits.push((block.start + span.start..block.start + span.end, quote(value))),
+            None => added.push(format!("{} = {},", name, quote(value))),
+        }
+    }
+
+    if !added.is_empty() {
+        let close = block.end - 1;
+        let before = source[block.start..close].trim_end();
+        if !before.ends_with(',') && !before.ends_with('{') {
+            let end = block.start + before.len();
+            edits.push((end..end, ",".to_string()));
+        }
+        let line_start = source[..close].rfind('\n').map_or(0, |i| i + 1);
+        let indent = &source[line_start..close];
+        if indent.trim().is_empty() {
+            let lines: String = added.iter().map(|field| format!("{}  {}\n", indent, field)).collect();
+            edits.push((line_start..line_start, lines));
+        } else {
+            edits.push((close..close, format!("{} ", added.join(" "))));
+        }
+    }
+
+    let mut edited = source.to_string();
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+    for (range, text) in edits {
+        edited.replace_range(range, &text);
+    }
+    parse(&edited)?;
+    Ok(edited)
+}
+
+/// Parse a wrapper's top-level record
+pub fn parse(source: &str) -> Result<Value> {
+    let error = |rest: &str, what: &str| {
+        let line = source[..source.len() - rest.len()].lines().count().max(1);
+        PolicyError::ParseError(format!(
+            "K9 line {}: {}: {}",
+            line,
+            what,
+            rest.lines().next().unwrap_or("").trim().chars().take(50).collect::<String>()
+        ))
+    };
+
+    let skip_ws = |input| ws(input).map_or(input, |(rest, _)| rest);
+    match record(skip_ws(source)) {
+        Ok((rest, value)) => match skip_ws(rest) {
+            "" => Ok(value),
+            rest => Err(error(rest, "unexpected content after the record")),
+        },
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(error(e.input, "expected a record")),
+        Err(nom::Err::Incomplete(_)) => Err(error("", "incomplete input")),
+    }
+}
+
+// ============================================================================
+// Parser Combinators
+// ============================================================================
+
+/// Whitespace and `#` comments
+fn ws(input: &str) -> IResult<&str, ()> {
+    let (input, _) = many0(alt((multispace1, preceded(char('#'), not_line_ending))))(input)?;
+    Ok((input, ()))
+}
+
+fn identifier(input: &str) -> IResult<&str, &str> {
+    recognize(pair(
+        satisfy(|c| c.is_ascii_alphabetic() || c == '_'),
+        take_while(|c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '\''),
+    ))(input)
+}
+
+/// `{ field = value, ... }`
+fn record(input: &str) -> IResult<&str, Value> {
+    let (mut input, _) = char('{')(input)?;
+    let mut fields: Vec<(String, Value)> = Vec::new();
+    loop {
+        let (rest, _) = ws(input)?;
+        if let Ok((rest, _)) = char::<_, nom::error::Error<&str>>('}')(rest) {
+            return Ok((rest, Value::Record(fields)));
+        }
+        let (rest, (path, value)) = field(rest)?;
+        if let Some(value) = value {
+            insert(&mut fields, &path, value);
+        }
+        let (rest, _) = ws(rest)?;
+        input = match char::<_, nom::error::Error<&str>>(',')(rest) {
+            Ok((rest, _)) => rest,
+            Err(_) => {
+                let (rest, _) = peek(char('}'))(rest)?;
+                rest
+            }
+        };
+    }
+}
+
+/// `a.b | optional = value`; a field with no `=` declares without defining
+fn field(input: &str) -> IResult<&str, (Vec<String>, Option<Value>)> {
+    let (input, path) = field_head(input)?;
+    let (input, value) = opt(preceded(pair(char('='), ws), value))(input)?;
+    Ok((input, (path, value)))
+}
+
+/// A field's path and annotations, up to its `=`
+fn field_head(input: &str) -> IResult<&str, Vec<String>> {
+    let (input, first) = field_name(input)?;
+    let (input, rest) = many0(preceded(pair(ws, char('.')), preceded(ws, field_name)))(input)?;
+    let mut path = vec![first];
+    path.extend(rest);
+
+    // Type and metadata annotations (`| optional`, `| String`) are skipped
+    let (input, _) = many0(preceded(pair(ws, char('|')), |i| expr_until(i, &['=', ',', '}'])))(input)?;
+    let (input, _) = ws(input)?;
+    Ok((input, path))
+}
+
+/// A field's path and the byte range of its value
+type FieldSpan = (Vec<String>, Range<usize>);
+
+/// Where the values of a record's fields are
+fn field_spans(source: &str) -> IResult<&str, Vec<FieldSpan>> {
+    let offset = |rest: &str| source.len() - rest.len();
+    let (mut input, _) = char('{')(source)?;
+    let mut spans = Vec::new();
+    loop {
+        let (rest, _) = ws(input)?;
+        if let Ok((rest, _)) = char::<_, nom::error::Error<&str>>('}')(rest) {
+            return Ok((rest, spans));
+        }
+        let (rest, path) = field_head(rest)?;
+        let rest = match pair(char::<_, nom::error::Error<&str>>('='), ws)(rest) {
+            Ok((start, _)) => {
+                let (end, _) = value(start)?;
+                spans.push((path, offset(start)..offset(end)));
+                end
+            }
+            Err(_) => rest,
+        };
+        let (rest, _) = ws(rest)?;
+        input = match char::<_, nom::error::Error<&str>>(',')(rest) {
+            Ok((rest, _)) => rest,
+            Err(_) => {
+                let (rest, _) = peek(char('}'))(rest)?;
+                rest
+            }
+        };
+    }
+}
+
+fn field_name(input: &str) -> IResult<&str, String> {
+    alt((string, map(identifier, str::to_string)))(input)
+}
+
+/// Add a field, merging dotted paths into nested records
+fn insert(fields: &mut Vec<(String, Value)>, path: &[String], value: Value) {
+    let (key, rest) = path.split_first().expect("field paths are not empty");
+    if rest.is_empty() {
+        match fields.iter_mut().find(|(name, _)| name == key) {
+            Some((_, existing)) => *existing = value,
+            None => fields.push((key.clone(), value)),
+        }
+        return;
+    }
+    if !fields.iter().any(|(name, value)| name == key && matches!(value, Value::Record(_))) {
+        fields.retain(|(name, _)| name != key);
+        fields.push((key.clone(), Value::Record(Vec::new())));
+    }
+    if let Some((_, Value::Record(nested))) = fields.iter_mut().find(|(name, _)| name == key) {
+        insert(nested, rest, value);
+    }
+}
+
+fn value(input: &str) -> IResult<&str, Value> {
+    alt((
+        record,
+        list,
+        map(multiline_string, Value::String),
+        map(string, Value::String),
+        map(preceded(char('\''), identifier), |tag| Value::Tag(tag.to_string())),
+        literal,
+        map(|i| expr_until(i, &[',', '}', ']']), |expr| Value::Expr(expr.trim().to_string())),
+    ))(input)
+}
+
+/// `[ value, ... ]`
+fn list(input: &str) -> IResult<&str, Value> {
+    let (mut input, _) = char('[')(input)?;
+    let mut items = Vec::new();
+    loop {
+        let (rest, _) = ws(input)?;
+        if let Ok((rest, _)) = char::<_, nom::error::Error<&str>>(']')(rest) {
+            return Ok((rest, Value::List(items)));
+        }
+        let (rest, item) = value(rest)?;
+        items.push(item);
+        let (rest, _) = ws(rest)?;
+        input = match char::<_, nom::error::Error<&str>>(',')(rest) {
+            Ok((rest, _)) => rest,
+            Err(_) => {
+                let (rest, _) = peek(char(']'))(rest)?;
+                rest
+            }
+        };
+    }
+}
+
+/// Numbers, booleans and `null`, when nothing follows them but a delimiter
+fn literal(input: &str) -> IResult<&str, Value> {
+    let (input, value) = alt((
+        map(tag("null"), |_| Value::Null),
+        map(tag("true"), |_| Value::Bool(true)),
+        map(tag("false"), |_| Value::Bool(false)),
+        map(double, Value::Number),
+    ))(input)?;
+    let (_, _) = peek(preceded(ws, alt((char(','), char('}'), char(']')))))(input)?;
+    Ok((input, value))
+}
+
+/// `"..."`, with the usual escapes; `%{}` interpolations are kept as written
+fn string(input: &str) -> IResult<&str, String> {
+    let (mut input, _) = char('"')(input)?;
+    let mut text = String::new();
+    loop {
+        let mut chars = input.chars();
+        match chars.next() {
+            Some('"') => return Ok((chars.as_str(), text)),
+            Some('\\') => {
+                match chars.next() {
+                    Some('n') => text.push('\n'),
+                    Some('t') => text.push('\t'),
+                    Some('r') => text.push('\r'),
+                    Some(c) => text.push(c),
+                    None => break,
+                }
+            }
+            Some(c) => text.push(c),
+            None => break,
+        }
+        input = chars.as_str();
+    }
+    Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Char)))
+}
+
+/// `m%"..."%` (any number of `%`), with common indentation removed
+fn multiline_string(input: &str) -> IResult<&str, String> {
+    let (input, _) = char('m')(input)?;
+    let (input, percents) = take_while1(|c| c == '%')(input)?;
+    let (input, _) = char('"')(input)?;
+    let close = format!("\"{}", percents);
+    let Some(end) = input.find(&close) else {
+        return Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::TakeUntil)));
+    };
+    Ok((&input[end + close.len()..], dedent(&input[..end])))
+}
+
+fn dedent(text: &str) -> String {
+    let text = text.strip_prefix('\n').unwrap_or(text);
+    let text = match text.rfind('\n') {
+        Some(last) if text[last + 1..].trim().is_empty() => &text[..last],
+        _ => text,
+    };
+    let indent = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    text.lines()
+        .map(|line| line.get(indent..).unwrap_or(line.trim_start()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Source text up to one of `ends` outside brackets, strings and comments
+fn expr_until<'a>(input: &'a str, ends: &[char]) -> IResult<&'a str, &'a str> {
+    let mut depth = 0usize;
+    let mut rest = input;
+    while let Some(c) = rest.chars().next() {
+        if depth == 0 && ends.contains(&c) {
+            break;
+        }
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => match depth.checked_sub(1) {
+                Some(d) => depth = d,
+                None => break,
+            },
+            '"' => {
+                rest = string(rest)?.0;
+                continue;
+            }
+            'm' if rest.starts_with("m%") => {
+                if let Ok((after, _)) = multiline_string(rest) {
+                    rest = after;
+                    continue;
+                }
+            }
+            '#' => {
+                rest = not_line_ending(rest)?.0;
+                continue;
+            }
+            _ => {}
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+
+    let expr = &input[..input.len() - rest.len()];
+    if expr.trim().is_empty() {
+        return Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::TakeUntil)));
+    }
+    Ok((rest, expr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WRAPPER: &str = r#"# A wrapper
+{
+  pedigree = { name = "freelance", version = "1.0.0" },
+  security.trust_level = 'Hunt,  # signed
+  validation = {
+    schema = "../schemas/nuj.a2ml",
+    required_clauses = ["source-protection", "kill-fee",],
+    union_clauses = { nuj = ["right-of-reply"] },
+    checksum | optional = null,
+  },
+  contracts = {
+    kill_fee_minimum = fun contract =>
+      let pct = contract.payment.kill_fee_percent in
+      std.contract.from_predicate (fun p => p >= 50.0),
+  },
+  recipes = {
+    validate = m%"
+      echo "${self.payload_file}"
+        done
+    "%,
+  },
+  memory_min_mb = 10,
+  payload_file = "../contracts/freelance.a2ml",
+}
+"#;
+
+    #[test]
+    fn test_reads_wrapper_subset() {
+        let wrapper = K9Wrapper::from_source(WRAPPER, Path::new("k9/freelance.k9.ncl")).unwrap();
+        assert_eq!(wrapper.name.as_deref(), Some("freelance"));
+        assert_eq!(wrapper.payload, Path::new("k9/../contracts/freelance.a2ml"));
+        assert_eq!(wrapper.schema.as_deref(), Some(Path::new("k9/../schemas/nuj.a2ml")));
+        assert_eq!(wrapper.required_clauses, ["source-protection", "kill-fee"]);
+        assert_eq!(wrapper.clauses_for_union("NUJ"), ["right-of-reply"]);
+        assert_eq!(wrapper.contracts.len(), 1);
+        assert_eq!(wrapper.contracts[0].0, "kill_fee_minimum");
+
+        let record = &wrapper.record;
+        assert_eq!(record.get("security.trust_level"), Some(&Value::Tag("Hunt".to_string())));
+        assert_eq!(record.get("validation.checksum"), Some(&Value::Null));
+        assert_eq!(record.get("memory_min_mb"), Some(&Value::Number(10.0)));
+        assert_eq!(
+            record.get("recipes.validate").and_then(Value::as_str),
+            Some("echo \"${self.payload_file}\"\n  done")
+        );
+        match record.get("contracts.kill_fee_minimum") {
+            Some(Value::Expr(expr)) => assert!(expr.starts_with("fun contract =>") && expr.ends_with("p >= 50.0)")),
+            other => panic!("expected an expression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_sets_validation_fields() {
+        let edited = set_validation_fields(WRAPPER, &[("checksum", "abc"), ("signed_by", "ed25519 K\"ey")]).unwrap();
+        assert!(edited.contains("checksum | optional = \"abc\","));
+        assert!(edited.contains("    signed_by = \"ed25519 K\\\"ey\",\n  },"));
+        let wrapper = K9Wrapper::from_source(&edited, Path::new("x.k9.ncl")).unwrap();
+        assert!(wrapper.requires_signature());
+        assert_eq!(wrapper.record.get("validation.signed_by").and_then(Value::as_str), Some("ed25519 K\"ey"));
+
+        let inline = set_validation_fields("{ validation = { schema = \"s\" }, payload_file = \"p\" }", &[("signature", "x")]).unwrap();
+        assert_eq!(inline, "{ validation = { schema = \"s\", signature = \"x\", }, payload_file = \"p\" }");
+        assert!(set_validation_fields("{ payload_file = \"p\" }", &[("signature", "x")]).is_err());
+    }
+
+    #[test]
+    fn test_errors_report_line() {
+        let error = parse("{\n  name = \"x\",\n  list = [1, 2\n}\n").unwrap_err().to_string();
+        assert!(error.contains("K9 line"), "{}", error);
+        assert!(K9Wrapper::from_source("{ pedigree = {} }", Path::new("x.k9.ncl")).is_err());
+    }
+}
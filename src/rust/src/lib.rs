@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Union Policy Parser - validate employment contracts against union standards
+//!
+//! The library behind the `union-policy-parser` command, for services that
+//! embed validation (intake forms, document pipelines). Parse an A2ML
+//! contract and schema, validate with a union's profile, then render the
+//! report or draft a grievance:
+//!
+//! ```no_run
+//! use union_policy_parser::{parse, parse_file, ReportRenderer, Union, UnionProfile, ValidationMode, Validator};
+//!
+//! # fn main() -> union_policy_parser::Result<()> {
+//! let schema = parse_file("schemas/nuj-code-of-ethics.a2ml")?;
+//! let contract = parse("# Commission\n\n## Source Protection\n\nSources are never disclosed.\n")?;
+//!
+//! let profile = UnionProfile::new(Union::Nuj);
+//! let validator = Validator::new(schema, ValidationMode::Checked).with_union(profile.union());
+//! let report = validator.validate(&contract, &profile.required_clauses());
+//!
+//! println!("{}", ReportRenderer::render_json(&report)?);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The items re-exported here are the stable API and follow semantic
+//! versioning. The modules also expose the building blocks the command uses
+//! (batch runs, caching, the concrete syntax tree, SARIF and HTML output);
+//! those may change in minor releases. Reports, findings and parsed
+//! documents implement `Serialize` and `Deserialize`.
+//!
+//! The command-line dependencies (clap, file watching, the language server)
+//! sit behind the default `cli` feature; library users can turn it off with
+//! `default-features = false`. The `ffi` feature adds a C ABI (the `ffi`
+//! module) and generates its header. On `wasm32` targets, the `wasm` module
+//! exposes the engine to JavaScript.
+
+pub mod audit_log;
+pub mod batch;
+pub mod cache;
+pub mod config;
+pub mod cst;
+pub mod diff;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod formatter;
+pub mod html;
+pub mod k9;
+pub mod markdown;
+pub mod parser;
+pub mod predicate;
+pub mod reporter;
+pub mod sarif;
+pub mod schemas;
+pub mod signing;
+pub mod text;
+pub mod validator;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
+
+use std::path::Path;
+
+pub use crate::error::{PolicyError, Result};
+pub use crate::parser::{A2mlDocument, Attestation, ContentBlock, Reference, Section};
+pub use crate::reporter::{GrievanceGenerator, ReportRenderer};
+pub use crate::schemas::{EmbeddedSchema, Union, UnionProfile};
+pub use crate::validator::{DocumentInfo, Finding, Severity, ValidationMode, ValidationReport, Validator};
+
+/// Parse A2ML source text
+pub fn parse(source: &str) -> Result<A2mlDocument> {
+    parser::parse_a2ml_string(source)
+}
+
+/// Parse an A2ML file; the document records its path
+pub fn parse_file(path: impl AsRef<Path>) -> Result<A2mlDocument> {
+    parser::parse_a2ml_file(path.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_round_trips_through_json() {
+        let schema = parse("# Schema\n\n## Source Protection\n\n**Attestation:** *Must* protect sources.\n").unwrap();
+        let contract = parse("# Commission\n\n## Payment\n\nAll rights assigned.\n").unwrap();
+
+        let profile = UnionProfile::new(Union::Nuj);
+        let validator = Validator::new(schema, ValidationMode::Lax).with_union(profile.union());
+        let report = validator.validate(&contract, &profile.required_clauses());
+
+        let json = serde_json::to_string(&report).unwrap();
+        let restored: ValidationReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.findings(), report.findings());
+        assert!(!restored.valid);
+    }
+}
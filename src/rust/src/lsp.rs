@@ -0,0 +1,777 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Language server for A2ML contracts (`lsp`)
+//!
+//! Speaks LSP over stdio, for VS Code, Neovim and other editors. Each open
+//! document is re-parsed and re-validated on every change, with the schema
+//! and union that `validate` would use for the same file (flags, then
+//! union-policy.toml), resolved when the document is opened.
+//!
+//! - Diagnostics: parse errors, validation findings, and `[n]` citations
+//!   with no `@refs` entry
+//! - Hover: the union rule and citation behind a finding, the rule for a
+//!   clause heading, and the `@refs` entry for a citation
+//! - Document symbols: the section tree, plus the directive blocks
+//! - Completion: clause IDs in headings, statutes in `@requires`, reference
+//!   IDs after `[`
+//! - Go to definition: from a `[n]` citation to its `@refs` entry
+//!
+//! Positions are UTF-16 columns, as the protocol specifies.
+
+use crate::error::{PolicyError, Result};
+use crate::parser::{parse_a2ml_string, A2mlDocument};
+use crate::schemas::Union;
+use crate::validator::{clause_matches, Finding, Severity, Validator};
+use lazy_static::lazy_static;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{Completion, DocumentSymbolRequest, GotoDefinition, HoverRequest, Request as _};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse, Diagnostic,
+    DiagnosticSeverity, DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse, Documentation, GotoDefinitionParams,
+    GotoDefinitionResponse, Hover, HoverContents, HoverParams, HoverProviderCapability, Location, MarkupContent,
+    MarkupKind, NumberOrString, OneOf, Position, PublishDiagnosticsParams, Range, ServerCapabilities, SymbolKind,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Uri,
+};
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// `source` of every diagnostic
+const SOURCE: &str = "union-policy-parser";
+
+lazy_static! {
+    static ref CITATION: Regex = Regex::new(r"\[(\d+)\]").unwrap();
+    static ref ERROR_LINE: Regex = Regex::new(r"at line (\d+)").unwrap();
+}
+
+/// What a document is validated against
+pub struct Rules {
+    validator: Validator,
+    required_clauses: Vec<String>,
+    union: Option<Union>,
+}
+
+impl Rules {
+    pub fn new(validator: Validator, required_clauses: Vec<String>, union: Option<Union>) -> Self {
+        Self {
+            validator,
+            required_clauses,
+            union,
+        }
+    }
+
+    fn schema(&self) -> &A2mlDocument {
+        self.validator.schema()
+    }
+
+    /// Every clause the rules ask for, with its default strength
+    fn clauses(&self) -> Vec<(String, Severity)> {
+        let mut clauses: Vec<(String, Severity)> =
+            self.required_clauses.iter().map(|c| (c.clone(), Severity::Must)).collect();
+        if let Some(union) = self.union {
+            clauses.extend(union.recommended_clauses().iter().map(|c| (c.to_string(), Severity::Should)));
+        }
+        clauses
+    }
+
+    /// The schema section behind a clause: heading, principle and attestation
+    fn explain(&self, clause: &str) -> Option<String> {
+        let schema = self.schema();
+        let section = schema.sections.iter().find(|s| clause_matches(&s.heading, clause))?;
+
+        let title = schema.sections.iter().find(|s| s.level == 1).map(|s| s.heading.as_str());
+        let mut text = match title {
+            Some(title) => format!("**{}** — {}", section.heading, title),
+            None => format!("**{}**", section.heading),
+        };
+        if let Some(principle) = section.content.iter().find_map(|block| match block {
+            crate::parser::ContentBlock::Paragraph(text) => Some(text),
+            _ => None,
+        }) {
+            text.push_str(&format!("\n\n{}", principle));
+        }
+        for attestation in &section.attestations {
+            text.push_str(&format!("\n\n{}", attestation.claim));
+        }
+        Some(text)
+    }
+}
+
+/// An open document and what was found in it
+struct Document {
+    text: String,
+    version: i32,
+    parsed: std::result::Result<A2mlDocument, String>,
+    findings: Vec<Finding>,
+    rules: Option<Rules>,
+    /// Why no rules could be loaded, shown as a diagnostic
+    rules_error: Option<String>,
+}
+
+impl Document {
+    fn new(text: String, version: i32, rules: anyhow::Result<Option<Rules>>) -> Self {
+        let (rules, rules_error) = match rules {
+            Ok(rules) => (rules, None),
+            Err(e) => (None, Some(format!("{:#}", e))),
+        };
+        let mut document = Self {
+            text,
+            version,
+            parsed: Err(String::new()),
+            findings: Vec::new(),
+            rules,
+            rules_error,
+        };
+        document.analyze();
+        document
+    }
+
+    fn update(&mut self, text: String, version: i32) {
+        self.text = text;
+        self.version = version;
+        self.analyze();
+    }
+
+    fn analyze(&mut self) {
+        self.parsed = parse_a2ml_string(&self.text).map_err(|e| e.to_string());
+        self.findings = match (&self.parsed, &self.rules) {
+            (Ok(doc), Some(rules)) => rules.validator.validate(doc, &rules.required_clauses).findings(),
+            _ => Vec::new(),
+        };
+    }
+
+    fn doc(&self) -> Option<&A2mlDocument> {
+        self.parsed.as_ref().ok()
+    }
+
+    /// 0-based line a finding is reported on: its own, its section's, or the first
+    fn finding_line(&self, finding: &Finding) -> u32 {
+        let section_line = || {
+            let heading = finding.location.as_deref()?;
+            let section = self.doc()?.sections.iter().find(|s| s.heading == heading)?;
+            Some(section.line_number)
+        };
+        finding.line.or_else(section_line).map_or(0, |line| line.saturating_sub(1) as u32)
+    }
+
+    fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        if let Some(error) = &self.rules_error {
+            diagnostics.push(diagnostic(
+                self.line_range(0),
+                DiagnosticSeverity::WARNING,
+                "a2ml/settings",
+                format!("No validation: {}", error),
+            ));
+        }
+
+        if let Err(message) = &self.parsed {
+            let line = parse_error_line(&self.text, message);
+            diagnostics.push(diagnostic(self.line_range(line), DiagnosticSeverity::ERROR, "a2ml/parse-error", message.clone()));
+        }
+
+        for finding in &self.findings {
+            let severity = match finding.severity {
+                Severity::Must => DiagnosticSeverity::ERROR,
+                Severity::Should => DiagnosticSeverity::WARNING,
+                Severity::Could => DiagnosticSeverity::INFORMATION,
+            };
+            let range = self.line_range(self.finding_line(finding));
+            diagnostics.push(diagnostic(range, severity, &finding.rule, finding.message.clone()));
+        }
+
+        // Citations that go nowhere
+        let refs = reference_lines(&self.text);
+        for (line, text) in self.text.lines().enumerate() {
+            if refs.iter().any(|(_, ref_line)| *ref_line == line) {
+                continue;
+            }
+            for capture in CITATION.captures_iter(text) {
+                let whole = capture.get(0).unwrap();
+                if !refs.iter().any(|(id, _)| *id == capture[1]) {
+                    diagnostics.push(diagnostic(
+                        span(line, text, whole.start(), whole.end()),
+                        DiagnosticSeverity::WARNING,
+                        "a2ml/unresolved-reference",
+                        format!("Citation {} has no @refs entry", whole.as_str()),
+                    ));
+                }
+            }
+        }
+
+        diagnostics
+    }
+
+    fn hover(&self, position: Position) -> Option<Hover> {
+        let line_text = self.text.lines().nth(position.line as usize)?;
+        let mut parts = Vec::new();
+
+        // The reference behind a citation
+        if let Some((id, range)) = citation_at(line_text, position) {
+            let reference = self.doc()?.references.iter().find(|r| r.id == id);
+            let text = match reference {
+                Some(reference) => match &reference.url {
+                    Some(url) => format!("**[{}]** {} <{}>", id, reference.text, url),
+                    None => format!("**[{}]** {}", id, reference.text),
+                },
+                None => format!("**[{}]** has no @refs entry", id),
+            };
+            return Some(markdown_hover(text, Some(range)));
+        }
+
+        // Findings reported on this line, with the rule behind each
+        for finding in self.findings.iter().filter(|f| self.finding_line(f) == position.line) {
+            let mut text = format!("**{}** `{}`: {}", finding.severity.as_str(), finding.rule, finding.message);
+            match (self.rules.as_ref(), finding_clause(&finding.rule)) {
+                (Some(rules), Some(clause)) => match rules.explain(clause) {
+                    Some(rule) => text.push_str(&format!("\n\n{}", rule)),
+                    None => push_help(&mut text, finding),
+                },
+                _ => push_help(&mut text, finding),
+            }
+            parts.push(text);
+        }
+
+        // The rule a clause heading satisfies
+        if parts.is_empty() {
+            let section = self.doc()?.sections.iter().find(|s| s.line_number == position.line as usize + 1)?;
+            let rules = self.rules.as_ref()?;
+            let (clause, severity) = rules.clauses().into_iter().find(|(c, _)| clause_matches(&section.heading, c))?;
+            let mut text = format!("Satisfies **{}** clause `{}`", severity.as_str(), clause);
+            if let Some(rule) = rules.explain(&clause) {
+                text.push_str(&format!("\n\n{}", rule));
+            }
+            parts.push(text);
+        }
+
+        Some(markdown_hover(parts.join("\n\n---\n\n"), None))
+    }
+
+    #[allow(deprecated)] // `DocumentSymbol::deprecated` must still be set
+    fn symbols(&self) -> Vec<DocumentSymbol> {
+        let Some(doc) = self.doc() else {
+            return Vec::new();
+        };
+        let lines: Vec<&str> = self.text.lines().collect();
+        let blocks = directive_blocks(&self.text);
+        let last_line = lines.len().saturating_sub(1);
+
+        let symbol = |name: String, detail: Option<String>, kind, start: usize, end: usize| DocumentSymbol {
+            name,
+            detail,
+            kind,
+            tags: None,
+            deprecated: None,
+            range: Range::new(
+                Position::new(start as u32, 0),
+                Position::new(end as u32, utf16_len(lines.get(end).copied().unwrap_or(""))),
+            ),
+            selection_range: span(start, lines.get(start).copied().unwrap_or(""), 0, lines.get(start).map_or(0, |l| l.len())),
+            children: None,
+        };
+
+        // Sections run to the next heading at the same or a higher level
+        let mut flat: Vec<(u8, DocumentSymbol)> = Vec::new();
+        for (index, section) in doc.sections.iter().enumerate() {
+            let start = section.line_number.saturating_sub(1);
+            let end = doc.sections[index + 1..]
+                .iter()
+                .find(|s| s.level <= section.level)
+                .map_or(last_line, |s| s.line_number.saturating_sub(2).max(start));
+            flat.push((section.level, symbol(section.heading.clone(), None, SymbolKind::NAMESPACE, start, end)));
+        }
+
+        // Nest by heading level
+        let mut roots: Vec<(u8, DocumentSymbol)> = Vec::new();
+        let mut stack: Vec<(u8, DocumentSymbol)> = Vec::new();
+        for (level, symbol) in flat {
+            while stack.last().is_some_and(|(top, _)| *top >= level) {
+                close(&mut stack, &mut roots);
+            }
+            stack.push((level, symbol));
+        }
+        while !stack.is_empty() {
+            close(&mut stack, &mut roots);
+        }
+        let mut symbols: Vec<DocumentSymbol> = roots.into_iter().map(|(_, symbol)| symbol).collect();
+
+        // Directive blocks go in the section they appear in
+        for (name, start, end) in blocks {
+            let mut block = symbol(format!("@{}", name), None, SymbolKind::PACKAGE, start, end);
+            if name == "refs" {
+                let children = reference_lines(&self.text)
+                    .into_iter()
+                    .filter(|(_, line)| (start..=end).contains(line))
+                    .map(|(id, line)| {
+                        let text = doc.references.iter().find(|r| r.id == id).map(|r| r.text.clone());
+                        symbol(format!("[{}]", id), text, SymbolKind::KEY, line, line)
+                    })
+                    .collect();
+                block.children = Some(children);
+            }
+            place(&mut symbols, block);
+        }
+
+        symbols.sort_by_key(|symbol| symbol.range.start.line);
+        symbols
+    }
+
+    fn completion(&self, position: Position) -> Vec<CompletionItem> {
+        let line_text = self.text.lines().nth(position.line as usize).unwrap_or("");
+        let prefix = &line_text[..byte_index(line_text, position.character)];
+        let block = directive_blocks(&self.text)
+            .into_iter()
+            .find(|(_, start, end)| (*start..=*end).contains(&(position.line as usize)))
+            .map(|(name, _, _)| name);
+
+        // Statutes in @requires, from the schema's own list
+        if block == Some("requires") && prefix.trim_start().starts_with('-') {
+            let Some(rules) = &self.rules else {
+                return Vec::new();
+            };
+            return rules
+                .schema()
+                .requirements
+                .iter()
+                .map(|statute| CompletionItem {
+                    label: statute.clone(),
+                    kind: Some(CompletionItemKind::REFERENCE),
+                    detail: Some("Required by the schema".to_string()),
+                    ..Default::default()
+                })
+                .collect();
+        }
+
+        // Reference IDs after `[`
+        if block != Some("refs") && prefix.rfind('[').is_some_and(|open| prefix[open + 1..].chars().all(|c| c.is_ascii_digit())) {
+            let Some(doc) = self.doc() else {
+                return Vec::new();
+            };
+            let closed = line_text[prefix.len()..].starts_with(']');
+            return doc
+                .references
+                .iter()
+                .filter(|r| !r.id.is_empty())
+                .map(|reference| CompletionItem {
+                    label: reference.id.clone(),
+                    kind: Some(CompletionItemKind::REFERENCE),
+                    detail: Some(reference.text.clone()),
+                    insert_text: Some(if closed { reference.id.clone() } else { format!("{}]", reference.id) }),
+                    ..Default::default()
+                })
+                .collect();
+        }
+
+        // Clause IDs in headings, missing ones first
+        if block.is_none() && prefix.starts_with('#') {
+            let Some(rules) = &self.rules else {
+                return Vec::new();
+            };
+            let sections = self.doc().map(|doc| doc.sections.as_slice()).unwrap_or_default();
+            return rules
+                .clauses()
+                .into_iter()
+                .map(|(clause, severity)| {
+                    let present = sections.iter().any(|s| clause_matches(&s.heading, &clause));
+                    let union = rules.union.map_or("schema", |u| u.as_str());
+                    CompletionItem {
+                        label: clause.clone(),
+                        kind: Some(CompletionItemKind::MODULE),
+                        detail: Some(format!(
+                            "{} clause ({}){}",
+                            severity.as_str(),
+                            union,
+                            if present { ", already present" } else { "" }
+                        )),
+                        documentation: rules.explain(&clause).map(|value| {
+                            Documentation::MarkupContent(MarkupContent { kind: MarkupKind::Markdown, value })
+                        }),
+                        insert_text: Some(heading_text(&clause)),
+                        filter_text: Some(clause.clone()),
+                        sort_text: Some(format!("{}{:?}{}", present as u8, severity, clause)),
+                        ..Default::default()
+                    }
+                })
+                .collect();
+        }
+
+        Vec::new()
+    }
+
+    fn definition(&self, uri: &Uri, position: Position) -> Option<Location> {
+        let line_text = self.text.lines().nth(position.line as usize)?;
+        let (id, _) = citation_at(line_text, position)?;
+        let (_, line) = reference_lines(&self.text).into_iter().find(|(ref_id, _)| *ref_id == id)?;
+        (line != position.line as usize).then(|| Location::new(uri.clone(), self.line_range(line as u32)))
+    }
+
+    /// Range covering the text of a line, without its indentation
+    fn line_range(&self, line: u32) -> Range {
+        let text = self.text.lines().nth(line as usize).unwrap_or("");
+        let start = text.len() - text.trim_start().len();
+        span(line as usize, text, start, text.trim_end().len().max(start))
+    }
+}
+
+/// Add a symbol under the innermost one containing it, in line order
+fn place(symbols: &mut Vec<DocumentSymbol>, symbol: DocumentSymbol) {
+    let line = symbol.range.start.line;
+    let parent = symbols
+        .iter_mut()
+        .find(|s| s.range.start.line < line && line <= s.range.end.line);
+    match parent {
+        Some(parent) => place(parent.children.get_or_insert_with(Vec::new), symbol),
+        None => {
+            symbols.push(symbol);
+            symbols.sort_by_key(|s| s.range.start.line);
+        }
+    }
+}
+
+fn close(stack: &mut Vec<(u8, DocumentSymbol)>, roots: &mut Vec<(u8, DocumentSymbol)>) {
+    let Some(done) = stack.pop() else {
+        return;
+    };
+    match stack.last_mut() {
+        Some((_, parent)) => parent.children.get_or_insert_with(Vec::new).push(done.1),
+        None => roots.push(done),
+    }
+}
+
+fn diagnostic(range: Range, severity: DiagnosticSeverity, rule: &str, message: String) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(severity),
+        code: Some(NumberOrString::String(rule.to_string())),
+        source: Some(SOURCE.to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+fn markdown_hover(value: String, range: Option<Range>) -> Hover {
+    Hover {
+        contents: HoverContents::Markup(MarkupContent { kind: MarkupKind::Markdown, value }),
+        range,
+    }
+}
+
+fn push_help(text: &mut String, finding: &Finding) {
+    if let Some(help) = &finding.help {
+        text.push_str(&format!("\n\n{}", help));
+    }
+}
+
+/// Clause ID of a `<prefix>/<clause>` rule; `None` for red flags and A2ML rules
+fn finding_clause(rule: &str) -> Option<&str> {
+    let (prefix, clause) = rule.split_once('/')?;
+    (prefix != "a2ml" && !clause.starts_with("red-flag/")).then_some(clause)
+}
+
+/// Heading text for a clause ID (`kill-fee` → `Kill Fee`)
+fn heading_text(clause: &str) -> String {
+    clause
+        .split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or(String::new(), |first| first.to_uppercase().chain(chars).collect())
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 0-based line of a parse error, from its message
+fn parse_error_line(text: &str, message: &str) -> u32 {
+    if let Some(line) = ERROR_LINE.captures(message).and_then(|c| c[1].parse::<u32>().ok()) {
+        return line.saturating_sub(1);
+    }
+    // "Parse error at: <the text that failed>"
+    message
+        .split_once("at: ")
+        .map(|(_, snippet)| snippet.lines().next().unwrap_or("").trim())
+        .filter(|snippet| !snippet.is_empty())
+        .and_then(|snippet| text.lines().position(|line| line.contains(snippet)))
+        .map_or(0, |line| line as u32)
+}
+
+/// `@abstract`, `@requires` and `@refs` blocks: name, first and last (`@end`) line
+fn directive_blocks(text: &str) -> Vec<(&'static str, usize, usize)> {
+    let mut blocks = Vec::new();
+    let mut open: Option<(&'static str, usize)> = None;
+    for (line, content) in text.lines().enumerate() {
+        let content = content.trim();
+        match open {
+            None => {
+                open = ["abstract", "requires", "refs"]
+                    .into_iter()
+                    .find(|name| content.strip_prefix('@').is_some_and(|rest| rest.starts_with(&format!("{}:", name))))
+                    .map(|name| (name, line));
+            }
+            Some((name, start)) if content.starts_with("@end") => {
+                blocks.push((name, start, line));
+                open = None;
+            }
+            Some(_) => {}
+        }
+    }
+    // An unfinished block runs to the end, as it does while being typed
+    if let Some((name, start)) = open {
+        blocks.push((name, start, text.lines().count().saturating_sub(1)));
+    }
+    blocks
+}
+
+/// Numbered `@refs` entries: ID and 0-based line
+fn reference_lines(text: &str) -> Vec<(String, usize)> {
+    let lines: Vec<&str> = text.lines().collect();
+    directive_blocks(text)
+        .into_iter()
+        .filter(|(name, _, _)| *name == "refs")
+        .flat_map(|(_, start, end)| start + 1..end.min(lines.len()))
+        .filter_map(|line| {
+            let id = lines[line].trim_start().strip_prefix('[')?.split_once(']')?.0;
+            (!id.is_empty() && id.chars().all(|c| c.is_ascii_digit())).then(|| (id.to_string(), line))
+        })
+        .collect()
+}
+
+/// The `[n]` citation under the cursor: its ID and range
+fn citation_at(line_text: &str, position: Position) -> Option<(String, Range)> {
+    let cursor = byte_index(line_text, position.character);
+    CITATION.captures_iter(line_text).find_map(|capture| {
+        let whole = capture.get(0)?;
+        (whole.start() <= cursor && cursor <= whole.end())
+            .then(|| (capture[1].to_string(), span(position.line as usize, line_text, whole.start(), whole.end())))
+    })
+}
+
+/// Range of bytes `start..end` of a line
+fn span(line: usize, text: &str, start: usize, end: usize) -> Range {
+    Range::new(
+        Position::new(line as u32, utf16_len(&text[..start])),
+        Position::new(line as u32, utf16_len(&text[..end])),
+    )
+}
+
+fn utf16_len(text: &str) -> u32 {
+    text.encode_utf16().count() as u32
+}
+
+/// Byte offset of a UTF-16 column, clamped to the line
+fn byte_index(text: &str, column: u32) -> usize {
+    let mut units = 0;
+    for (index, c) in text.char_indices() {
+        if units >= column {
+            return index;
+        }
+        units += c.len_utf16() as u32;
+    }
+    text.len()
+}
+
+/// Local path of a `file://` URI
+fn uri_path(uri: &Uri) -> Option<PathBuf> {
+    let path = uri.as_str().strip_prefix("file://")?;
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match (bytes[i], bytes.get(i + 1..i + 3)) {
+            (b'%', Some(hex)) => match u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16) {
+                Ok(byte) => {
+                    decoded.push(byte);
+                    i += 3;
+                }
+                Err(_) => return None,
+            },
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    Some(PathBuf::from(String::from_utf8(decoded).ok()?))
+}
+
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["[".to_string(), "#".to_string(), "-".to_string()]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn protocol(e: impl std::fmt::Display) -> PolicyError {
+    PolicyError::ValidationError(format!("Language server protocol error: {}", e))
+}
+
+/// Serve LSP on stdin/stdout until the client shuts the server down
+///
+/// `rules_for` gives the rules for a document's path, or `None` when no
+/// schema applies; documents without rules still get parse diagnostics,
+/// symbols, reference completion and go-to-definition.
+pub fn run(rules_for: impl Fn(&Path) -> anyhow::Result<Option<Rules>>) -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    connection
+        .initialize(serde_json::to_value(capabilities())?)
+        .map_err(protocol)?;
+    log::info!("Language server ready");
+
+    let mut documents: HashMap<String, Document> = HashMap::new();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request).map_err(protocol)? {
+                    break;
+                }
+                let response = respond(&documents, request);
+                connection.sender.send(Message::Response(response)).map_err(protocol)?;
+            }
+            Message::Notification(notification) => {
+                if let Some(uri) = on_notification(&mut documents, notification, &rules_for) {
+                    let (diagnostics, version) = match documents.get(uri.as_str()) {
+                        Some(document) => (document.diagnostics(), Some(document.version)),
+                        None => (Vec::new(), None),
+                    };
+                    let params = PublishDiagnosticsParams { uri, diagnostics, version };
+                    let notification = Notification::new(PublishDiagnostics::METHOD.to_string(), params);
+                    connection.sender.send(Message::Notification(notification)).map_err(protocol)?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+/// Apply a document notification; returns the document whose diagnostics changed
+fn on_notification(
+    documents: &mut HashMap<String, Document>,
+    notification: Notification,
+    rules_for: &impl Fn(&Path) -> anyhow::Result<Option<Rules>>,
+) -> Option<Uri> {
+    match notification.method.as_str() {
+        DidOpenTextDocument::METHOD => {
+            let params: DidOpenTextDocumentParams = serde_json::from_value(notification.params).ok()?;
+            let item = params.text_document;
+            let rules = match uri_path(&item.uri) {
+                Some(path) => rules_for(&path),
+                None => Ok(None),
+            };
+            documents.insert(item.uri.to_string(), Document::new(item.text, item.version, rules));
+            Some(item.uri)
+        }
+        DidChangeTextDocument::METHOD => {
+            let params: DidChangeTextDocumentParams = serde_json::from_value(notification.params).ok()?;
+            // Full sync: the last change holds the whole text
+            let text = params.content_changes.into_iter().last()?.text;
+            let document = documents.get_mut(params.text_document.uri.as_str())?;
+            document.update(text, params.text_document.version);
+            Some(params.text_document.uri)
+        }
+        DidCloseTextDocument::METHOD => {
+            let params: DidCloseTextDocumentParams = serde_json::from_value(notification.params).ok()?;
+            documents.remove(params.text_document.uri.as_str());
+            Some(params.text_document.uri)
+        }
+        _ => None,
+    }
+}
+
+fn respond(documents: &HashMap<String, Document>, request: Request) -> Response {
+    fn reply<P: serde::de::DeserializeOwned, R: serde::Serialize>(
+        request: Request,
+        handle: impl FnOnce(P) -> R,
+    ) -> Response {
+        match serde_json::from_value::<P>(request.params) {
+            Ok(params) => Response::new_ok(request.id, handle(params)),
+            Err(e) => Response::new_err(request.id, ErrorCode::InvalidParams as i32, e.to_string()),
+        }
+    }
+
+    match request.method.as_str() {
+        HoverRequest::METHOD => reply(request, |params: HoverParams| {
+            let at = params.text_document_position_params;
+            documents.get(at.text_document.uri.as_str()).and_then(|d| d.hover(at.position))
+        }),
+        DocumentSymbolRequest::METHOD => reply(request, |params: DocumentSymbolParams| {
+            let symbols = documents.get(params.text_document.uri.as_str()).map(Document::symbols).unwrap_or_default();
+            DocumentSymbolResponse::Nested(symbols)
+        }),
+        Completion::METHOD => reply(request, |params: CompletionParams| {
+            let at = params.text_document_position;
+            let items = documents.get(at.text_document.uri.as_str()).map(|d| d.completion(at.position)).unwrap_or_default();
+            CompletionResponse::Array(items)
+        }),
+        GotoDefinition::METHOD => reply(request, |params: GotoDefinitionParams| {
+            let at = params.text_document_position_params;
+            documents
+                .get(at.text_document.uri.as_str())
+                .and_then(|d| d.definition(&at.text_document.uri, at.position))
+                .map(GotoDefinitionResponse::Scalar)
+        }),
+        method => Response::new_err(request.id, ErrorCode::MethodNotFound as i32, format!("Unsupported method: {}", method)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTRACT: &str = "# Commission\n\n@abstract:\nA feature.\n@end\n\n## Payment Terms\n\nNET 30 days [1].\n\n### Kill Fee\n\nHalf the fee [2], per [9].\n\n@refs:\n[1] Late Payment Act 1998\n[2] NUJ Freelance Fees Guide\n@end\n";
+
+    #[test]
+    fn test_citations_and_symbols() {
+        let document = Document::new(CONTRACT.to_string(), 1, Ok(None));
+        let uri: Uri = "file:///tmp/c%20d.a2ml".parse().unwrap();
+        assert_eq!(uri_path(&uri), Some(PathBuf::from("/tmp/c d.a2ml")));
+
+        // `[1]` on line 8 goes to its entry on line 15
+        let location = document.definition(&uri, Position::new(8, 13)).unwrap();
+        assert_eq!(location.range.start.line, 15);
+        assert!(document.definition(&uri, Position::new(8, 2)).is_none());
+
+        let unresolved: Vec<_> = document
+            .diagnostics()
+            .into_iter()
+            .filter(|d| d.code == Some(NumberOrString::String("a2ml/unresolved-reference".into())))
+            .collect();
+        assert_eq!(unresolved.len(), 1);
+        assert_eq!(unresolved[0].range.start, Position::new(12, 22));
+
+        // Commission > [@abstract, Payment Terms > Kill Fee > @refs > [1], [2]]
+        let symbols = document.symbols();
+        assert_eq!(symbols.len(), 1);
+        let children = symbols[0].children.as_ref().unwrap();
+        let names: Vec<_> = children.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["@abstract", "Payment Terms"]);
+        let kill_fee = &children[1].children.as_ref().unwrap()[0];
+        assert_eq!(kill_fee.name, "Kill Fee");
+        let refs = &kill_fee.children.as_ref().unwrap()[0];
+        assert_eq!(refs.children.as_ref().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_positions_are_utf16() {
+        let line = "“Fee” [1]";
+        assert_eq!(byte_index(line, 6), "“Fee” ".len());
+        let (id, range) = citation_at(line, Position::new(0, 8)).unwrap();
+        assert_eq!(id, "1");
+        assert_eq!((range.start.character, range.end.character), (6, 9));
+        assert_eq!(heading_text("kill-fee"), "Kill Fee");
+        assert_eq!(parse_error_line("a\nb\n", "Unexpected content at line 2: b"), 1);
+    }
+}
@@ -4,22 +4,46 @@
 //! Supports: NUJ (National Union of Journalists), IWW (Industrial Workers of the World),
 //! UCU (University and College Union), and general UK employment law compliance.
 
-use clap::{Parser, Subcommand};
-use anyhow::Result;
-use std::path::PathBuf;
-use std::fs;
-
-mod parser;
-mod validator;
-mod reporter;
-mod schemas;
-mod error;
-
-use crate::error::PolicyError;
-use crate::parser::{parse_a2ml_file, parse_a2ml_string};
-use crate::validator::{Validator, ValidationMode as ValidatorMode};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::io::IsTerminal;
+use std::process::ExitCode;
+
+mod discovery;
+mod exit;
+mod output;
+mod lsp;
+mod stdio;
+mod watch;
+
+// The library's modules, so CLI modules reach them as `crate::...`
+use union_policy_parser::{audit_log, batch, cache, config, diff, error, formatter, k9, markdown, html, parser, predicate, reporter, sarif, schemas, signing, validator};
+
+use crate::audit_log::{AuditLog, LogEntry};
+use crate::batch::{BatchEntry, BatchSummary};
+use crate::cache::Cache;
+use crate::config::PolicyConfig;
+use crate::diff::Change;
+use crate::discovery::{Discovered, DiscoveryOptions};
+use crate::exit::{FailOn, Status};
+use crate::k9::K9Wrapper;
+use crate::output::{Output, OutputMode};
+use crate::parser::A2mlDocument;
+use crate::predicate::{Outcome, Predicate};
+use crate::validator::{clause_matches, find_red_flags, DocumentInfo, ValidationReport, Validator, ValidationMode as ValidatorMode};
 use crate::reporter::{GrievanceGenerator, ReportRenderer};
-use crate::schemas::Union;
+use crate::schemas::{EmbeddedSchema, Union, EMBEDDED_SCHEMAS};
+use crate::signing::{PublicKey, SecretKey, Signature, SignedKind};
+use serde_json::json;
+
+/// Print human output; silent under `--output-format json|ndjson`
+macro_rules! say {
+    ($out:expr, $($arg:tt)*) => {
+        $out.say(format_args!($($arg)*))
+    };
+}
 
 /// Union Policy Parser - Validate contracts against union ethics and employment law
 #[derive(Parser)]
@@ -36,12 +60,28 @@ Examples:
   # Validate a contract
   union-policy-parser validate contract.a2ml --schema schemas/nuj-ethics.a2ml
 
+  # Validate against a union's built-in schema
+  union-policy-parser validate contract.a2ml --union nuj
+
   # Generate grievance for violations
   union-policy-parser grievance contract.a2ml --violation source-protection
 
   # Batch validate multiple contracts
   union-policy-parser batch contracts/ --schema nuj-ethics.a2ml --output report.json
 
+Defaults for --schema, --union, --mode, required clauses, red flags and output
+formats are read from the nearest union-policy.toml above the working directory.
+Command-line flags take precedence. With --union and no schema, the union's
+schema is used: schemas/<name>.a2ml if present, else the copy built into the
+tool (see `schemas list`).
+
+Exit codes:
+  0  clean (or nothing at the --fail-on level)
+  1  MUST violations, including red flags
+  2  SHOULD-level findings only (with --fail-on=warning)
+  3  a contract or schema could not be parsed
+  4  usage or IO error
+
 License: PMPL-1.0-or-later (Palimpsest Mozilla Public License)
 "#)]
 struct Cli {
@@ -52,23 +92,39 @@ struct Cli {
     /// Enable verbose logging
     #[arg(short, long, global = true)]
     verbose: bool,
+
+    /// Configuration file (default: nearest union-policy.toml)
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Findings that make the exit status non-zero [default: error]
+    #[arg(long, global = true, value_name = "LEVEL")]
+    fail_on: Option<FailOn>,
+
+    /// Result format on stdout; logs always go to stderr
+    #[arg(long, global = true, value_name = "FORMAT", default_value = "human")]
+    output_format: OutputMode,
+
+    /// Contract ID shown in reports (default: the path, or <stdin> for `-`)
+    #[arg(long, global = true, value_name = "ID")]
+    name: Option<String>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Validate a contract against a schema
     Validate {
-        /// Path to A2ML contract file
+        /// Path to A2ML contract file (`-` for stdin), or a K9 wrapper (`.k9.ncl`)
         #[arg(value_name = "CONTRACT")]
         contract: PathBuf,
 
-        /// Path to A2ML schema file (e.g., nuj-code-of-ethics.a2ml)
+        /// Path to A2ML schema file (default: the union's schema)
         #[arg(short, long, value_name = "SCHEMA")]
-        schema: PathBuf,
+        schema: Option<PathBuf>,
 
-        /// Validation mode: lax, checked, or attested
-        #[arg(short, long, default_value = "checked")]
-        mode: ValidationMode,
+        /// Validation mode: lax, checked, or attested [default: checked]
+        #[arg(short, long)]
+        mode: Option<ValidationMode>,
 
         /// Union to validate for (nuj, iww, ucu)
         #[arg(short, long, value_name = "UNION")]
@@ -78,33 +134,58 @@ enum Commands {
         #[arg(long, value_delimiter = ',')]
         required_clauses: Vec<String>,
 
-        /// Exit with error code if validation fails
+        /// Fail on SHOULD findings too; shorthand for --fail-on=warning
         #[arg(long)]
         strict: bool,
+
+        /// Also write findings as SARIF 2.1.0 to this file (`-` for stdout)
+        #[arg(long, value_name = "FILE")]
+        sarif: Option<PathBuf>,
+
+        /// Re-validate whenever the contract, schema or K9 wrapper changes, showing fixed and new findings
+        #[arg(long)]
+        watch: bool,
+
+        /// Append this run (with --watch, every run) to an audit log (see `log`)
+        #[arg(long, value_name = "FILE")]
+        log: Option<PathBuf>,
     },
 
     /// Generate an audit report
     Audit {
-        /// Path to A2ML contract file
+        /// Path to A2ML contract file (`-` for stdin)
         #[arg(value_name = "CONTRACT")]
         contract: PathBuf,
 
         /// Path to A2ML schema file
         #[arg(short, long, value_name = "SCHEMA")]
-        schema: PathBuf,
+        schema: Option<PathBuf>,
 
-        /// Output file path (JSON format)
-        #[arg(short, long, value_name = "FILE")]
+        /// Output file path (`-` for stdout)
+        #[arg(short, long, value_name = "FILE", default_value = "-")]
         output: PathBuf,
 
         /// Union to audit for
         #[arg(short, long)]
         union: Option<String>,
+
+        /// Report format (json, html, markdown, sarif) [default: json]
+        #[arg(short, long)]
+        format: Option<ReportFormat>,
+
+        /// Audit log to append this run to [default: `audit_log` in
+        /// union-policy.toml; without either the run is not logged]
+        #[arg(long, value_name = "FILE")]
+        log: Option<PathBuf>,
+
+        /// Do not append this run to the audit log set in union-policy.toml
+        #[arg(long, conflicts_with = "log")]
+        no_log: bool,
     },
 
     /// Auto-generate a grievance letter for violations
     Grievance {
-        /// Path to A2ML contract file
+        /// Path to A2ML contract file (`-` for stdin)
         #[arg(value_name = "CONTRACT")]
         contract: PathBuf,
 
@@ -116,8 +197,8 @@ enum Commands {
         #[arg(short, long, value_name = "TEMPLATE")]
         template: Option<PathBuf>,
 
-        /// Output file path
-        #[arg(short, long, value_name = "FILE")]
+        /// Output file path (`-` for stdout)
+        #[arg(short, long, value_name = "FILE", default_value = "-")]
         output: PathBuf,
 
         /// Union context (nuj, iww, ucu)
@@ -130,31 +211,66 @@ enum Commands {
     },
 
     /// Batch validate multiple contracts
+    ///
+    /// Files matched by .gitignore or .uppignore (same syntax) are skipped;
+    /// use them to keep drafts and archived contracts out of the stats.
     Batch {
-        /// Directory containing A2ML contract files
+        /// Directory containing A2ML contract files (`-` reads paths from stdin, one per line)
         #[arg(value_name = "DIR")]
         dir: PathBuf,
 
         /// Path to A2ML schema file
         #[arg(short, long, value_name = "SCHEMA")]
-        schema: PathBuf,
+        schema: Option<PathBuf>,
 
-        /// Output report file (JSON format)
-        #[arg(short, long, value_name = "FILE")]
+        /// Output report file (`-` for stdout)
+        #[arg(short, long, value_name = "FILE", default_value = "-")]
         output: PathBuf,
 
         /// Union to validate for
         #[arg(short, long)]
         union: Option<String>,
 
-        /// Validation mode
-        #[arg(short, long, default_value = "checked")]
-        mode: ValidationMode,
+        /// Validation mode [default: checked]
+        #[arg(short, long)]
+        mode: Option<ValidationMode>,
+
+        /// Report format (json, csv, junit, markdown, sarif) [default: json]
+        #[arg(short, long)]
+        format: Option<BatchFormat>,
+
+        /// Worker threads (default: one per CPU)
+        #[arg(short, long, value_name = "N")]
+        jobs: Option<usize>,
+
+        /// Only validate files matching this glob (repeatable; default "*.a2ml")
+        #[arg(long, value_name = "GLOB")]
+        include: Vec<String>,
+
+        /// Skip files and directories matching this glob (repeatable)
+        #[arg(long, value_name = "GLOB")]
+        exclude: Vec<String>,
+
+        /// Also validate the A2ML payloads of *.k9.ncl wrappers, with their clause lists and schema
+        #[arg(long)]
+        k9: bool,
+
+        /// Cache directory for results of unchanged files (default: the user cache directory)
+        #[arg(long, value_name = "DIR")]
+        cache_dir: Option<PathBuf>,
+
+        /// Re-validate every file, without reading or writing the cache
+        #[arg(long, conflicts_with = "cache_dir")]
+        no_cache: bool,
+
+        /// Keep running, re-validating only contracts that change (or whose schema changes)
+        #[arg(long)]
+        watch: bool,
     },
 
     /// Check a specific clause value
     CheckClause {
-        /// Path to A2ML contract file
+        /// Path to A2ML contract file (`-` for stdin)
         #[arg(value_name = "CONTRACT")]
         contract: PathBuf,
 
@@ -178,18 +294,18 @@ enum Commands {
         #[arg(long, value_delimiter = ',')]
         allowed: Vec<String>,
 
-        /// Exit with error if check fails
+        /// Treat a missing clause as a MUST violation (exit 1)
         #[arg(long)]
         error_if_not: bool,
 
-        /// Warn if check fails (instead of error)
-        #[arg(long)]
+        /// Treat a missing clause as a SHOULD finding (exit 2 unless --fail-on is given)
+        #[arg(long, conflicts_with = "error_if_not")]
         warn_if_not: bool,
     },
 
     /// Get a clause value
     GetClause {
-        /// Path to A2ML contract file
+        /// Path to A2ML contract file (`-` for stdin)
         #[arg(value_name = "CONTRACT")]
         contract: PathBuf,
 
@@ -200,7 +316,7 @@ enum Commands {
 
     /// Scan for red flag keywords (exploitative clauses)
     ScanRedFlags {
-        /// Path to A2ML contract file
+        /// Path to A2ML contract file (`-` for stdin)
         #[arg(value_name = "CONTRACT")]
         contract: PathBuf,
 
@@ -211,36 +327,211 @@ enum Commands {
         /// Case-insensitive matching
         #[arg(short = 'i', long)]
         case_insensitive: bool,
+
+        /// Also write findings as SARIF 2.1.0 to this file (`-` for stdout)
+        #[arg(long, value_name = "FILE")]
+        sarif: Option<PathBuf>,
     },
 
     /// Render contract to HTML/Markdown
     Render {
-        /// Path to A2ML contract file
+        /// Path to A2ML contract file (`-` for stdin)
         #[arg(value_name = "CONTRACT")]
         contract: PathBuf,
 
-        /// Output format (html, markdown, json)
-        #[arg(short, long, default_value = "html")]
-        format: OutputFormat,
+        /// Output format (html, markdown, json) [default: html]
+        #[arg(short, long)]
+        format: Option<OutputFormat>,
 
-        /// Output file path
-        #[arg(short, long, value_name = "FILE")]
+        /// Output file path (`-` for stdout)
+        #[arg(short, long, value_name = "FILE", default_value = "-")]
         output: PathBuf,
 
         /// Template file (optional)
         #[arg(short, long)]
         template: Option<PathBuf>,
+
+        /// Validate against this schema and annotate findings in the output
+        #[arg(short, long, value_name = "SCHEMA")]
+        schema: Option<PathBuf>,
+
+        /// Union whose required clauses and red flags to annotate
+        #[arg(short, long)]
+        union: Option<String>,
+    },
+
+    /// Compare two versions of a contract, clause by clause
+    ///
+    /// Sections are aligned by heading, then by clause number. Both versions
+    /// are validated, and findings the new version fixed or introduced are
+    /// shown; the exit status reflects only the introduced ones.
+    Diff {
+        /// Earlier version (`-` for stdin)
+        #[arg(value_name = "OLD")]
+        old: PathBuf,
+
+        /// Later version
+        #[arg(value_name = "NEW")]
+        new: PathBuf,
+
+        /// Path to A2ML schema file (default: the union's schema)
+        #[arg(short, long, value_name = "SCHEMA")]
+        schema: Option<PathBuf>,
+
+        /// Union to validate for (nuj, iww, ucu)
+        #[arg(short, long, value_name = "UNION")]
+        union: Option<String>,
+
+        /// Validation mode: lax, checked, or attested [default: checked]
+        #[arg(short, long)]
+        mode: Option<ValidationMode>,
     },
 
     /// Check schema validity
     CheckSchema {
-        /// Path to A2ML schema file
+        /// Path to A2ML schema file (`-` for stdin)
         #[arg(value_name = "SCHEMA")]
         schema: PathBuf,
     },
+
+    /// Rewrite A2ML files in canonical layout
+    Fmt {
+        /// A2ML files to format in place (`-` formats stdin to stdout)
+        #[arg(value_name = "FILE", required = true)]
+        files: Vec<PathBuf>,
+
+        /// Report files that are not canonically formatted, without writing
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Create an Ed25519 key pair for signing contracts and reports
+    Keygen {
+        /// Secret key file to write; the public key goes to FILE.pub
+        #[arg(value_name = "FILE")]
+        output: PathBuf,
+
+        /// Overwrite existing key files
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Sign a contract, K9 wrapper or JSON audit report
+    ///
+    /// A wrapper's payload is signed into its `validation` block (checksum,
+    /// signature, signed_by); anything else gets a detached FILE.sig.
+    Sign {
+        /// A2ML contract, `.k9.ncl` wrapper or `.json` report
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Secret key file (from `keygen`)
+        #[arg(short, long, value_name = "KEY")]
+        key: PathBuf,
+
+        /// For a wrapper, write PAYLOAD.sig instead of editing the wrapper
+        #[arg(long)]
+        detached: bool,
+    },
+
+    /// Check the signature on a contract, K9 wrapper or JSON audit report
+    Verify {
+        /// A2ML contract, `.k9.ncl` wrapper or `.json` report
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Public key (`.pub` file) the signature must be from [default:
+        /// the trusted_keys in union-policy.toml]
+        #[arg(short, long, value_name = "KEY")]
+        key: Option<PathBuf>,
+
+        /// Detached signature [default: the wrapper's `validation` block, else FILE.sig]
+        #[arg(short, long, value_name = "SIG")]
+        signature: Option<PathBuf>,
+    },
+
+    /// Show or check the audit log of validation runs
+    ///
+    /// Every `audit` (and `validate --log`) appends a line holding the
+    /// SHA-256 of the line before it, so edits and deletions are detectable.
+    Log {
+        #[command(subcommand)]
+        action: LogAction,
+    },
+
+    /// List, show or export the schemas built into the tool
+    Schemas {
+        #[command(subcommand)]
+        action: SchemasAction,
+    },
+
+    /// Run a language server on stdin/stdout, for editors
+    Lsp {
+        /// Path to A2ML schema file (default: `schema` in union-policy.toml)
+        #[arg(short, long, value_name = "SCHEMA")]
+        schema: Option<PathBuf>,
+
+        /// Union to validate for (nuj, iww, ucu)
+        #[arg(short, long, value_name = "UNION")]
+        union: Option<String>,
+
+        /// Validation mode: lax, checked, or attested [default: checked]
+        #[arg(short, long)]
+        mode: Option<ValidationMode>,
+    },
 }
 
-#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+#[derive(Subcommand)]
+enum LogAction {
+    /// Show logged runs, grouped by contract
+    Show {
+        /// Only runs on this contract (as given when it was validated)
+        #[arg(value_name = "CONTRACT")]
+        contract: Option<String>,
+
+        /// Audit log [default: as for `audit`]
+        #[arg(long, value_name = "FILE")]
+        log: Option<PathBuf>,
+    },
+
+    /// Check that no entry has been edited or removed
+    Verify {
+        /// Audit log [default: as for `audit`]
+        #[arg(long, value_name = "FILE")]
+        log: Option<PathBuf>,
+
+        /// Head hash reported by an earlier `log verify`; fails if it is no
+        /// longer in the log (entries removed from the end)
+        #[arg(long, value_name = "SHA256")]
+        head: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SchemasAction {
+    /// List built-in schemas, the unions using them and any local override
+    List,
+
+    /// Print a built-in schema's A2ML source
+    Show {
+        /// Schema name (e.g., nuj-code-of-ethics) or union (nuj)
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+
+    /// Write the built-in schemas to a directory, to edit as local overrides
+    Export {
+        /// Directory to write to
+        #[arg(value_name = "DIR", default_value = "schemas")]
+        dir: PathBuf,
+
+        /// Overwrite existing files
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 enum ValidationMode {
     /// Parse A2ML syntax only
     Lax,
@@ -267,32 +558,120 @@ enum OutputFormat {
     Json,
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ReportFormat {
+    Html,
+    Markdown,
+    Json,
+    /// SARIF 2.1.0, for code-scanning UIs
+    Sarif,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum BatchFormat {
+    Json,
+    /// One row per finding, for spreadsheets
+    Csv,
+    /// JUnit XML, one test case per contract, for CI systems
+    Junit,
+    Markdown,
+    /// SARIF 2.1.0, for code-scanning UIs
+    Sarif,
+}
+
+fn main() -> ExitCode {
+    let parsed = Cli::command().try_get_matches().and_then(|matches| {
+        let command = matches.subcommand_name().unwrap_or_default().to_string();
+        Ok((Cli::from_arg_matches(&matches)?, command))
+    });
+    let (cli, command) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            // clap's own usage status is 2, which we use for SHOULD findings
+            let _ = e.print();
+            return if e.use_stderr() { ExitCode::from(exit::USAGE) } else { ExitCode::SUCCESS };
+        }
+    };
 
     // Initialize logger
     let log_level = if cli.verbose { "debug" } else { "info" };
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(log_level)).init();
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(log_level))
+        .target(env_logger::Target::Stderr)
+        .init();
 
     log::info!("Union Policy Parser v{}", env!("CARGO_PKG_VERSION"));
 
+    // --warn-if-not and --strict ask for SHOULD findings to fail unless told otherwise
+    let fail_on = cli.fail_on.unwrap_or(match cli.command {
+        Commands::CheckClause { warn_if_not: true, .. } | Commands::Validate { strict: true, .. } => FailOn::Warning,
+        _ => FailOn::Error,
+    });
+
+    let out = Output::new(cli.output_format);
+    match run(cli, &out) {
+        Ok(status) => ExitCode::from(status.code(fail_on)),
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            let code = exit::error_code(&e);
+            if let Err(e) = out.error(&command, code, &e) {
+                eprintln!("Error: {}", e);
+            }
+            ExitCode::from(code)
+        }
+    }
+}
+
+fn run(cli: Cli, out: &Output) -> Result<Status> {
+    let name = cli.name.as_deref();
+    let config = match &cli.config {
+        Some(path) => Some(PolicyConfig::load(path)?),
+        None => PolicyConfig::discover(Path::new("."))?,
+    };
+    if let Some(config) = &config {
+        log::debug!("Using configuration from {:?}", config.root);
+    }
+    let config = config.as_ref();
+
     // Dispatch to subcommand handlers
-    match cli.command {
+    let status = match cli.command {
         Commands::Validate {
             contract,
             schema,
             mode,
             union,
             required_clauses,
-            strict,
-        } => cmd_validate(contract, schema, mode, union, required_clauses, strict)?,
+            strict: _,
+            sarif,
+            watch,
+            log,
+        } => {
+            let flags = CliSettings { schema, union, mode, required_clauses, ..Default::default() };
+            let out = out.redirected(sarif.as_deref().is_some_and(stdio::is_stdio));
+            let log = log.map(AuditLog::new);
+            if watch {
+                cmd_validate_watch(&out, config, contract, name, flags, sarif, log)?
+            } else {
+                let (contract, settings) = resolve_validate(config, &contract, &flags)?;
+                cmd_validate(&out, ContractInput::new(contract, name), settings, sarif, log)?
+            }
+        }
 
         Commands::Audit {
             contract,
             schema,
             output,
             union,
-        } => cmd_audit(contract, schema, output, union)?,
+            format,
+            log,
+            no_log,
+        } => {
+            let flags = CliSettings { schema, union, ..Default::default() };
+            let settings = resolve(config, &contract, &flags)?;
+            let format = resolve_format(format, config.and_then(|c| c.formats.audit.as_deref()), ReportFormat::Json)?;
+            let log = if no_log { None } else { configured_log(config, log) };
+            let out = out.redirected(stdio::is_stdio(&output));
+            cmd_audit(&out, ContractInput::new(contract, name), output, settings, format, log)?
+        }
 
         Commands::Grievance {
             contract,
@@ -301,7 +680,12 @@ fn main() -> Result<()> {
             output,
             union,
             schema,
-        } => cmd_grievance(contract, violation, template, output, union, schema)?,
+        } => {
+            let flags = CliSettings { schema, union, ..Default::default() };
+            let settings = resolve(config, &contract, &flags)?;
+            let out = out.redirected(stdio::is_stdio(&output));
+            cmd_grievance(&out, ContractInput::new(contract, name), violation, template, output, settings)?
+        }
 
         Commands::Batch {
             dir,
@@ -309,7 +693,22 @@ fn main() -> Result<()> {
             output,
             union,
             mode,
-        } => cmd_batch(dir, schema, output, union, mode)?,
+            format,
+            jobs,
+            include,
+            exclude,
+            k9,
+            cache_dir,
+            no_cache,
+            watch,
+        } => {
+            let discovery = DiscoveryOptions { include, exclude, k9 };
+            let cache_dir = if no_cache { None } else { cache_dir.or_else(cache::default_dir) };
+            let flags = CliSettings { schema, union, mode, ..Default::default() };
+            let format = resolve_format(format, config.and_then(|c| c.formats.batch.as_deref()), BatchFormat::Json)?;
+            let out = out.redirected(stdio::is_stdio(&output));
+            cmd_batch(&out, dir, output, config, flags, format, jobs, discovery, cache_dir, watch)?
+        }
 
         Commands::CheckClause {
             contract,
@@ -320,27 +719,74 @@ fn main() -> Result<()> {
             allowed,
             error_if_not,
             warn_if_not,
-        } => cmd_check_clause(contract, clause, expected, min, max, allowed, error_if_not, warn_if_not)?,
+        } => {
+            let input = ContractInput::new(contract, name);
+            cmd_check_clause(out, input, clause, expected, min, max, allowed, error_if_not, warn_if_not)?
+        }
 
-        Commands::GetClause { contract, clause } => cmd_get_clause(contract, clause)?,
+        Commands::GetClause { contract, clause } => cmd_get_clause(out, ContractInput::new(contract, name), clause)?,
 
         Commands::ScanRedFlags {
             contract,
             patterns,
             case_insensitive,
-        } => cmd_scan_red_flags(contract, patterns, case_insensitive)?,
+            sarif,
+        } => {
+            let out = out.redirected(sarif.as_deref().is_some_and(stdio::is_stdio));
+            cmd_scan_red_flags(&out, ContractInput::new(contract, name), patterns, case_insensitive, sarif)?
+        }
 
         Commands::Render {
             contract,
             format,
             output,
             template,
-        } => cmd_render(contract, format, output, template)?,
+            schema,
+            union,
+        } => {
+            let flags = CliSettings { schema, union, ..Default::default() };
+            let settings = resolve(config, &contract, &flags)?;
+            let format = resolve_format(format, config.and_then(|c| c.formats.render.as_deref()), OutputFormat::Html)?;
+            let out = out.redirected(stdio::is_stdio(&output));
+            cmd_render(&out, ContractInput::new(contract, name), format, output, template, settings)?
+        }
 
-        Commands::CheckSchema { schema } => cmd_check_schema(schema)?,
-    }
+        Commands::Diff { old, new, schema, union, mode } => {
+            let flags = CliSettings { schema, union, mode, ..Default::default() };
+            let settings = resolve(config, &new, &flags)?;
+            cmd_diff(out, ContractInput::new(old, None), ContractInput::new(new, None), settings)?
+        }
 
-    Ok(())
+        Commands::CheckSchema { schema } => cmd_check_schema(out, schema)?,
+
+        Commands::Fmt { files, check } => {
+            let out = out.redirected(!check && files.iter().any(|f| stdio::is_stdio(f)));
+            cmd_fmt(&out, files, check)?
+        }
+
+        Commands::Keygen { output, force } => cmd_keygen(out, output, force)?,
+        Commands::Sign { file, key, detached } => cmd_sign(out, file, key, detached)?,
+        Commands::Verify { file, key, signature } => cmd_verify(out, config, file, key, signature)?,
+
+        Commands::Log { action } => match action {
+            LogAction::Show { contract, log } => cmd_log_show(out, audit_log(config, log), contract)?,
+            LogAction::Verify { log, head } => cmd_log_verify(out, audit_log(config, log), head)?,
+        },
+
+        Commands::Schemas { action } => match action {
+            SchemasAction::List => cmd_schemas_list(out, config)?,
+            SchemasAction::Show { name } => cmd_schemas_show(out, name)?,
+            SchemasAction::Export { dir, force } => cmd_schemas_export(out, dir, force)?,
+        },
+
+        Commands::Lsp { schema, union, mode } => {
+            let flags = CliSettings { schema, union, mode, ..Default::default() };
+            lsp::run(|path| lsp_rules(config, path, &flags))?;
+            Status::Clean
+        }
+    };
+
+    Ok(status)
 }
 
 // ============================================================================
@@ -348,145 +794,545 @@ fn main() -> Result<()> {
 // ============================================================================
 
 fn cmd_validate(
-    contract_path: PathBuf,
-    schema_path: PathBuf,
-    mode: ValidationMode,
-    union: Option<String>,
-    required_clauses: Vec<String>,
-    strict: bool,
-) -> Result<()> {
-    log::info!("Validating contract: {:?}", contract_path);
+    out: &Output,
+    input: ContractInput,
+    settings: Resolved,
+    sarif_path: Option<PathBuf>,
+    log: Option<AuditLog>,
+) -> Result<Status> {
+    let schema_path = settings.require_schema()?;
+    let mode = settings.mode.unwrap_or(ValidationMode::Checked);
+    log::info!("Validating contract: {}", input.name);
     log::info!("Schema: {:?}", schema_path);
     log::info!("Mode: {:?}", mode);
 
     // Parse contract
-    let contract = parse_a2ml_file(&contract_path)?;
-    println!("✅ Contract parsed successfully");
-    println!("   Abstract: {}", if contract.abstract_text.is_some() { "present" } else { "missing" });
-    println!("   Sections: {}", contract.sections.len());
-    println!("   References: {}", contract.references.len());
-    println!("   Requirements: {}", contract.requirements.len());
+    let contract = input.parse()?;
+    say!(out, "✅ Contract parsed successfully");
+    say!(out, "   Abstract: {}", if contract.abstract_text.is_some() { "present" } else { "missing" });
+    say!(out, "   Sections: {}", contract.sections.len());
+    say!(out, "   References: {}", contract.references.len());
+    say!(out, "   Requirements: {}", contract.requirements.len());
 
     // Parse schema
-    let schema = parse_a2ml_file(&schema_path)?;
-    println!("✅ Schema parsed successfully");
+    let schema = stdio::parse(schema_path)?;
+    say!(out, "✅ Schema parsed successfully");
 
     // Get union-specific required clauses if union specified
-    let mut all_required_clauses = required_clauses.clone();
-    if let Some(union_name) = &union {
-        let union_enum = Union::from_str(union_name)?;
-        all_required_clauses.extend(
-            union_enum.required_clauses().iter().map(|s| s.to_string())
-        );
-        println!("📋 Union: {} ({} required clauses)", union_name.to_uppercase(), union_enum.required_clauses().len());
+    let (validator, all_required_clauses) = union_validator(schema, mode.into(), &settings)?;
+    if let Some(union_name) = &settings.union {
+        let union_clauses = union_name.parse::<Union>()?.required_clauses().len();
+        say!(out, "📋 Union: {} ({} required clauses)", union_name.to_uppercase(), union_clauses);
     }
 
     // Validate
-    let validator = Validator::new(schema, mode.into());
     let report = validator.validate(&contract, &all_required_clauses);
 
     // Display results
-    println!("\n{}", "=".repeat(60));
+    say!(out, "\n{}", "=".repeat(60));
     if report.valid {
-        println!("✅ VALID: Contract complies with schema");
+        say!(out, "✅ VALID: Contract complies with schema");
     } else {
-        println!("❌ INVALID: Contract has violations");
+        say!(out, "❌ INVALID: Contract has violations");
     }
-    println!("{}", "=".repeat(60));
+    say!(out, "{}", "=".repeat(60));
 
     if !report.errors.is_empty() {
-        println!("\n❌ Errors ({}):", report.errors.len());
+        say!(out, "\n❌ Errors ({}):", report.errors.len());
         for error in &report.errors {
-            println!("   - {}", error.message);
+            say!(out, "   - {}", error.message);
             if let Some(loc) = &error.location {
-                println!("     Location: {}", loc);
+                say!(out, "     Location: {}", loc);
             }
         }
     }
 
     if !report.warnings.is_empty() {
-        println!("\n⚠️  Warnings ({}):", report.warnings.len());
+        say!(out, "\n⚠️  Warnings ({}):", report.warnings.len());
         for warning in &report.warnings {
-            println!("   - {}", warning.message);
+            say!(out, "   - {}", warning.message);
+        }
+    }
+
+    if !report.red_flags.is_empty() {
+        say!(out, "\n🚩 Red Flags ({}):", report.red_flags.len());
+        for flag in &report.red_flags {
+            say!(out, "   - \"{}\" in {}", flag.excerpt, flag.location.as_deref().unwrap_or("contract"));
         }
     }
 
     if !report.required_clauses.is_empty() {
-        println!("\n📋 Required Clauses:");
+        say!(out, "\n📋 Required Clauses:");
         for clause_check in &report.required_clauses {
             let status = if clause_check.present { "✓" } else { "✗" };
-            println!("   {} {}", status, clause_check.clause);
+            say!(out, "   {} {} ({})", status, clause_check.clause, clause_check.severity.as_str());
+        }
+    }
+
+    if !report.predicates.is_empty() {
+        say!(out, "\n🧮 Contract Predicates:");
+        for check in &report.predicates {
+            let status = match check.outcome {
+                Outcome::Pass => "✓",
+                Outcome::Fail => "✗",
+                Outcome::Unknown => "?",
+            };
+            let detail = match (&check.value, &check.reason) {
+                (_, Some(reason)) => reason.clone(),
+                (Some(value), None) => format!("{} (needs {})", value, check.condition.as_deref().unwrap_or("?")),
+                (None, None) => String::new(),
+            };
+            say!(out, "   {} {}: {}", status, check.name, detail);
         }
     }
 
-    if strict && !report.valid {
-        anyhow::bail!("Validation failed (strict mode)");
+    if let Some(sarif_path) = &sarif_path {
+        stdio::write(sarif_path, sarif::render_sarif(std::slice::from_ref(&report))?)?;
+        say!(out, "\n✅ SARIF saved to: {}", stdio::describe(sarif_path));
     }
 
-    Ok(())
+    let logged = log.map(|log| log_run(out, &log, &report, "validate", &value_name(mode))).transpose()?;
+
+    let status = Status::of_report(&report);
+    out.emit("validate", status, json!({
+        "contract": report.contract.path,
+        "contract_sha256": report.contract.sha256,
+        "schema": report.schema.path,
+        "schema_name": report.schema.name,
+        "schema_document_version": report.schema.version,
+        "tool_version": report.tool_version,
+        "timestamp": report.timestamp,
+        "union": report.union,
+        "mode": value_name(mode),
+        "valid": report.valid,
+        "required_clauses": report.required_clauses.iter().map(|check| json!({
+            "clause": check.clause,
+            "present": check.present,
+            "severity": check.severity.as_str(),
+            "section": check.section,
+        })).collect::<Vec<_>>(),
+        "predicates": report.predicates,
+        "sarif": sarif_path,
+        "log": logged,
+        "findings": report.findings().iter().map(output::finding).collect::<Vec<_>>(),
+    }), Some(("findings", "finding")))?;
+
+    Ok(status)
+}
+
+/// `validate --watch`: re-validate on every change to the contract or
+/// schema, and for a K9 wrapper to the wrapper or the payload's signature
+fn cmd_validate_watch(
+    out: &Output,
+    config: Option<&PolicyConfig>,
+    target: PathBuf,
+    name: Option<&str>,
+    flags: CliSettings,
+    sarif_path: Option<PathBuf>,
+    log: Option<AuditLog>,
+) -> Result<Status> {
+    if stdio::is_stdio(&target) {
+        anyhow::bail!("--watch needs a contract file; stdin cannot be watched");
+    }
+    let (contract, mut settings) = resolve_validate(config, &target, &flags)?;
+    let mut input = ContractInput::new(contract, name);
+    settings.require_schema()?;
+    let mut watched = watched_files(&target, &input, &settings);
+    let mut watcher = watch::Watcher::new(&watched)?;
+
+    let mut previous = Vec::new();
+    let mut run = 0;
+    loop {
+        run += 1;
+        let time = chrono::Local::now().format("%H:%M:%S");
+        // A wrapper may since name another payload, schema or signature
+        let resolved = if run > 1 && target != input.path {
+            resolve_validate(config, &target, &flags)
+        } else {
+            Ok((input.path.clone(), settings.clone()))
+        };
+        let outcome = resolved.and_then(|(contract, resolved)| {
+            input = ContractInput::new(contract, name);
+            settings = resolved;
+            validate_contract(&input, &settings, settings.mode.unwrap_or(ValidationMode::Checked))
+        });
+        match outcome {
+            Ok(report) => {
+                let findings = report.findings();
+                let diff = watch::diff_findings(&previous, &findings);
+                let status = Status::of_report(&report);
+
+                let verdict = if report.valid { "✅ valid" } else { "❌ invalid" };
+                say!(out, "\n[{}] {}: {}", time, input.name, verdict);
+                say_diff(out, &diff);
+
+                if let Some(sarif_path) = &sarif_path {
+                    stdio::write(sarif_path, sarif::render_sarif(std::slice::from_ref(&report))?)?;
+                }
+                let mode = value_name(settings.mode.unwrap_or(ValidationMode::Checked));
+                let logged = log.as_ref().map(|log| log_run(out, log, &report, "validate", &mode)).transpose()?;
+
+                out.emit("validate", status, merge_diff(json!({
+                    "run": run,
+                    "contract": report.contract.path,
+                    "contract_sha256": report.contract.sha256,
+                    "schema": report.schema.path,
+                    "timestamp": report.timestamp,
+                    "valid": report.valid,
+                    "log": logged,
+                }), &diff), None)?;
+                previous = findings;
+            }
+            Err(e) => {
+                // Keep the last good findings, so the next diff is against them
+                eprintln!("[{}] Error: {:#}", time, e);
+                out.error("validate", exit::error_code(&e), &e)?;
+            }
+        }
+
+        if run == 1 {
+            say!(out, "\n👀 Watching for changes (Ctrl-C to stop)");
+        }
+        let now = watched_files(&target, &input, &settings);
+        if now != watched {
+            watcher = watch::Watcher::new(&now)?;
+            watched = now;
+        }
+        watcher.wait()?;
+    }
+}
+
+/// What `validate --watch` re-runs on: the contract and a schema file, and
+/// for a K9 wrapper the wrapper itself and the payload's detached signature
+fn watched_files(target: &Path, input: &ContractInput, settings: &Resolved) -> Vec<PathBuf> {
+    let mut watched = vec![input.path.clone()];
+    // Built-in schemas cannot change under us
+    if let Some(schema) = settings.schema.as_ref().filter(|schema| EmbeddedSchema::from_path(schema).is_none()) {
+        watched.push(schema.clone());
+    }
+    if target != input.path {
+        watched.push(target.to_path_buf());
+        watched.push(signing::detached_path(&input.path));
+    }
+    watched
+}
+
+/// The contract `validate` checks and its settings: for a K9 wrapper, its
+/// payload under the wrapper's settings
+fn resolve_validate(config: Option<&PolicyConfig>, contract: &Path, flags: &CliSettings) -> Result<(PathBuf, Resolved)> {
+    if k9::is_k9(contract) {
+        let wrapper = K9Wrapper::load(contract)?;
+        Ok((discovery::normalize(&wrapper.payload), resolve_k9(config, &wrapper, flags)?))
+    } else {
+        Ok((contract.to_path_buf(), resolve(config, contract, flags)?))
+    }
+}
+
+/// Parse and validate one contract against its resolved schema
+fn validate_contract(input: &ContractInput, settings: &Resolved, mode: ValidationMode) -> Result<ValidationReport> {
+    let contract = input.parse()?;
+    let schema = stdio::parse(settings.require_schema()?)?;
+    let (validator, required_clauses) = union_validator(schema, mode.into(), settings)?;
+    Ok(validator.validate(&contract, &required_clauses))
+}
+
+/// Print what changed since the previous run
+fn say_diff(out: &Output, diff: &watch::FindingsDiff) {
+    if diff.is_empty() {
+        say!(out, "   No change in findings ({} unchanged)", diff.unchanged.len());
+        return;
+    }
+    for finding in &diff.fixed {
+        say!(out, "   ✓ fixed  [{}] {}", finding.rule, finding.message);
+    }
+    for finding in &diff.new {
+        say!(out, "   ✗ new    [{}] {}", finding.rule, finding.message);
+    }
+    say!(out, "   {} fixed, {} new, {} unchanged", diff.fixed.len(), diff.new.len(), diff.unchanged.len());
+}
+
+/// `body` with the diff's findings added as `fixed`, `new` and `unchanged`
+fn merge_diff(mut body: serde_json::Value, diff: &watch::FindingsDiff) -> serde_json::Value {
+    let list = |findings: &[validator::Finding]| findings.iter().map(output::finding).collect::<Vec<_>>();
+    body["fixed"] = json!(list(&diff.fixed));
+    body["new"] = json!(list(&diff.new));
+    body["unchanged"] = json!(list(&diff.unchanged));
+    body
 }
 
 fn cmd_audit(
-    contract_path: PathBuf,
-    schema_path: PathBuf,
+    out: &Output,
+    input: ContractInput,
     output_path: PathBuf,
-    union: Option<String>,
-) -> Result<()> {
-    log::info!("Auditing contract: {:?}", contract_path);
+    settings: Resolved,
+    format: ReportFormat,
+    log: Option<AuditLog>,
+) -> Result<Status> {
+    log::info!("Auditing contract: {}", input.name);
+    let schema_path = settings.require_schema()?;
 
     // Parse contract and schema
-    let contract = parse_a2ml_file(&contract_path)?;
-    let schema = parse_a2ml_file(&schema_path)?;
-
-    // Get union-specific clauses
-    let required_clauses = if let Some(union_name) = &union {
-        let union_enum = Union::from_str(union_name)?;
-        union_enum.required_clauses().iter().map(|s| s.to_string()).collect()
-    } else {
-        Vec::new()
-    };
+    let contract = input.parse()?;
+    let schema = stdio::parse(schema_path)?;
 
-    // Validate
-    let validator = Validator::new(schema, ValidatorMode::Attested);
+    // Validate, with union-specific clauses
+    let (validator, required_clauses) = union_validator(schema, ValidatorMode::Attested, &settings)?;
     let report = validator.validate(&contract, &required_clauses);
 
-    // Render to JSON
-    let json = ReportRenderer::render_json(&report)?;
+    let rendered = match format {
+        ReportFormat::Json => ReportRenderer::render_json(&report)?,
+        ReportFormat::Markdown => ReportRenderer::render_markdown(&report)?,
+        ReportFormat::Html => ReportRenderer::render_html(&report)?,
+        ReportFormat::Sarif => sarif::render_sarif(std::slice::from_ref(&report))?,
+    };
 
     // Write to file
-    fs::write(&output_path, json)?;
+    stdio::write(&output_path, rendered)?;
+
+    say!(out, "✅ Audit report saved to: {}", stdio::describe(&output_path));
+    say!(out, "   Valid: {}", report.valid);
+    say!(out, "   Errors: {}", report.errors.len());
+    say!(out, "   Warnings: {}", report.warnings.len());
+
+    let logged = log.map(|log| log_run(out, &log, &report, "audit", "attested")).transpose()?;
+
+    let status = Status::of_report(&report);
+    out.emit("audit", status, json!({
+        "contract": report.contract.path,
+        "contract_sha256": report.contract.sha256,
+        "schema": report.schema.path,
+        "schema_name": report.schema.name,
+        "schema_document_version": report.schema.version,
+        "tool_version": report.tool_version,
+        "timestamp": report.timestamp,
+        "union": report.union,
+        "format": value_name(format),
+        "output": output_path,
+        "log": logged,
+        "valid": report.valid,
+        "findings": report.findings().iter().map(output::finding).collect::<Vec<_>>(),
+    }), Some(("findings", "finding")))?;
+
+    Ok(status)
+}
 
-    println!("✅ Audit report saved to: {:?}", output_path);
-    println!("   Valid: {}", report.valid);
-    println!("   Errors: {}", report.errors.len());
-    println!("   Warnings: {}", report.warnings.len());
+/// The audit log named on the command line, else the configured one
+/// The log `audit` appends to: only one asked for with `--log` or `audit_log`
+fn configured_log(config: Option<&PolicyConfig>, flag: Option<PathBuf>) -> Option<AuditLog> {
+    match (flag, config) {
+        (Some(path), _) => Some(AuditLog::new(path)),
+        (None, Some(config)) if config.audit_log.is_some() => Some(AuditLog::new(config.audit_log_path())),
+        _ => None,
+    }
+}
+
+fn audit_log(config: Option<&PolicyConfig>, flag: Option<PathBuf>) -> AuditLog {
+    match (flag, config) {
+        (Some(path), _) => AuditLog::new(path),
+        (None, Some(config)) => AuditLog::new(config.audit_log_path()),
+        (None, None) => AuditLog::new(audit_log::DEFAULT_LOG_FILE),
+    }
+}
 
+/// Append a run to the audit log; its position, for the JSON result
+fn log_run(out: &Output, log: &AuditLog, report: &ValidationReport, command: &str, mode: &str) -> Result<serde_json::Value> {
+    let entry = log
+        .append(LogEntry::of_report(report, command, mode))
+        .with_context(|| format!("Cannot append to audit log {:?}", log.path()))?;
+    say!(out, "📜 Logged as entry {} in {}", entry.seq, log.path().display());
+    Ok(json!({ "path": log.path(), "seq": entry.seq, "prev": entry.prev }))
+}
+
+fn existing_log(log: &AuditLog) -> Result<()> {
+    if !log.path().is_file() {
+        anyhow::bail!("No audit log at {:?}; pass --log, or set audit_log in union-policy.toml", log.path());
+    }
     Ok(())
 }
 
+fn cmd_log_show(out: &Output, log: AuditLog, contract: Option<String>) -> Result<Status> {
+    existing_log(&log)?;
+    let entries = log.entries().context("Audit log is damaged; run `log verify` to find where")?;
+
+    let mut by_contract: BTreeMap<&str, Vec<&LogEntry>> = BTreeMap::new();
+    for entry in &entries {
+        if contract.as_deref().is_none_or(|c| c == entry.contract.path) {
+            by_contract.entry(&entry.contract.path).or_default().push(entry);
+        }
+    }
+
+    if by_contract.is_empty() {
+        say!(out, "No logged runs{}", contract.as_deref().map(|c| format!(" for {}", c)).unwrap_or_default());
+    }
+    for (path, runs) in &by_contract {
+        say!(out, "\n📄 {} ({} run{})", path, runs.len(), if runs.len() == 1 { "" } else { "s" });
+        let mut previous: Option<&LogEntry> = None;
+        for entry in runs {
+            let verdict = if entry.valid { "✅ valid  " } else { "❌ invalid" };
+            let sha = entry.contract.sha256.as_deref().map_or("-", |sha| &sha[..sha.len().min(12)]);
+            say!(
+                out,
+                "   #{:<4} {}  {:<8} {}  {} MUST, {} SHOULD  contract {}  {} {}",
+                entry.seq,
+                entry.time,
+                entry.command,
+                verdict,
+                entry.findings.must,
+                entry.findings.should,
+                sha,
+                entry.union.as_deref().unwrap_or("-"),
+                entry.mode
+            );
+            if let Some(previous) = previous {
+                if previous.contract.sha256 != entry.contract.sha256 {
+                    say!(out, "         contract changed since #{}", previous.seq);
+                }
+                if previous.schema.sha256 != entry.schema.sha256 {
+                    say!(out, "         schema changed since #{}", previous.seq);
+                }
+            }
+            previous = Some(entry);
+        }
+    }
+
+    let shown: Vec<&LogEntry> = by_contract.into_values().flatten().collect();
+    out.emit("log", Status::Clean, json!({
+        "log": log.path(),
+        "contract": contract,
+        "entries": shown,
+    }), Some(("entries", "entry")))?;
+    Ok(Status::Clean)
+}
+
+fn cmd_log_verify(out: &Output, log: AuditLog, head: Option<String>) -> Result<Status> {
+    existing_log(&log)?;
+    let verification = match &head {
+        Some(head) => log.verify_head(head)?,
+        None => log.verify()?,
+    };
+
+    let status = if verification.is_intact() {
+        say!(out, "✅ Audit log intact: {} entries in {}", verification.entries, log.path().display());
+        Status::Clean
+    } else {
+        say!(out, "❌ Audit log tampered with: {}", log.path().display());
+        for chain_break in &verification.breaks {
+            say!(out, "   line {}: {}", chain_break.line, chain_break.problem);
+        }
+        Status::Violations
+    };
+    // Deleting the newest entries leaves a valid chain; only a recorded head shows it
+    say!(out, "   Head: {}", verification.head);
+    say!(out, "   (keep this hash with the grievance file to detect later truncation)");
+
+    out.emit("log", status, json!({
+        "log": log.path(),
+        "entries": verification.entries,
+        "head": verification.head,
+        "recorded_head": head,
+        "intact": verification.is_intact(),
+        "breaks": verification.breaks,
+    }), None)?;
+    Ok(status)
+}
+
+fn cmd_diff(out: &Output, old: ContractInput, new: ContractInput, settings: Resolved) -> Result<Status> {
+    if stdio::is_stdio(&old.path) && stdio::is_stdio(&new.path) {
+        anyhow::bail!("Only one version can come from stdin");
+    }
+    let mode = settings.mode.unwrap_or(ValidationMode::Checked);
+    let (old_doc, new_doc) = (old.parse()?, new.parse()?);
+    let clauses = diff::diff_contracts(&old_doc, &new_doc);
+
+    say!(out, "📝 {} → {}", old.name, new.name);
+    say!(
+        out,
+        "   {} modified, {} added, {} removed, {} unchanged",
+        clauses.count(Change::Modified),
+        clauses.count(Change::Added),
+        clauses.count(Change::Removed),
+        clauses.unchanged
+    );
+    for clause in &clauses.clauses {
+        let (mark, prefix) = match clause.change {
+            Change::Added => ("+", "+ "),
+            Change::Removed => ("-", "- "),
+            Change::Modified => ("~", ""),
+        };
+        let renamed = clause.old_heading.as_ref().map(|h| format!(" (was \"{}\")", h)).unwrap_or_default();
+        say!(out, "\n{} {}{}", mark, clause.heading, renamed);
+        let lines = match clause.change {
+            Change::Modified => clause.changed_lines(),
+            _ => clause.words.iter().flat_map(|span| span.text.lines().filter(|l| !l.is_empty())).map(str::to_string).collect(),
+        };
+        for line in lines {
+            say!(out, "   {}{}", prefix, line);
+        }
+    }
+
+    // Compliance delta, when there is a schema to validate against
+    let reports = match &settings.schema {
+        Some(schema_path) => {
+            let (validator, required_clauses) = union_validator(stdio::parse(schema_path)?, mode.into(), &settings)?;
+            Some((validator.validate(&old_doc, &required_clauses), validator.validate(&new_doc, &required_clauses)))
+        }
+        None => {
+            say!(out, "\n(no schema or union given, so findings were not compared)");
+            None
+        }
+    };
+    let findings = reports.as_ref().map(|(before, after)| watch::diff_findings(&before.findings(), &after.findings()));
+    if let (Some((before, after)), Some(findings)) = (&reports, &findings) {
+        let verdict = |valid: bool| if valid { "✅ valid" } else { "❌ invalid" };
+        say!(out, "\n🧾 Findings: {} {} → {} {}", old.name, verdict(before.valid), new.name, verdict(after.valid));
+        say_diff(out, findings);
+    }
+
+    let status = findings.as_ref().map_or(Status::Clean, |f| Status::of_findings(&f.new));
+    let body = json!({
+        "old_contract": old.name,
+        "new_contract": new.name,
+        "old_sha256": reports.as_ref().and_then(|(before, _)| before.contract.sha256.clone()),
+        "new_sha256": reports.as_ref().and_then(|(_, after)| after.contract.sha256.clone()),
+        "schema": settings.schema,
+        "union": settings.union,
+        "mode": value_name(mode),
+        "old_valid": reports.as_ref().map(|(before, _)| before.valid),
+        "new_valid": reports.as_ref().map(|(_, after)| after.valid),
+        "unchanged_clauses": clauses.unchanged,
+        "clauses": clauses.clauses,
+    });
+    let body = match &findings {
+        Some(findings) => merge_diff(body, findings),
+        None => body,
+    };
+    out.emit("diff", status, body, Some(("clauses", "clause")))?;
+    Ok(status)
+}
+
 fn cmd_grievance(
-    contract_path: PathBuf,
+    out: &Output,
+    input: ContractInput,
     violation: String,
     template_path: Option<PathBuf>,
     output_path: PathBuf,
-    union: Option<String>,
-    schema_path: Option<PathBuf>,
-) -> Result<()> {
+    settings: Resolved,
+) -> Result<Status> {
     log::info!("Generating grievance for: {}", violation);
+    let union = settings.union.clone();
 
     // Parse contract
-    let contract = parse_a2ml_file(&contract_path)?;
+    let contract = input.parse()?;
 
     // Validate if schema provided
-    let report = if let Some(schema_path) = schema_path {
-        let schema = parse_a2ml_file(&schema_path)?;
+    let report = if let Some(schema_path) = &settings.schema {
+        let schema = stdio::parse(schema_path)?;
         let validator = Validator::new(schema, ValidatorMode::Attested);
-        validator.validate(&contract, &vec![])
+        validator.validate(&contract, &[])
     } else {
-        validator::ValidationReport::new(
-            contract_path.to_string_lossy().to_string(),
-            "no-schema".to_string(),
-        )
+        let mut report = ValidationReport::new(input.name.clone(), "no-schema".to_string());
+        report.contract = DocumentInfo::of(&contract);
+        report
     };
 
     // Generate grievance
@@ -494,85 +1340,338 @@ fn cmd_grievance(
     let grievance = generator.generate(&violation, &report)?;
 
     // Write to file
-    fs::write(&output_path, grievance)?;
+    stdio::write(&output_path, grievance)?;
 
-    println!("✅ Grievance letter saved to: {:?}", output_path);
+    say!(out, "✅ Grievance letter saved to: {}", stdio::describe(&output_path));
 
-    Ok(())
+    // The letter is the product; the violations it cites are expected
+    out.emit("grievance", Status::Clean, json!({
+        "contract": input.name,
+        "violation": violation,
+        "union": settings.union,
+        "output": output_path,
+    }), None)?;
+
+    Ok(Status::Clean)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn cmd_batch(
+    out: &Output,
     dir: PathBuf,
-    schema_path: PathBuf,
     output_path: PathBuf,
-    union: Option<String>,
-    mode: ValidationMode,
-) -> Result<()> {
+    config: Option<&PolicyConfig>,
+    flags: CliSettings,
+    format: BatchFormat,
+    jobs: Option<usize>,
+    discovery: DiscoveryOptions,
+    cache_dir: Option<PathBuf>,
+    watch: bool,
+) -> Result<Status> {
     log::info!("Batch validating contracts in: {:?}", dir);
+    if watch && stdio::is_stdio(&dir) {
+        anyhow::bail!("--watch needs a directory; a file list on stdin cannot be watched");
+    }
+
+    // Sorted, so that reports are identical from run to run; a list on
+    // stdin is taken as given
+    let discovered = if stdio::is_stdio(&dir) {
+        Discovered::from_list(
+            stdio::read_to_string(&dir)?
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(PathBuf::from)
+                .collect(),
+        )?
+    } else {
+        discovery::discover(&dir, &discovery)?
+    };
+    say!(out, "Found {} A2ML files", discovered.files.len());
+    if !discovered.wrappers.is_empty() {
+        say!(out, "   {} through K9 wrappers", discovered.wrappers.len());
+    }
 
-    use walkdir::WalkDir;
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.unwrap_or(0))
+        .build()?;
 
-    // Find all .a2ml files
-    let mut a2ml_files = Vec::new();
-    for entry in WalkDir::new(&dir).follow_links(true) {
-        let entry = entry?;
-        if entry.file_type().is_file() {
-            if let Some(ext) = entry.path().extension() {
-                if ext == "a2ml" {
-                    a2ml_files.push(entry.path().to_path_buf());
-                }
-            }
+    let (entries, stats) = validate_batch(discovered.files.clone(), &discovered, config, &flags, &pool, cache_dir.as_deref())?;
+
+    if let Some((hits, misses)) = stats {
+        say!(out, "   Cache: {} reused, {} validated", hits, misses);
+    }
+
+    // Write batch report
+    stdio::write(&output_path, render_batch(&entries, format)?)?;
+
+    let summary = BatchSummary::new(&entries);
+    say!(
+        out,
+        "   Valid: {}  Invalid: {}  Parse errors: {}",
+        summary.valid, summary.invalid, summary.parse_errors
+    );
+    say!(out, "✅ Batch report saved to: {}", stdio::describe(&output_path));
+
+    let status = Status::of_batch(&entries);
+    out.emit("batch", status, json!({
+        "dir": dir,
+        "format": value_name(format),
+        "output": output_path,
+        "files": summary.files,
+        "valid": summary.valid,
+        "invalid": summary.invalid,
+        "parse_errors": summary.parse_errors,
+        "cache": stats.map(|(hits, misses)| json!({ "reused": hits, "validated": misses })),
+        "contracts": entries.iter().map(|entry| json!({
+            "file": entry.path,
+            "status": entry.status(),
+            "findings": entry.findings().iter().map(output::finding).collect::<Vec<_>>(),
+        })).collect::<Vec<_>>(),
+    }), Some(("contracts", "contract")))?;
+
+    if watch {
+        let batch = WatchedBatch { dir: &dir, output_path: &output_path, config, flags: &flags, format, discovery: &discovery, cache_dir: cache_dir.as_deref() };
+        return watch_batch(out, batch, &pool, discovered, entries);
+    }
+
+    Ok(status)
+}
+
+/// Hits and misses of a batch's cache
+type CacheStats = (usize, usize);
+
+/// Validate files grouped by effective settings; entries come back in the
+/// order of `files`, with the cache's hit and miss counts if it was used
+fn validate_batch(
+    files: Vec<PathBuf>,
+    discovered: &Discovered,
+    config: Option<&PolicyConfig>,
+    flags: &CliSettings,
+    pool: &rayon::ThreadPool,
+    cache_dir: Option<&Path>,
+) -> Result<(Vec<BatchEntry>, Option<CacheStats>)> {
+    // Group files by effective settings, so per-directory overrides in
+    // union-policy.toml apply; each group shares one validator
+    let mut groups: Vec<(Resolved, Vec<(usize, PathBuf)>)> = Vec::new();
+    for (index, file) in files.into_iter().enumerate() {
+        let settings = batch_settings(config, discovered, &file, flags)?;
+        match groups.iter_mut().find(|(s, _)| *s == settings) {
+            Some((_, files)) => files.push((index, file)),
+            None => groups.push((settings, vec![(index, file)])),
         }
     }
 
-    println!("Found {} A2ML files", a2ml_files.len());
+    let mut indexed = Vec::new();
+    let mut stats: Option<CacheStats> = None;
+    for (settings, files) in groups {
+        let schema_path = settings.require_schema()?;
+        let schema = stdio::parse(schema_path)?;
+        let mode = settings.mode.unwrap_or(ValidationMode::Checked);
+        let (validator, required_clauses) = union_validator(schema, mode.into(), &settings)?;
+
+        // A cache is only an optimisation: without one, validate everything
+        let cache = cache_dir.and_then(|cache_dir| {
+            Cache::open(cache_dir, &validator.fingerprint(&required_clauses))
+                .map_err(|e| log::warn!("Not caching results: cannot use {}: {}", cache_dir.display(), e))
+                .ok()
+        });
+
+        // Validate in parallel; parse failures are recorded as results too
+        let (indices, paths): (Vec<usize>, Vec<PathBuf>) = files.into_iter().unzip();
+        let entries = pool.install(|| {
+            batch::validate_files(
+                &paths,
+                &validator,
+                &required_clauses,
+                cache.as_ref(),
+                std::io::stderr().is_terminal(),
+            )
+        });
+        indexed.extend(indices.into_iter().zip(entries));
+
+        if let Some(cache) = &cache {
+            let (hits, misses) = cache.stats();
+            let (h, m) = stats.get_or_insert((0, 0));
+            *h += hits;
+            *m += misses;
+        }
+    }
 
-    // Parse schema
-    let schema = parse_a2ml_file(&schema_path)?;
+    // Back to discovery order, so reports are deterministic
+    indexed.sort_by_key(|(index, _)| *index);
+    Ok((indexed.into_iter().map(|(_, entry)| entry).collect(), stats))
+}
 
-    // Get union clauses
-    let required_clauses = if let Some(union_name) = &union {
-        let union_enum = Union::from_str(union_name)?;
-        union_enum.required_clauses().iter().map(|s| s.to_string()).collect()
-    } else {
-        Vec::new()
-    };
+/// Settings for a batch file: its K9 wrapper's, if it was reached through one
+fn batch_settings(config: Option<&PolicyConfig>, discovered: &Discovered, file: &Path, flags: &CliSettings) -> Result<Resolved> {
+    match discovered.wrappers.get(file) {
+        Some(wrapper) => resolve_k9(config, wrapper, flags),
+        None => resolve(config, file, flags),
+    }
+}
 
-    // Validate each file
-    let mut all_reports = Vec::new();
-    for file in &a2ml_files {
-        println!("Validating: {:?}", file);
-        match parse_a2ml_file(file) {
-            Ok(contract) => {
-                let validator = Validator::new(schema.clone(), mode.into());
-                let report = validator.validate(&contract, &required_clauses);
-                all_reports.push(serde_json::json!({
-                    "file": file.to_string_lossy(),
-                    "valid": report.valid,
-                    "errors": report.errors.len(),
-                    "warnings": report.warnings.len(),
-                }));
+fn render_batch(entries: &[BatchEntry], format: BatchFormat) -> Result<String> {
+    Ok(match format {
+        BatchFormat::Json => batch::render_json(entries)?,
+        BatchFormat::Csv => batch::render_csv(entries),
+        BatchFormat::Junit => batch::render_junit(entries),
+        BatchFormat::Markdown => batch::render_markdown(entries),
+        BatchFormat::Sarif => sarif::render_sarif(&batch::reports(entries))?,
+    })
+}
+
+/// What `batch --watch` re-runs with
+struct WatchedBatch<'a> {
+    dir: &'a Path,
+    output_path: &'a Path,
+    config: Option<&'a PolicyConfig>,
+    flags: &'a CliSettings,
+    format: BatchFormat,
+    discovery: &'a DiscoveryOptions,
+    cache_dir: Option<&'a Path>,
+}
+
+impl WatchedBatch<'_> {
+    /// Schema each file is validated against, if any
+    fn schema_for(&self, discovered: &Discovered, file: &Path) -> Result<Option<PathBuf>> {
+        Ok(batch_settings(self.config, discovered, file, self.flags)?.schema)
+    }
+}
+
+/// `batch --watch`: after the first run, re-validate contracts that change,
+/// every contract using a schema that changes, and new contracts
+fn watch_batch(
+    out: &Output,
+    batch: WatchedBatch,
+    pool: &rayon::ThreadPool,
+    discovered: Discovered,
+    entries: Vec<BatchEntry>,
+) -> Result<Status> {
+    // Schemas may live outside the directory, so they are watched as well
+    let mut watched = vec![batch.dir.to_path_buf()];
+    for entry in &entries {
+        if let Some(schema) = batch.schema_for(&discovered, Path::new(&entry.path))? {
+            if !watched.contains(&schema) && EmbeddedSchema::from_path(&schema).is_none() {
+                watched.push(schema);
             }
+        }
+    }
+    let watcher = watch::Watcher::new(&watched)?;
+    say!(out, "\n👀 Watching {:?} (Ctrl-C to stop)", batch.dir);
+
+    let mut results = entries;
+    // Files whose last re-run failed before validating, e.g. on a broken schema
+    let mut pending = BTreeSet::new();
+    let mut run = 1;
+    loop {
+        let changed = watcher.wait()?;
+        let time = chrono::Local::now().format("%H:%M:%S");
+
+        let discovered = match discovery::discover(batch.dir, batch.discovery) {
+            Ok(discovered) => discovered,
             Err(e) => {
-                eprintln!("❌ Failed to parse {:?}: {}", file, e);
+                eprintln!("[{}] Error: {}", time, e);
+                continue;
+            }
+        };
+        let previous: BTreeMap<&str, &BatchEntry> = results.iter().map(|entry| (entry.path.as_str(), entry)).collect();
+
+        let mut affected = Vec::new();
+        let files = &discovered.files;
+        for file in files {
+            let schema_changed = batch
+                .schema_for(&discovered, file)?
+                .is_some_and(|schema| changed.contains(&watch::absolute(&schema)));
+            let wrapper_changed = discovered
+                .wrappers
+                .get(file)
+                .is_some_and(|wrapper| changed.contains(&watch::absolute(&wrapper.path)));
+            if schema_changed
+                || wrapper_changed
+                || changed.contains(&watch::absolute(file))
+                || pending.contains(file)
+                || !previous.contains_key(file.to_string_lossy().as_ref())
+            {
+                affected.push(file.clone());
             }
         }
-    }
+        let removed: Vec<&BatchEntry> = previous
+            .values()
+            .filter(|entry| !files.iter().any(|file| file.to_string_lossy() == entry.path))
+            .copied()
+            .collect();
+
+        // Our own report and cache writes land here too
+        if affected.is_empty() && removed.is_empty() {
+            continue;
+        }
+        run += 1;
 
-    // Write batch report
-    let batch_report = serde_json::json!({
-        "total_files": a2ml_files.len(),
-        "results": all_reports,
-    });
+        let fresh = match validate_batch(affected.clone(), &discovered, batch.config, batch.flags, pool, batch.cache_dir) {
+            Ok((fresh, _)) => fresh,
+            Err(e) => {
+                eprintln!("[{}] Error: {:#}", time, e);
+                out.error("batch", exit::error_code(&e), &e)?;
+                pending.extend(affected);
+                continue;
+            }
+        };
+        pending.clear();
+
+        say!(out, "\n[{}] {} re-validated, {} removed", time, fresh.len(), removed.len());
+        let mut changes = Vec::new();
+        for entry in &fresh {
+            let before = previous.get(entry.path.as_str()).map(|entry| entry.findings()).unwrap_or_default();
+            let diff = watch::diff_findings(&before, &entry.findings());
+            say!(out, "  {} ({})", entry.path, entry.status());
+            say_diff(out, &diff);
+            changes.push(merge_diff(json!({ "file": entry.path, "status": entry.status() }), &diff));
+        }
+        for entry in &removed {
+            let diff = watch::diff_findings(&entry.findings(), &[]);
+            say!(out, "  {} (removed)", entry.path);
+            changes.push(merge_diff(json!({ "file": entry.path, "status": "removed" }), &diff));
+        }
 
-    fs::write(&output_path, serde_json::to_string_pretty(&batch_report)?)?;
-    println!("✅ Batch report saved to: {:?}", output_path);
+        // Unchanged files keep their earlier results, in discovery order
+        let mut fresh: BTreeMap<String, BatchEntry> = fresh.into_iter().map(|entry| (entry.path.clone(), entry)).collect();
+        results = files
+            .iter()
+            .filter_map(|file| {
+                let path = file.to_string_lossy();
+                fresh.remove(path.as_ref()).or_else(|| previous.get(path.as_ref()).map(|entry| (*entry).clone()))
+            })
+            .collect();
+
+        if let Err(e) = stdio::write(batch.output_path, render_batch(&results, batch.format)?) {
+            eprintln!("[{}] Error: {}", time, e);
+        }
 
-    Ok(())
+        let summary = BatchSummary::new(&results);
+        say!(
+            out,
+            "   Valid: {}  Invalid: {}  Parse errors: {}",
+            summary.valid, summary.invalid, summary.parse_errors
+        );
+
+        out.emit("batch", Status::of_batch(&results), json!({
+            "run": run,
+            "dir": batch.dir,
+            "output": batch.output_path,
+            "files": summary.files,
+            "valid": summary.valid,
+            "invalid": summary.invalid,
+            "parse_errors": summary.parse_errors,
+            "changes": changes,
+        }), Some(("changes", "change")))?;
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn cmd_check_clause(
-    contract_path: PathBuf,
+    out: &Output,
+    input: ContractInput,
     clause: String,
     expected: Option<String>,
     min: Option<f64>,
@@ -580,155 +1679,789 @@ fn cmd_check_clause(
     allowed: Vec<String>,
     error_if_not: bool,
     warn_if_not: bool,
-) -> Result<()> {
+) -> Result<Status> {
     log::info!("Checking clause: {}", clause);
 
-    let contract = parse_a2ml_file(&contract_path)?;
+    let contract = input.parse()?;
 
-    // Simple clause lookup (check if section heading contains clause)
-    let found = contract.sections.iter().any(|s| {
-        s.heading.to_lowercase().contains(&clause.to_lowercase())
-    });
+    // Found the way `validate` finds required clauses
+    let section = contract.sections.iter().find(|s| clause_matches(&s.heading, &clause));
 
-    if found {
-        println!("✓ Clause '{}' found", clause);
+    let not_found = if error_if_not {
+        Status::Violations
+    } else if warn_if_not {
+        Status::Warnings
     } else {
-        println!("✗ Clause '{}' NOT found", clause);
-        if error_if_not {
-            anyhow::bail!("Clause check failed");
-        }
-    }
+        Status::Clean
+    };
 
-    Ok(())
-}
+    let checks_value = expected.is_some() || min.is_some() || max.is_some() || !allowed.is_empty();
+    if !checks_value {
+        let status = match section {
+            Some(_) => {
+                say!(out, "✓ Clause '{}' found", clause);
+                Status::Clean
+            }
+            None => {
+                say!(out, "✗ Clause '{}' NOT found", clause);
+                not_found
+            }
+        };
 
-fn cmd_get_clause(contract_path: PathBuf, clause: String) -> Result<()> {
-    log::info!("Getting clause value: {}", clause);
+        out.emit("check-clause", status, json!({
+            "contract": input.name,
+            "clause": clause,
+            "found": section.is_some(),
+            "section": section.map(|s| &s.heading),
+            "line": section.map(|s| s.line_number),
+        }), None)?;
 
-    let contract = parse_a2ml_file(&contract_path)?;
-
-    // Find section with matching heading
-    if let Some(section) = contract.sections.iter().find(|s| {
-        s.heading.to_lowercase().contains(&clause.to_lowercase())
-    }) {
-        println!("Clause: {}", section.heading);
-        println!("Content:");
-        for block in &section.content {
-            match block {
-                parser::ContentBlock::Paragraph(text) => println!("{}", text),
-                parser::ContentBlock::BulletList(items) => {
-                    for item in items {
-                        println!("- {}", item);
-                    }
+        return Ok(status);
+    }
+
+    // "payment.terms.net-days" names the field the way K9 predicates do
+    let path: Vec<String> = clause.split('.').map(|part| part.replace('-', "_")).collect();
+    let binding = predicate::field_value(&contract, &path);
+
+    let (status, problems) = match &binding {
+        Some(binding) => {
+            let problems = clause_value_problems(binding, expected.as_deref(), min, max, &allowed);
+            if problems.is_empty() {
+                say!(out, "✓ Clause '{}' is {} ({})", clause, binding.text, binding.section);
+                (Status::Clean, problems)
+            } else {
+                say!(out, "✗ Clause '{}' is {} ({})", clause, binding.text, binding.section);
+                for problem in &problems {
+                    say!(out, "  {}", problem);
                 }
-                _ => {}
+                (Status::Violations, problems)
             }
         }
+        None => {
+            say!(out, "✗ No value found for clause '{}'", clause);
+            (not_found, vec!["no value found".to_string()])
+        }
+    };
+
+    out.emit("check-clause", status, json!({
+        "contract": input.name,
+        "clause": clause,
+        "found": binding.is_some(),
+        "value": binding.as_ref().map(|b| &b.text),
+        "section": binding.as_ref().map(|b| &b.section),
+        "problems": problems,
+    }), None)?;
+
+    Ok(status)
+}
+
+/// Why a clause value fails `--expected`, `--min`, `--max` or `--allowed`
+fn clause_value_problems(
+    binding: &predicate::Binding,
+    expected: Option<&str>,
+    min: Option<f64>,
+    max: Option<f64>,
+    allowed: &[String],
+) -> Vec<String> {
+    let number = match binding.value {
+        predicate::Value::Number(number) => Some(number),
+        _ => None,
+    };
+    // Numbers compare as numbers ("30" == "30 days"), anything else as prose
+    let matches = |wanted: &str| match (number, wanted.trim().parse::<f64>()) {
+        (Some(number), Ok(wanted)) => number == wanted,
+        _ => predicate::prose_eq(&binding.text, wanted),
+    };
+
+    let mut problems = Vec::new();
+    if let Some(expected) = expected.filter(|e| !matches(e)) {
+        problems.push(format!("expected {}", expected));
+    }
+    if (min.is_some() || max.is_some()) && number.is_none() {
+        problems.push("--min/--max need a number".to_string());
+    }
+    if let (Some(min), Some(number)) = (min, number) {
+        if number < min {
+            problems.push(format!("minimum is {}", min));
+        }
+    }
+    if let (Some(max), Some(number)) = (max, number) {
+        if number > max {
+            problems.push(format!("maximum is {}", max));
+        }
+    }
+    if !allowed.is_empty() && !allowed.iter().any(|a| matches(a)) {
+        problems.push(format!("allowed: {}", allowed.join(", ")));
+    }
+    problems
+}
+
+fn cmd_get_clause(out: &Output, input: ContractInput, clause: String) -> Result<Status> {
+    log::info!("Getting clause value: {}", clause);
+
+    let contract = input.parse()?;
+
+    let found = contract.sections.iter().find(|s| clause_matches(&s.heading, &clause));
+    if let Some(section) = found {
+        say!(out, "Clause: {}", section.heading);
+        say!(out, "Content:");
+        say!(out, "{}", formatter::format_blocks(&section.content));
     } else {
-        println!("Clause '{}' not found", clause);
+        say!(out, "Clause '{}' not found", clause);
     }
 
-    Ok(())
+    out.emit("get-clause", Status::Clean, json!({
+        "contract": input.name,
+        "clause": clause,
+        "found": found.is_some(),
+        "section": found.map(|s| &s.heading),
+        "line": found.map(|s| s.line_number),
+        "content": found.map(|s| &s.content),
+    }), None)?;
+
+    Ok(Status::Clean)
 }
 
 fn cmd_scan_red_flags(
-    contract_path: PathBuf,
+    out: &Output,
+    input: ContractInput,
     patterns: Vec<String>,
     case_insensitive: bool,
-) -> Result<()> {
+    sarif_path: Option<PathBuf>,
+) -> Result<Status> {
     log::info!("Scanning for red flags: {:?}", patterns);
 
-    let contract = parse_a2ml_file(&contract_path)?;
-    let contract_text = serde_json::to_string(&contract)?;
-
-    let mut found_flags = Vec::new();
-
-    for pattern in &patterns {
-        let search_pattern = if case_insensitive {
-            pattern.to_lowercase()
-        } else {
-            pattern.clone()
-        };
-
-        let search_text = if case_insensitive {
-            contract_text.to_lowercase()
-        } else {
-            contract_text.clone()
-        };
-
-        if search_text.contains(&search_pattern) {
-            found_flags.push(pattern.clone());
-        }
-    }
+    let contract = input.parse()?;
+    let found_flags = find_red_flags(&contract, &patterns, case_insensitive);
 
     if found_flags.is_empty() {
-        println!("✅ No red flags found");
+        say!(out, "✅ No red flags found");
     } else {
-        println!("⚠️  Red flags detected ({}):", found_flags.len());
-        for flag in found_flags {
-            println!("   - {}", flag);
+        say!(out, "⚠️  Red flags detected ({}):", found_flags.len());
+        for flag in &found_flags {
+            match flag.line {
+                Some(line) => say!(out, "   - \"{}\" at line {}", flag.excerpt, line),
+                None => say!(out, "   - \"{}\"", flag.excerpt),
+            }
         }
     }
 
-    Ok(())
+    let mut report = ValidationReport::new(input.name.clone(), "none".to_string());
+    report.contract = DocumentInfo::of(&contract);
+    for flag in found_flags {
+        report.add_red_flag(flag);
+    }
+
+    if let Some(sarif_path) = &sarif_path {
+        stdio::write(sarif_path, sarif::render_sarif(std::slice::from_ref(&report))?)?;
+        say!(out, "✅ SARIF saved to: {}", stdio::describe(sarif_path));
+    }
+
+    let status = Status::of_report(&report);
+    out.emit("scan-red-flags", status, json!({
+        "contract": input.name,
+        "patterns": patterns,
+        "sarif": sarif_path,
+        "findings": report.findings().iter().map(output::finding).collect::<Vec<_>>(),
+    }), Some(("findings", "finding")))?;
+
+    Ok(status)
 }
 
 fn cmd_render(
-    contract_path: PathBuf,
+    out: &Output,
+    input: ContractInput,
     format: OutputFormat,
     output_path: PathBuf,
     _template: Option<PathBuf>,
-) -> Result<()> {
+    settings: Resolved,
+) -> Result<Status> {
     log::info!("Rendering contract to: {:?}", output_path);
 
-    let contract = parse_a2ml_file(&contract_path)?;
+    let contract = input.parse()?;
+
+    // Annotate with findings when there is something to validate against
+    let report = if settings.schema.is_some() || settings.union.is_some() {
+        let schema = match &settings.schema {
+            Some(path) => stdio::parse(path)?,
+            None => A2mlDocument::default(),
+        };
+        let (validator, required_clauses) = union_validator(schema, ValidatorMode::Checked, &settings)?;
+        Some(validator.validate(&contract, &required_clauses))
+    } else {
+        None
+    };
 
     let output = match format {
         OutputFormat::Json => serde_json::to_string_pretty(&contract)?,
-        OutputFormat::Markdown => {
-            // Simple Markdown rendering
-            let mut md = String::new();
-            if let Some(abstract_text) = &contract.abstract_text {
-                md.push_str("## Abstract\n\n");
-                md.push_str(abstract_text);
-                md.push_str("\n\n");
-            }
-            for section in &contract.sections {
-                md.push_str(&"#".repeat(section.level as usize + 1));
-                md.push_str(" ");
-                md.push_str(&section.heading);
-                md.push_str("\n\n");
+        OutputFormat::Markdown => markdown::render_contract(&contract, report.as_ref()),
+        OutputFormat::Html => html::render_contract(&contract, report.as_ref()),
+    };
+
+    stdio::write(&output_path, output)?;
+    say!(out, "✅ Rendered to: {}", stdio::describe(&output_path));
+
+    // Findings are annotations here, not failures
+    out.emit("render", Status::Clean, json!({
+        "contract": input.name,
+        "format": value_name(format),
+        "output": output_path,
+        "annotated": report.is_some(),
+    }), None)?;
+
+    Ok(Status::Clean)
+}
+
+/// A contract argument: where to read it and what reports call it
+struct ContractInput {
+    path: PathBuf,
+    name: String,
+}
+
+impl ContractInput {
+    fn new(path: PathBuf, name: Option<&str>) -> Self {
+        let name = stdio::contract_name(&path, name);
+        Self { path, name }
+    }
+
+    /// Parse the contract, from stdin for `-`, under its report name
+    fn parse(&self) -> Result<A2mlDocument> {
+        let mut doc = stdio::parse(&self.path)?;
+        doc.path = Some(self.name.clone());
+        Ok(doc)
+    }
+}
+
+/// Settings given on the command line, before union-policy.toml is applied
+#[derive(Default)]
+struct CliSettings {
+    schema: Option<PathBuf>,
+    union: Option<String>,
+    mode: Option<ValidationMode>,
+    required_clauses: Vec<String>,
+    recommended_clauses: Vec<String>,
+}
+
+/// Effective settings for one contract: command-line flags over union-policy.toml
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Resolved {
+    schema: Option<PathBuf>,
+    union: Option<String>,
+    mode: Option<ValidationMode>,
+    required_clauses: Vec<String>,
+    recommended_clauses: Vec<String>,
+    red_flags: Vec<String>,
+    /// K9 `contracts` predicates, by name
+    predicates: Vec<(String, String)>,
+    /// For a K9 'Hunt wrapper, the payload's signature (`None` if unsigned)
+    required_signature: Option<Option<Signature>>,
+    /// Keys the required signature may be made with
+    trusted_keys: Vec<PublicKey>,
+}
+
+impl Resolved {
+    fn require_schema(&self) -> Result<&PathBuf> {
+        self.schema.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("No schema given: pass --schema or --union, or set `schema` in {}", config::CONFIG_FILE)
+        })
+    }
+}
+
+fn resolve(config: Option<&PolicyConfig>, target: &Path, flags: &CliSettings) -> Result<Resolved> {
+    let settings = config.map(|c| c.settings_for(target)).unwrap_or_default();
+
+    let mode = match (flags.mode, &settings.mode) {
+        (Some(mode), _) => Some(mode),
+        (None, Some(name)) => Some(config_value(name, "mode")?),
+        (None, None) => None,
+    };
+    let required_clauses = if flags.required_clauses.is_empty() {
+        settings.required_clauses
+    } else {
+        flags.required_clauses.clone()
+    };
+
+    let union = flags.union.clone().or(settings.union);
+    let schema = match (flags.schema.clone().or(settings.schema), &union) {
+        (Some(schema), _) => Some(schema),
+        (None, Some(name)) => Some(default_schema(config, name.parse::<Union>()?)),
+        (None, None) => None,
+    };
+
+    Ok(Resolved {
+        schema,
+        union,
+        mode,
+        required_clauses,
+        recommended_clauses: flags.recommended_clauses.clone(),
+        red_flags: settings.red_flags,
+        predicates: Vec::new(),
+        required_signature: None,
+        trusted_keys: Vec::new(),
+    })
+}
+
+/// Settings for a K9 wrapper's payload: command-line flags, then the
+/// wrapper's `validation` block, then union-policy.toml
+fn resolve_k9(config: Option<&PolicyConfig>, wrapper: &K9Wrapper, flags: &CliSettings) -> Result<Resolved> {
+    let k9_flags = CliSettings {
+        schema: flags.schema.clone().or_else(|| wrapper.schema.as_deref().map(discovery::normalize)),
+        union: flags.union.clone(),
+        mode: flags.mode,
+        required_clauses: if flags.required_clauses.is_empty() {
+            wrapper.required_clauses.clone()
+        } else {
+            flags.required_clauses.clone()
+        },
+        recommended_clauses: wrapper.recommended_clauses.clone(),
+    };
+    let mut settings = resolve(config, &discovery::normalize(&wrapper.payload), &k9_flags)?;
+    settings.predicates = wrapper.contracts.clone();
+    // The trust level sits in the unsigned wrapper, so the configuration
+    // can insist on signatures whatever it says
+    if wrapper.requires_signature() || config.is_some_and(|c| c.require_signatures) {
+        settings.required_signature = Some(payload_signature(wrapper)?);
+        settings.trusted_keys = trusted_keys(config)?;
+    }
+
+    if flags.required_clauses.is_empty() {
+        if let Some(union) = &settings.union {
+            for clause in wrapper.clauses_for_union(union) {
+                if !settings.required_clauses.contains(clause) {
+                    settings.required_clauses.push(clause.clone());
+                }
             }
-            md
-        }
-        OutputFormat::Html => {
-            format!("<pre>{}</pre>", serde_json::to_string_pretty(&contract)?)
         }
+    }
+    Ok(settings)
+}
+
+/// Signer keys trusted by union-policy.toml; none without one
+fn trusted_keys(config: Option<&PolicyConfig>) -> Result<Vec<PublicKey>> {
+    Ok(config.map(PolicyConfig::trusted_keys).transpose()?.unwrap_or_default())
+}
+
+/// A wrapper payload's signature: the `validation` block's, else a detached
+/// PAYLOAD.sig
+fn payload_signature(wrapper: &K9Wrapper) -> Result<Option<Signature>> {
+    if let Some(signature) = &wrapper.signature {
+        return Ok(Some(signature.clone()));
+    }
+    let detached = signing::detached_path(&discovery::normalize(&wrapper.payload));
+    if !detached.is_file() {
+        return Ok(None);
+    }
+    let signature = Signature::load(&detached).with_context(|| format!("Cannot read signature {:?}", detached))?;
+    Ok(Some(signature))
+}
+
+/// A union's schema: a local copy at its usual path (beside union-policy.toml,
+/// else in the working directory) if there is one, else the built-in copy
+fn default_schema(config: Option<&PolicyConfig>, union: Union) -> PathBuf {
+    match local_schema(config, union.embedded_schema()) {
+        Some(path) => path,
+        None => union.embedded_schema().path(),
+    }
+}
+
+/// The local file overriding a built-in schema, if any
+fn local_schema(config: Option<&PolicyConfig>, schema: &EmbeddedSchema) -> Option<PathBuf> {
+    let relative = Path::new("schemas").join(schema.file_name);
+    let path = match config {
+        Some(config) => config.root.join(relative),
+        None => relative,
     };
+    path.is_file().then_some(path)
+}
 
-    fs::write(&output_path, output)?;
-    println!("✅ Rendered to: {:?}", output_path);
+/// Output format: the flag, else union-policy.toml, else the command's default
+fn resolve_format<T: clap::ValueEnum>(flag: Option<T>, configured: Option<&str>, default: T) -> Result<T> {
+    match (flag, configured) {
+        (Some(format), _) => Ok(format),
+        (None, Some(name)) => config_value(name, "format"),
+        (None, None) => Ok(default),
+    }
+}
 
-    Ok(())
+/// Command-line name of an enum value, for machine-readable output
+fn value_name<T: clap::ValueEnum>(value: T) -> String {
+    value.to_possible_value().map(|v| v.get_name().to_string()).unwrap_or_default()
+}
+
+/// Parse a union-policy.toml value using the same names as the command line
+fn config_value<T: clap::ValueEnum>(value: &str, key: &str) -> Result<T> {
+    T::from_str(value, true)
+        .map_err(|_| anyhow::anyhow!("{}: invalid {} '{}'", config::CONFIG_FILE, key, value))
+}
+
+/// Validator with the settings' union and red flags applied, and every required
+/// clause: the extra ones first, then the union's
+fn union_validator(
+    schema: A2mlDocument,
+    mode: ValidatorMode,
+    settings: &Resolved,
+) -> Result<(Validator, Vec<String>)> {
+    let mut validator = Validator::new(schema, mode)
+        .with_red_flags(settings.red_flags.clone())
+        .with_recommended_clauses(settings.recommended_clauses.clone())
+        .with_predicates(settings.predicates.iter().map(|(name, source)| Predicate::new(name, source)).collect());
+    if let Some(signature) = &settings.required_signature {
+        validator = validator.with_required_signature(signature.clone(), settings.trusted_keys.clone());
+    }
+    let mut required_clauses = settings.required_clauses.clone();
+
+    if let Some(union_name) = &settings.union {
+        let union_enum = union_name.parse::<Union>()?;
+        validator = validator.with_union(union_enum);
+        required_clauses.extend(union_enum.required_clauses().iter().map(|s| s.to_string()));
+    }
+
+    Ok((validator, required_clauses))
 }
 
-fn cmd_check_schema(schema_path: PathBuf) -> Result<()> {
+/// Rules the language server checks a document against, as `validate` would
+fn lsp_rules(config: Option<&PolicyConfig>, path: &Path, flags: &CliSettings) -> Result<Option<lsp::Rules>> {
+    let settings = resolve(config, path, flags)?;
+    let Some(schema_path) = &settings.schema else {
+        return Ok(None);
+    };
+    let schema = stdio::parse(schema_path)?;
+    let mode = settings.mode.unwrap_or(ValidationMode::Checked);
+    let (validator, required_clauses) = union_validator(schema, mode.into(), &settings)?;
+    let union = settings.union.as_deref().map(str::parse::<Union>).transpose()?;
+    Ok(Some(lsp::Rules::new(validator, required_clauses, union)))
+}
+
+fn cmd_check_schema(out: &Output, schema_path: PathBuf) -> Result<Status> {
     log::info!("Checking schema: {:?}", schema_path);
 
-    let schema = parse_a2ml_file(&schema_path)?;
+    let schema = stdio::parse(&schema_path)?;
 
-    println!("✅ Schema is valid A2ML");
-    println!("   Sections: {}", schema.sections.len());
-    println!("   References: {}", schema.references.len());
-    println!("   Requirements: {}", schema.requirements.len());
+    let info = DocumentInfo::of(&schema);
+    say!(out, "✅ Schema is valid A2ML");
+    if let Some(name) = &info.name {
+        say!(out, "   Name: {}", name);
+    }
+    if let Some(version) = &info.version {
+        say!(out, "   Version: {}", version);
+    }
+    say!(out, "   Sections: {}", schema.sections.len());
+    say!(out, "   References: {}", schema.references.len());
+    say!(out, "   Requirements: {}", schema.requirements.len());
 
     // Check for common schema elements
+    let mut warnings = Vec::new();
     if schema.abstract_text.is_none() {
-        println!("⚠️  Warning: Schema missing abstract");
+        warnings.push("Schema missing abstract");
     }
     if schema.references.is_empty() {
-        println!("⚠️  Warning: Schema has no references");
+        warnings.push("Schema has no references");
+    }
+    for warning in &warnings {
+        say!(out, "⚠️  Warning: {}", warning);
     }
 
-    Ok(())
+    let status = if warnings.is_empty() { Status::Clean } else { Status::Warnings };
+    out.emit("check-schema", status, json!({
+        "schema": schema_path,
+        "name": info.name,
+        "version": info.version,
+        "sha256": info.sha256,
+        "sections": schema.sections.len(),
+        "references": schema.references.len(),
+        "requirements": schema.requirements.len(),
+        "warnings": warnings,
+    }), None)?;
+
+    Ok(status)
+}
+
+fn cmd_schemas_list(out: &Output, config: Option<&PolicyConfig>) -> Result<Status> {
+    let mut schemas = Vec::new();
+    for schema in EMBEDDED_SCHEMAS {
+        let info = DocumentInfo::of(&schema.parse()?);
+        let unions: Vec<&str> = schema.unions().iter().map(Union::as_str).collect();
+        // Only a union's default schema is looked up locally
+        let local = if unions.is_empty() { None } else { local_schema(config, schema) };
+
+        say!(
+            out,
+            "{:<32} {:<5} {}",
+            schema.name(),
+            unions.join(","),
+            info.name.as_deref().unwrap_or("")
+        );
+        if let Some(local) = &local {
+            say!(out, "{:<32} overridden by {}", "", local.display());
+        }
+        schemas.push(json!({
+            "name": schema.name(),
+            "path": schema.path(),
+            "title": info.name,
+            "version": info.version,
+            "sha256": info.sha256,
+            "unions": unions,
+            "override": local,
+        }));
+    }
+
+    out.emit("schemas", Status::Clean, json!({ "schemas": schemas }), Some(("schemas", "schema")))?;
+    Ok(Status::Clean)
+}
+
+fn cmd_schemas_show(out: &Output, name: String) -> Result<Status> {
+    let schema = match EmbeddedSchema::find(&name) {
+        Some(schema) => schema,
+        None => name
+            .parse::<Union>()
+            .map(|union| union.embedded_schema())
+            .map_err(|_| anyhow::anyhow!("No built-in schema '{}' (see `schemas list`)", name))?,
+    };
+
+    if out.is_human() {
+        print!("{}", schema.source);
+    }
+    out.emit("schemas", Status::Clean, json!({
+        "name": schema.name(),
+        "path": schema.path(),
+        "source": schema.source,
+    }), None)?;
+    Ok(Status::Clean)
+}
+
+fn cmd_schemas_export(out: &Output, dir: PathBuf, force: bool) -> Result<Status> {
+    std::fs::create_dir_all(&dir).with_context(|| format!("Cannot create {:?}", dir))?;
+
+    let mut files = Vec::new();
+    for schema in EMBEDDED_SCHEMAS {
+        let path = dir.join(schema.file_name);
+        let written = force || !path.exists();
+        if written {
+            std::fs::write(&path, schema.source).with_context(|| format!("Cannot write {:?}", path))?;
+            say!(out, "✅ {}", path.display());
+        } else {
+            say!(out, "   {} exists; kept (use --force to overwrite)", path.display());
+        }
+        files.push(json!({ "name": schema.name(), "file": path, "written": written }));
+    }
+
+    out.emit("schemas", Status::Clean, json!({ "dir": dir, "files": files }), Some(("files", "file")))?;
+    Ok(Status::Clean)
+}
+
+fn cmd_keygen(out: &Output, output: PathBuf, force: bool) -> Result<Status> {
+    let public_path = public_key_path(&output);
+    for path in [&output, &public_path] {
+        if path.exists() && !force {
+            anyhow::bail!("{:?} exists; use --force to overwrite", path);
+        }
+    }
+
+    let mut seed = [0u8; 32];
+    getrandom::getrandom(&mut seed).map_err(|e| anyhow::anyhow!("No system randomness: {}", e))?;
+    let key = SecretKey::from_seed(seed);
+    write_secret(&output, &key.to_text()).with_context(|| format!("Cannot write {:?}", output))?;
+    let public_key = key.public_key();
+    std::fs::write(&public_path, format!("{}\n", public_key)).with_context(|| format!("Cannot write {:?}", public_path))?;
+
+    say!(out, "✅ Secret key: {} (keep it private)", output.display());
+    say!(out, "✅ Public key: {}", public_path.display());
+    say!(out, "   {}", public_key);
+
+    out.emit("keygen", Status::Clean, json!({
+        "secret_key": output,
+        "public_key": public_path,
+        "key": public_key.to_string(),
+    }), None)?;
+    Ok(Status::Clean)
+}
+
+/// `key` → `key.pub`
+fn public_key_path(secret: &Path) -> PathBuf {
+    let mut name = secret.as_os_str().to_os_string();
+    name.push(".pub");
+    PathBuf::from(name)
+}
+
+/// Write a secret key readable by the owner only
+fn write_secret(path: &Path, text: &str) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    std::io::Write::write_all(&mut options.open(path)?, text.as_bytes())
+}
+
+/// What `sign` and `verify` cover in a file: its kind, canonical form and,
+/// for a wrapper, the wrapper
+fn signed_content(file: &Path) -> Result<(SignedKind, String, Option<K9Wrapper>)> {
+    if stdio::is_stdio(file) {
+        anyhow::bail!("Signing needs a file; stdin has nowhere to keep the signature");
+    }
+    if k9::is_k9(file) {
+        let wrapper = K9Wrapper::load(file)?;
+        let payload = stdio::parse(&discovery::normalize(&wrapper.payload))?;
+        return Ok((SignedKind::Contract, signing::canonical_contract(&payload), Some(wrapper)));
+    }
+    if file.extension().is_some_and(|ext| ext == "json") {
+        let source = std::fs::read_to_string(file).with_context(|| format!("Cannot read {:?}", file))?;
+        let report: serde_json::Value = serde_json::from_str(&source).with_context(|| format!("{:?} is not JSON", file))?;
+        return Ok((SignedKind::Report, signing::canonical_json(&report), None));
+    }
+    let contract = stdio::parse(file)?;
+    Ok((SignedKind::Contract, signing::canonical_contract(&contract), None))
+}
+
+fn cmd_sign(out: &Output, file: PathBuf, key_path: PathBuf, detached: bool) -> Result<Status> {
+    let key_text = std::fs::read_to_string(&key_path).with_context(|| format!("Cannot read key {:?}", key_path))?;
+    let key = SecretKey::from_text(&key_text).with_context(|| format!("Bad secret key {:?}", key_path))?;
+    let (kind, canonical, wrapper) = signed_content(&file)?;
+    let signature = key.sign(kind, &canonical);
+
+    let written = match &wrapper {
+        Some(_) if !detached => {
+            let source = std::fs::read_to_string(&file)?;
+            let signed = k9::set_validation_fields(&source, &[
+                ("checksum", &signature.checksum),
+                ("signature", &signature.signature),
+                ("signed_by", &signature.signed_by),
+            ])?;
+            std::fs::write(&file, signed).with_context(|| format!("Cannot write {:?}", file))?;
+            file.clone()
+        }
+        _ => {
+            let target = wrapper.as_ref().map_or(file.clone(), |w| discovery::normalize(&w.payload));
+            let path = signing::detached_path(&target);
+            let text = format!("{}\n", serde_json::to_string_pretty(&signature)?);
+            std::fs::write(&path, text).with_context(|| format!("Cannot write {:?}", path))?;
+            path
+        }
+    };
+
+    say!(out, "✅ Signed {} {:?}", kind.as_str(), file);
+    say!(out, "   Checksum:  {}", signature.checksum);
+    say!(out, "   Signed by: {}", signature.signed_by);
+    say!(out, "   Saved to:  {}", written.display());
+
+    out.emit("sign", Status::Clean, json!({
+        "file": file,
+        "kind": kind.as_str(),
+        "checksum": signature.checksum,
+        "signed_by": signature.signed_by,
+        "written": written,
+    }), None)?;
+    Ok(Status::Clean)
+}
+
+fn cmd_verify(
+    out: &Output,
+    config: Option<&PolicyConfig>,
+    file: PathBuf,
+    key_path: Option<PathBuf>,
+    signature_path: Option<PathBuf>,
+) -> Result<Status> {
+    // `signed_by` travels with the signature, so the signer must be a key
+    // the user already trusts: --key, else union-policy.toml's trusted_keys
+    let trusted = match &key_path {
+        Some(path) => vec![PublicKey::load(path).with_context(|| format!("Bad public key {:?}", path))?],
+        None => trusted_keys(config)?,
+    };
+    let (kind, canonical, wrapper) = signed_content(&file)?;
+
+    let (signature, source) = match (&signature_path, &wrapper) {
+        (Some(path), _) => (Some(Signature::load(path)?), path.display().to_string()),
+        (None, Some(wrapper)) => (payload_signature(wrapper)?, "K9 validation block or payload .sig".to_string()),
+        (None, None) => {
+            let path = signing::detached_path(&file);
+            let signature = path.is_file().then(|| Signature::load(&path)).transpose()?;
+            (signature, path.display().to_string())
+        }
+    };
+
+    let result = match &signature {
+        None => Err(format!("no signature found ({})", source)),
+        Some(signature) => signature.verify(&canonical, &trusted).map_err(|e| match e {
+            error::PolicyError::SignatureError(reason) => reason,
+            e => e.to_string(),
+        }),
+    };
+
+    let status = match &result {
+        Ok(()) => {
+            say!(out, "✅ Signature valid: {} {:?}", kind.as_str(), file);
+            Status::Clean
+        }
+        Err(reason) => {
+            say!(out, "❌ Signature check failed: {:?}", file);
+            say!(out, "   {}", reason);
+            Status::Violations
+        }
+    };
+    if let Some(signature) = &signature {
+        say!(out, "   Signed by: {}", signature.signed_by);
+        if trusted.is_empty() {
+            say!(out, "   (pass --key, or set trusted_keys in {}, to name the signers you trust)", config::CONFIG_FILE);
+        }
+    }
+
+    out.emit("verify", status, json!({
+        "file": file,
+        "kind": kind.as_str(),
+        "signature": source,
+        "signed_by": signature.as_ref().map(|s| &s.signed_by),
+        "trusted_key": key_path,
+        "trusted_keys": trusted.iter().map(PublicKey::to_string).collect::<Vec<_>>(),
+        "valid": result.is_ok(),
+        "reason": result.err(),
+    }), None)?;
+    Ok(status)
+}
+
+fn cmd_fmt(out: &Output, files: Vec<PathBuf>, check: bool) -> Result<Status> {
+    let mut unformatted = 0;
+    let mut results = Vec::new();
+
+    for file in &files {
+        log::info!("Formatting: {:?}", file);
+
+        let source = stdio::read_to_string(file)
+            .with_context(|| format!("Failed to read {:?}", file))?;
+        let formatted = formatter::format_source(&source)
+            .with_context(|| format!("Failed to format {:?}", file))?;
+
+        let changed = formatted != source;
+        results.push(json!({ "file": file, "canonical": !changed, "rewritten": changed && !check }));
+
+        // Like a filter, stdin is always echoed back formatted
+        if stdio::is_stdio(file) && !check {
+            stdio::write(file, formatted)?;
+            continue;
+        }
+        if !changed {
+            continue;
+        }
+
+        if check {
+            say!(out, "✗ Would reformat: {:?}", file);
+            unformatted += 1;
+        } else {
+            stdio::write(file, formatted)?;
+            say!(out, "✅ Formatted: {:?}", file);
+        }
+    }
+
+    let status = if unformatted > 0 {
+        say!(out, "{} of {} file(s) not canonically formatted", unformatted, files.len());
+        Status::Violations
+    } else {
+        Status::Clean
+    };
+
+    out.emit("fmt", status, json!({
+        "check": check,
+        "unformatted": unformatted,
+        "files": results,
+    }), Some(("files", "file")))?;
+
+    Ok(status)
 }
@@ -0,0 +1,350 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Markdown rendering of contracts
+//!
+//! Plain output writes back every block of the parsed contract so model
+//! contracts can be published as-is. When a validation report is supplied,
+//! findings are added as GitHub-style callouts (`> [!WARNING]`) under the
+//! section that triggered them.
+
+use crate::parser::{A2mlDocument, ContentBlock, Section};
+use crate::validator::{Finding, Severity, ValidationReport};
+
+/// Render a contract to Markdown, annotating findings if a report is given
+pub fn render_contract(doc: &A2mlDocument, report: Option<&ValidationReport>) -> String {
+    let mut md = String::new();
+
+    // A leading level-1 heading is the document title; the directives
+    // follow it, as in the source
+    let (title, sections) = match doc.sections.split_first() {
+        Some((first, rest)) if first.level == 1 => (Some(first), rest),
+        _ => (None, doc.sections.as_slice()),
+    };
+
+    for block in &doc.preamble {
+        md.push_str(&render_block(block));
+    }
+
+    if let Some(title) = title {
+        md.push_str(&format!("# {}\n\n", title.heading));
+    }
+
+    if let Some(report) = report {
+        md.push_str(&summary_callout(report));
+    }
+
+    if let Some(abstract_text) = &doc.abstract_text {
+        md.push_str("## Abstract\n\n");
+        md.push_str(abstract_text);
+        md.push_str("\n\n");
+    }
+
+    if !doc.requirements.is_empty() {
+        md.push_str("## Requirements\n\n");
+        for requirement in &doc.requirements {
+            md.push_str(&format!("- {}\n", requirement));
+        }
+        md.push('\n');
+    }
+
+    if let Some(title) = title {
+        if let Some(report) = report {
+            md.push_str(&section_callouts(report, title));
+        }
+        for block in &title.content {
+            md.push_str(&render_block(block));
+        }
+    }
+
+    for section in sections {
+        md.push_str(&render_section(section, report));
+    }
+
+    if !doc.references.is_empty() {
+        md.push_str("## References\n\n");
+        for reference in &doc.references {
+            if reference.id.is_empty() {
+                md.push_str(&format!("- {}", reference.text));
+            } else {
+                md.push_str(&format!("- [{}] {}", reference.id, reference.text));
+            }
+            if let Some(url) = &reference.url {
+                md.push_str(&format!(" <{}>", url));
+            }
+            md.push('\n');
+        }
+        md.push('\n');
+    }
+
+    format!("{}\n", md.trim_end())
+}
+
+fn render_section(section: &Section, report: Option<&ValidationReport>) -> String {
+    let mut md = format!("{} {}\n\n", "#".repeat(section.level.clamp(1, 6) as usize), section.heading);
+
+    if let Some(report) = report {
+        md.push_str(&section_callouts(report, section));
+    }
+
+    for block in &section.content {
+        md.push_str(&render_block(block));
+    }
+
+    md
+}
+
+fn render_block(block: &ContentBlock) -> String {
+    match block {
+        ContentBlock::Paragraph(text) => format!("{}\n\n", text),
+        ContentBlock::BulletList(items) => {
+            let mut md = String::new();
+            for item in items {
+                md.push_str(&format!("- {}\n", item));
+            }
+            md.push('\n');
+            md
+        }
+        ContentBlock::OrderedList(items) => {
+            let mut md = String::new();
+            for item in items {
+                md.push_str(&format!("{} {}\n", item.marker, item.text));
+            }
+            md.push('\n');
+            md
+        }
+        ContentBlock::Table { headers, rows } => {
+            let mut md = format!("| {} |\n", headers.join(" | "));
+            md.push_str(&format!("|{}\n", "---|".repeat(headers.len().max(1))));
+            for row in rows {
+                md.push_str(&format!("| {} |\n", row.join(" | ")));
+            }
+            md.push('\n');
+            md
+        }
+        ContentBlock::CodeBlock { language, code } => {
+            let mut md = format!("```{}\n{}", language.as_deref().unwrap_or(""), code);
+            if !code.ends_with('\n') {
+                md.push('\n');
+            }
+            md.push_str("```\n\n");
+            md
+        }
+        ContentBlock::HorizontalRule => "---\n\n".to_string(),
+    }
+}
+
+fn summary_callout(report: &ValidationReport) -> String {
+    let predicates = predicate_findings(report);
+    let mut md = if report.valid {
+        String::from("> [!NOTE]\n> **VALID:** contract complies with the schema.\n")
+    } else {
+        let failed = predicates.iter().filter(|f| f.severity == Severity::Must).count();
+        format!(
+            "> [!CAUTION]\n> **INVALID:** {} error(s), {} warning(s), {} red flag(s){}.\n",
+            report.errors.len(),
+            report.warnings.len(),
+            report.red_flags.len(),
+            if failed > 0 { format!(", {} failed predicate(s)", failed) } else { String::new() },
+        )
+    };
+
+    for (severity, kind) in [(Severity::Must, "required"), (Severity::Should, "recommended")] {
+        let missing: Vec<&str> = report
+            .required_clauses
+            .iter()
+            .filter(|c| !c.present && c.severity == severity)
+            .map(|c| c.clause.as_str())
+            .collect();
+        if !missing.is_empty() {
+            md.push_str(&format!(">\n> Missing {} clauses:\n", kind));
+            for clause in missing {
+                md.push_str(&format!("> - `{}`\n", clause));
+            }
+        }
+    }
+
+    // Findings without a section have nowhere else to go
+    for error in report.errors.iter().filter(|e| e.location.is_none()) {
+        md.push_str(&format!(">\n> **{:?}:** {}\n", error.kind, error.message));
+    }
+    for warning in report.warnings.iter().filter(|w| w.location.is_none()) {
+        md.push_str(&format!(">\n> {}\n", warning.message));
+    }
+    for finding in predicates.iter().filter(|f| f.location.is_none()) {
+        md.push_str(&format!(">\n> {}\n", predicate_line(finding)));
+    }
+
+    md.push('\n');
+    md
+}
+
+fn section_callouts(report: &ValidationReport, section: &Section) -> String {
+    let mut md = String::new();
+    let predicates: Vec<Finding> = predicate_findings(report)
+        .into_iter()
+        .filter(|f| f.location.as_deref() == Some(section.heading.as_str()))
+        .collect();
+
+    // Red flags and failed predicates are MUST-level findings, so they sit with the errors
+    let errors: Vec<String> = report
+        .errors_at(&section.heading)
+        .map(|e| format!("> **{:?}:** {}\n", e.kind, e.message))
+        .chain(
+            report
+                .red_flags_at(&section.heading)
+                .map(|f| format!("> **Red flag:** \"{}\"\n", f.excerpt)),
+        )
+        .chain(
+            predicates
+                .iter()
+                .filter(|f| f.severity == Severity::Must)
+                .map(|f| format!("> {}\n", predicate_line(f))),
+        )
+        .collect();
+    if !errors.is_empty() {
+        md.push_str("> [!CAUTION]\n");
+        md.push_str(&errors.join(">\n"));
+        md.push('\n');
+    }
+
+    let warnings: Vec<String> = report
+        .warnings_at(&section.heading)
+        .map(|w| format!("> {}\n", w.message))
+        .chain(
+            predicates
+                .iter()
+                .filter(|f| f.severity != Severity::Must)
+                .map(|f| format!("> {}\n", predicate_line(f))),
+        )
+        .collect();
+    if !warnings.is_empty() {
+        md.push_str("> [!WARNING]\n");
+        md.push_str(&warnings.join(">\n"));
+        md.push('\n');
+    }
+
+    md
+}
+
+/// K9 predicates that failed or could not be checked, as `findings()` reports them
+fn predicate_findings(report: &ValidationReport) -> Vec<Finding> {
+    report.findings().into_iter().filter(|f| f.rule.starts_with("k9/")).collect()
+}
+
+fn predicate_line(finding: &Finding) -> String {
+    match &finding.help {
+        Some(help) => format!("**`{}`:** {} {}", finding.rule, finding.message, help),
+        None => format!("**`{}`:** {}", finding.rule, finding.message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_a2ml_string;
+    use crate::predicate::Predicate;
+    use crate::validator::{ClauseCheck, RedFlag};
+
+    const CONTRACT: &str = r#"# Freelance Contract
+
+@abstract:
+A model contract.
+@end
+
+@requires:
+- Late Payment of Commercial Debts Act 1998
+@end
+
+---
+
+## 1. Payment
+
+Payment within **NET 30** days [1].
+
+- Invoice on delivery
+- Late penalty of 8%
+
+### 1.1 Rates
+
+```text
+day rate: 350
+```
+
+@refs:
+[1] Late Payment Act https://www.legislation.gov.uk/ukpga/1998/20
+@end
+"#;
+
+    #[test]
+    fn test_render_keeps_all_blocks_and_levels() {
+        let doc = parse_a2ml_string(CONTRACT).unwrap();
+        let md = render_contract(&doc, None);
+
+        assert!(md.starts_with("# Freelance Contract\n"));
+        assert!(md.contains("\n## 1. Payment\n"));
+        assert!(md.contains("\n### 1.1 Rates\n"));
+        assert!(md.contains("Payment within **NET 30** days [1]."));
+        assert!(md.contains("- Late penalty of 8%"));
+        assert!(md.contains("```text\nday rate: 350\n```"));
+        assert!(md.contains("- Late Payment of Commercial Debts Act 1998"));
+        assert!(md.contains("- [1] Late Payment Act <https://www.legislation.gov.uk/ukpga/1998/20>"));
+    }
+
+    #[test]
+    fn test_render_reparses_to_same_sections() {
+        let doc = parse_a2ml_string(CONTRACT).unwrap();
+        let reparsed = parse_a2ml_string(&render_contract(&doc, None)).unwrap();
+
+        let headings: Vec<_> = reparsed.sections.iter().map(|s| (s.level, s.heading.clone())).collect();
+        assert!(headings.contains(&(2, "1. Payment".to_string())));
+        assert!(headings.contains(&(3, "1.1 Rates".to_string())));
+    }
+
+    #[test]
+    fn test_render_annotated_callouts() {
+        let doc = parse_a2ml_string(CONTRACT).unwrap();
+        let mut report = ValidationReport::new("c.a2ml".to_string(), "s.a2ml".to_string());
+        report.add_red_flag(RedFlag {
+            pattern: "net 30".to_string(),
+            excerpt: "NET 30".to_string(),
+            location: Some("1. Payment".to_string()),
+            line: Some(19),
+        });
+        report.add_warning("No references".to_string(), Some("1.1 Rates".to_string()));
+
+        let md = render_contract(&doc, Some(&report));
+        assert!(md.contains("> [!CAUTION]\n> **INVALID:** 0 error(s), 1 warning(s), 1 red flag(s)."));
+        assert!(md.contains("## 1. Payment\n\n> [!CAUTION]\n> **Red flag:** \"NET 30\"\n"));
+        assert!(md.contains("### 1.1 Rates\n\n> [!WARNING]\n> No references\n"));
+    }
+
+    #[test]
+    fn test_summary_splits_clauses_and_shows_predicates() {
+        let doc = parse_a2ml_string(CONTRACT).unwrap();
+        let mut report = ValidationReport::new("c.a2ml".to_string(), "s.a2ml".to_string());
+        for (clause, severity) in [("kill-fee", Severity::Must), ("source-protection", Severity::Should)] {
+            report.add_clause_check(ClauseCheck {
+                clause: clause.to_string(),
+                present: false,
+                value: None,
+                expected: None,
+                section: None,
+                severity,
+                help: None,
+            });
+        }
+        let predicates = [
+            ("pay-window", "fun contract => let d = contract.payment.net_days in std.contract.from_predicate (fun d => d <= 14)"),
+            ("day-rate", "fun contract => let r = contract.payment.day_rate_gbp in std.contract.from_predicate (fun r => r >= 350)"),
+        ];
+        for (name, source) in predicates {
+            report.add_predicate_check(Predicate::new(name, source).check(&doc));
+        }
+
+        let md = render_contract(&doc, Some(&report));
+        assert!(md.contains("1 failed predicate(s)."), "{}", md);
+        assert!(md.contains("> Missing required clauses:\n> - `kill-fee`\n"));
+        assert!(md.contains("> Missing recommended clauses:\n> - `source-protection`\n"));
+        assert!(md.contains("> **`k9/day-rate`:** Contract predicate day-rate not checked"));
+        assert!(md.contains("## 1. Payment\n\n> [!CAUTION]\n> **`k9/pay-window`:** Contract predicate pay-window failed"));
+    }
+}
@@ -0,0 +1,183 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Machine-readable command output (`--output-format`)
+//!
+//! With `json`, each command prints one JSON object on stdout. With `ndjson`,
+//! it prints one object per line: list items (findings, contracts, files, schemas)
+//! first, each as its own record, then the result. Human text is suppressed
+//! in both, and logs stay on stderr, so stdout is always parseable. When a
+//! command writes its report to stdout (`--output -`), its own text and
+//! records move to stderr instead.
+//!
+//! Every object starts with the same header:
+//!
+//! ```json
+//! {"schema_version": 1, "command": "validate", "record": "result", "status": "violations", ...}
+//! ```
+//!
+//! `record` is `result` for the final object, the item kind (`finding`,
+//! `contract`, `file`, `schema`, or `change` under `batch --watch`) for
+//! streamed items, or `error` when the command stopped early. `status` is
+//! `clean`, `warnings`, `violations` or `parse-errors`, matching the exit
+//! code. Within a `schema_version`, fields are only ever added. In watch
+//! mode each run prints its own records.
+
+use crate::error::Result;
+use crate::exit::Status;
+use crate::validator::Finding;
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+/// Version of the JSON output; bumped when a field is removed or changes meaning
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// How commands report their results on stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputMode {
+    /// Text for people
+    Human,
+    /// One JSON object
+    Json,
+    /// One JSON object per line, items streamed before the result
+    Ndjson,
+}
+
+/// Writer for a command's result in the chosen mode
+#[derive(Debug, Clone, Copy)]
+pub struct Output {
+    mode: OutputMode,
+    to_stderr: bool,
+}
+
+impl Output {
+    pub fn new(mode: OutputMode) -> Self {
+        Self { mode, to_stderr: false }
+    }
+
+    /// The same output, moved to stderr if stdout carries a report
+    pub fn redirected(self, report_on_stdout: bool) -> Self {
+        Self {
+            to_stderr: self.to_stderr || report_on_stdout,
+            ..self
+        }
+    }
+
+    /// Whether human text should be printed
+    pub fn is_human(&self) -> bool {
+        self.mode == OutputMode::Human
+    }
+
+    /// Print a line of human text; a no-op in machine modes
+    pub fn say(&self, line: fmt::Arguments) {
+        if self.is_human() {
+            self.print(line);
+        }
+    }
+
+    fn print(&self, line: impl fmt::Display) {
+        if self.to_stderr {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+    }
+
+    /// Print a command's result; a no-op in human mode
+    ///
+    /// `stream` names a list field of `body` and its record kind, e.g.
+    /// `("findings", "finding")`. In ndjson mode its items are printed as
+    /// separate records and left out of the result.
+    pub fn emit(&self, command: &str, status: Status, body: Value, stream: Option<(&str, &str)>) -> Result<()> {
+        let header = |record: &str| {
+            json!({
+                "schema_version": SCHEMA_VERSION,
+                "command": command,
+                "record": record,
+            })
+        };
+
+        match self.mode {
+            OutputMode::Human => {}
+            OutputMode::Json => {
+                let mut result = header("result");
+                result["status"] = json!(status.as_str());
+                self.print(serde_json::to_string_pretty(&merge(result, body))?);
+            }
+            OutputMode::Ndjson => {
+                let mut body = body;
+                if let Some((key, record)) = stream {
+                    let items = body.as_object_mut().and_then(|fields| fields.remove(key));
+                    if let Some(Value::Array(items)) = items {
+                        for item in items {
+                            self.print(serde_json::to_string(&merge(header(record), item))?);
+                        }
+                    }
+                }
+                let mut result = header("result");
+                result["status"] = json!(status.as_str());
+                self.print(serde_json::to_string(&merge(result, body))?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Print an error that stopped the command; a no-op in human mode
+    pub fn error(&self, command: &str, exit_code: u8, error: &anyhow::Error) -> Result<()> {
+        let record = json!({
+            "schema_version": SCHEMA_VERSION,
+            "command": command,
+            "record": "error",
+            "exit_code": exit_code,
+            "message": error.to_string(),
+            "causes": error.chain().skip(1).map(|cause| cause.to_string()).collect::<Vec<_>>(),
+        });
+        match self.mode {
+            OutputMode::Human => {}
+            OutputMode::Json => self.print(serde_json::to_string_pretty(&record)?),
+            OutputMode::Ndjson => self.print(serde_json::to_string(&record)?),
+        }
+        Ok(())
+    }
+}
+
+/// A finding as it appears in every command's output
+pub fn finding(finding: &Finding) -> Value {
+    json!({
+        "rule": finding.rule,
+        "severity": finding.severity.as_str(),
+        "message": finding.message,
+        "section": finding.location,
+        "line": finding.line,
+        "help": finding.help,
+    })
+}
+
+/// Fields of `body` appended after those of `header`
+fn merge(header: Value, body: Value) -> Value {
+    let mut fields: Map<String, Value> = match header {
+        Value::Object(fields) => fields,
+        _ => Map::new(),
+    };
+    if let Value::Object(body) = body {
+        for (key, value) in body {
+            // Consumers branch on the header, so a body field must not replace it
+            debug_assert!(!fields.contains_key(&key), "output field `{}` clashes with the header", key);
+            fields.entry(key).or_insert(value);
+        }
+    }
+    Value::Object(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_comes_first() {
+        let merged = merge(
+            json!({"schema_version": SCHEMA_VERSION, "command": "get-clause"}),
+            json!({"clause": "pay", "found": true}),
+        );
+        let keys: Vec<_> = merged.as_object().unwrap().keys().cloned().collect();
+        assert_eq!(keys, ["schema_version", "command", "clause", "found"]);
+    }
+}
@@ -12,21 +12,25 @@ use crate::error::{PolicyError, Result};
 use nom::{
     IResult,
     branch::alt,
-    bytes::complete::{tag, take_until, take_while, take_while1, is_not},
-    character::complete::{char, line_ending, multispace0, multispace1, not_line_ending, space0, space1},
-    combinator::{map, opt, recognize, value},
-    multi::{many0, many1, separated_list0, separated_list1},
-    sequence::{delimited, pair, preceded, terminated, tuple},
+    bytes::complete::{tag, take_until, take_while1},
+    character::complete::{char, line_ending, multispace0, not_line_ending, space0, space1},
+    combinator::{eof, map, opt, verify},
+    multi::{many0, many1},
+    sequence::{preceded, terminated, tuple},
 };
 use std::path::Path;
 use std::fs;
 
 /// Represents a parsed A2ML document
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct A2mlDocument {
     /// Document abstract
     pub abstract_text: Option<String>,
 
+    /// Content before the first heading (a cover note, fee schedule, ...)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preamble: Vec<ContentBlock>,
+
     /// Document sections
     pub sections: Vec<Section>,
 
@@ -36,11 +40,17 @@ pub struct A2mlDocument {
     /// Requirements (external dependencies)
     pub requirements: Vec<String>,
 
-    /// Raw source text (for preservation)
+    /// Raw source text, byte-for-byte (see [`crate::cst::SyntaxTree`])
     pub raw: String,
+
+    /// Where the document came from (a path, or a name for piped input);
+    /// not part of its content
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+/// A heading and the content under it, up to the next heading
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Section {
     /// Section heading text
     pub heading: String,
@@ -54,20 +64,47 @@ pub struct Section {
     /// Attestations in this section
     pub attestations: Vec<Attestation>,
 
-    /// Line number where section starts
+    /// Line number (1-based) of the section heading
     pub line_number: usize,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+/// Sections compare by content, not by where they sit in the source
+impl PartialEq for Section {
+    fn eq(&self, other: &Self) -> bool {
+        self.heading == other.heading
+            && self.level == other.level
+            && self.content == other.content
+            && self.attestations == other.attestations
+    }
+}
+
+/// A block of section content
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum ContentBlock {
     Paragraph(String),
     BulletList(Vec<String>),
+    OrderedList(Vec<OrderedItem>),
     Table { headers: Vec<String>, rows: Vec<Vec<String>> },
     CodeBlock { language: Option<String>, code: String },
     HorizontalRule,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+/// An ordered-list item with its number as written (`3)`, `1.`)
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct OrderedItem {
+    pub marker: String,
+    pub text: String,
+}
+
+impl OrderedItem {
+    /// The number in the marker
+    pub fn number(&self) -> u64 {
+        self.marker.trim_end_matches(['.', ')']).parse().unwrap_or(1)
+    }
+}
+
+/// An `**Attestation:**` line: what a section commits to, and how strongly
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Attestation {
     /// Claim being attested
     pub claim: String,
@@ -79,9 +116,10 @@ pub struct Attestation {
     pub reference: Option<String>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+/// An `@refs` entry
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Reference {
-    /// Reference ID (e.g., "1")
+    /// Reference ID (e.g., "1"); empty for unnumbered entries
     pub id: String,
 
     /// Reference text
@@ -91,6 +129,38 @@ pub struct Reference {
     pub url: Option<String>,
 }
 
+/// Documents compare by content; `raw` is the source they came from
+impl PartialEq for A2mlDocument {
+    fn eq(&self, other: &Self) -> bool {
+        self.abstract_text == other.abstract_text
+            && self.preamble == other.preamble
+            && self.sections == other.sections
+            && self.references == other.references
+            && self.requirements == other.requirements
+    }
+}
+
+impl Section {
+    /// Plain text of the section body (paragraphs, list items, table cells, code)
+    pub fn text(&self) -> String {
+        let mut parts = Vec::new();
+        for block in &self.content {
+            match block {
+                ContentBlock::Paragraph(text) => parts.push(text.clone()),
+                ContentBlock::BulletList(items) => parts.extend(items.iter().cloned()),
+                ContentBlock::OrderedList(items) => parts.extend(items.iter().map(|item| item.text.clone())),
+                ContentBlock::Table { headers, rows } => {
+                    parts.push(headers.join(" "));
+                    parts.extend(rows.iter().map(|row| row.join(" ")));
+                }
+                ContentBlock::CodeBlock { code, .. } => parts.push(code.clone()),
+                ContentBlock::HorizontalRule => {}
+            }
+        }
+        parts.join("\n")
+    }
+}
+
 /// Parse an A2ML file
 pub fn parse_a2ml_file(path: &Path) -> Result<A2mlDocument> {
     log::debug!("Parsing A2ML file: {:?}", path);
@@ -100,7 +170,9 @@ pub fn parse_a2ml_file(path: &Path) -> Result<A2mlDocument> {
     }
 
     let content = fs::read_to_string(path)?;
-    parse_a2ml_string(&content)
+    let mut doc = parse_a2ml_string(&content)?;
+    doc.path = Some(path.to_string_lossy().to_string());
+    Ok(doc)
 }
 
 /// Parse A2ML from a string
@@ -108,7 +180,20 @@ pub fn parse_a2ml_string(content: &str) -> Result<A2mlDocument> {
     log::debug!("Parsing A2ML from string ({} bytes)", content.len());
 
     match document(content) {
-        Ok((_, doc)) => Ok(doc),
+        Ok((rest, mut doc)) if rest.trim().is_empty() => {
+            doc.raw = content.to_string();
+            Ok(doc)
+        }
+        Ok((rest, _)) => {
+            // Anything the grammar does not recognise is an error rather than
+            // silently dropped
+            let line = content[..content.len() - rest.len()].lines().count() + 1;
+            Err(PolicyError::ParseError(format!(
+                "Unexpected content at line {}: {}",
+                line,
+                rest.lines().next().unwrap_or("").chars().take(50).collect::<String>()
+            )))
+        }
         Err(e) => {
             let error_msg = match e {
                 nom::Err::Error(e) | nom::Err::Failure(e) => {
@@ -125,33 +210,72 @@ pub fn parse_a2ml_string(content: &str) -> Result<A2mlDocument> {
 // Parser Combinators
 // ============================================================================
 
+/// Top-level items that may appear in any order in an A2ML document
+enum TopLevel {
+    Abstract(String),
+    Requires(Vec<String>),
+    Refs(Vec<Reference>),
+    Section(Section),
+    Block(ContentBlock),
+}
+
 /// Parse a complete A2ML document
+///
+/// Directives and sections may be interleaved: real contracts open with a
+/// title heading before `@abstract`, and `@refs` closes the document.
 fn document(input: &str) -> IResult<&str, A2mlDocument> {
-    let (input, _) = multispace0(input)?;
-
-    // Parse abstract (optional)
-    let (input, abstract_text) = opt(abstract_directive)(input)?;
-    let (input, _) = multispace0(input)?;
-
-    // Parse requires (optional)
-    let (input, requirements) = opt(requires_directive)(input)?;
-    let (input, _) = multispace0(input)?;
+    let source = input;
+    let (mut input, _) = multispace0(input)?;
+
+    let mut items = Vec::new();
+    loop {
+        let start = source.len() - input.len();
+        match alt((
+            map(abstract_directive, TopLevel::Abstract),
+            map(requires_directive, TopLevel::Requires),
+            map(refs_directive, TopLevel::Refs),
+            map(section, TopLevel::Section),
+            map(content_block, TopLevel::Block),
+        ))(input)
+        {
+            Ok((rest, mut item)) => {
+                if let TopLevel::Section(section) = &mut item {
+                    section.line_number = source[..start].matches('\n').count() + 1;
+                }
+                items.push(item);
+                input = multispace0(rest)?.0;
+            }
+            Err(nom::Err::Error(_)) => break,
+            Err(e) => return Err(e),
+        }
+    }
 
-    // Parse sections
-    let (input, sections) = many0(section)(input)?;
-    let (input, _) = multispace0(input)?;
+    let mut doc = A2mlDocument {
+        abstract_text: None,
+        preamble: Vec::new(),
+        sections: Vec::new(),
+        references: Vec::new(),
+        requirements: Vec::new(),
+        raw: String::new(),
+        path: None,
+    };
 
-    // Parse references (optional)
-    let (input, references) = opt(refs_directive)(input)?;
-    let (input, _) = multispace0(input)?;
+    for item in items {
+        match item {
+            TopLevel::Abstract(text) => doc.abstract_text = Some(text),
+            TopLevel::Requires(items) => doc.requirements.extend(items),
+            TopLevel::Refs(refs) => doc.references.extend(refs),
+            TopLevel::Section(section) => doc.sections.push(section),
+            // Blocks after a directive (e.g. a `---` rule) belong to the
+            // preceding section; before any heading, to the preamble
+            TopLevel::Block(block) => match doc.sections.last_mut() {
+                Some(section) => section.content.push(block),
+                None => doc.preamble.push(block),
+            },
+        }
+    }
 
-    Ok((input, A2mlDocument {
-        abstract_text,
-        sections,
-        references: references.unwrap_or_default(),
-        requirements: requirements.unwrap_or_default(),
-        raw: input.to_string(),
-    }))
+    Ok((input, doc))
 }
 
 /// Parse @abstract: ... @end
@@ -189,7 +313,7 @@ fn refs_directive(input: &str) -> IResult<&str, Vec<Reference>> {
     let (input, _) = tag("@refs:")(input)?;
     let (input, _) = multispace0(input)?;
 
-    let (input, refs) = many1(reference)(input)?;
+    let (input, refs) = many0(terminated(alt((reference, unnumbered_reference)), multispace0))(input)?;
 
     let (input, _) = tag("@end")(input)?;
     let (input, _) = multispace0(input)?;
@@ -206,29 +330,64 @@ fn reference(input: &str) -> IResult<&str, Reference> {
     let (input, text) = not_line_ending(input)?;
     let (input, _) = line_ending(input)?;
 
-    // Check if URL in text (simple heuristic)
-    let text_str = text.trim();
-    let (text_final, url) = if text_str.contains("http://") || text_str.contains("https://") {
-        // Extract URL (simplified - just find first http URL)
-        if let Some(start) = text_str.find("http") {
-            let url_part = &text_str[start..];
-            let url_end = url_part.find(|c: char| c.is_whitespace() || c == ')').unwrap_or(url_part.len());
-            let url = url_part[..url_end].to_string();
-            (text_str[..start].trim().to_string(), Some(url))
-        } else {
-            (text_str.to_string(), None)
-        }
-    } else {
-        (text_str.to_string(), None)
-    };
+    let (text, url) = split_reference_url(text.trim());
 
     Ok((input, Reference {
         id: id.to_string(),
-        text: text_final,
+        text,
+        url,
+    }))
+}
+
+/// Parse a free-text line inside @refs (e.g. "None - see ...")
+fn unnumbered_reference(input: &str) -> IResult<&str, Reference> {
+    let (input, text) = verify(not_line_ending, |line: &str| {
+        let line = line.trim();
+        !line.is_empty() && !line.starts_with("@end")
+    })(input)?;
+    let (input, _) = line_ending(input)?;
+
+    let (text, url) = split_reference_url(text.trim());
+
+    Ok((input, Reference {
+        id: String::new(),
+        text,
         url,
     }))
 }
 
+/// Separate the first http(s) URL from reference text, keeping the rest
+fn split_reference_url(text: &str) -> (String, Option<String>) {
+    let start = match text.find("https://").or_else(|| text.find("http://")) {
+        Some(start) => start,
+        None => return (text.to_string(), None),
+    };
+
+    let url_part = &text[start..];
+    let url_end = url_part
+        .find(|c: char| c.is_whitespace() || c == ')' || c == '>')
+        .unwrap_or(url_part.len());
+    let url = url_part[..url_end].to_string();
+
+    // Drop brackets that only wrapped the URL: "Act (https://...)"
+    let before = text[..start].trim_end();
+    let after = text[start + url_end..].trim_start();
+    let (before, after) = match (before.chars().last(), after.chars().next()) {
+        (Some('('), Some(')')) | (Some('<'), Some('>')) => {
+            (before[..before.len() - 1].trim_end(), after[1..].trim_start())
+        }
+        _ => (before, after),
+    };
+
+    let text = if after.is_empty() {
+        before.to_string()
+    } else {
+        format!("{} {}", before, after).trim().to_string()
+    };
+
+    (text, Some(url))
+}
+
 /// Parse a section (heading + content)
 fn section(input: &str) -> IResult<&str, Section> {
     let (input, (level, heading)) = heading(input)?;
@@ -245,16 +404,16 @@ fn section(input: &str) -> IResult<&str, Section> {
         level,
         content: blocks,
         attestations,
-        line_number: 0,  // TODO: Track line numbers
+        line_number: 0,  // set by `document`, which knows the offset
     }))
 }
 
 /// Parse a heading: # Level 1, ## Level 2, etc.
-fn heading(input: &str) -> IResult<&str, (u8, String)> {
+pub(crate) fn heading(input: &str) -> IResult<&str, (u8, String)> {
     let (input, hashes) = take_while1(|c| c == '#')(input)?;
     let (input, _) = space1(input)?;
     let (input, text) = not_line_ending(input)?;
-    let (input, _) = line_ending(input)?;
+    let (input, _) = line_end(input)?;
 
     let level = hashes.len().min(6) as u8;
 
@@ -266,6 +425,8 @@ fn content_block(input: &str) -> IResult<&str, ContentBlock> {
     alt((
         horizontal_rule,
         bullet_list,
+        ordered_list,
+        table,
         code_block,
         paragraph,
     ))(input)
@@ -273,40 +434,78 @@ fn content_block(input: &str) -> IResult<&str, ContentBlock> {
 
 /// Check if a line is a paragraph line (not a heading, list, or other structure)
 fn is_paragraph_line(input: &str) -> bool {
-    if input.is_empty() {
+    let line = input.split(['\r', '\n']).next().unwrap_or("");
+
+    if line.trim().is_empty() {
         return false;
     }
 
-    let first_char = input.chars().next().unwrap();
-
-    // Not a heading
-    if first_char == '#' {
+    // Not a heading ("#hashtag" is text)
+    if heading(input).is_ok() {
         return false;
     }
 
     // Not a horizontal rule
-    if input.starts_with("---") {
+    if line.starts_with("---") {
         return false;
     }
 
     // Not a code block
-    if input.starts_with("```") {
+    if line.starts_with("```") {
         return false;
     }
 
-    // Not a directive
-    if input.starts_with("@") {
+    // Not a directive ("@abstract:", "@end"); other @-words are text
+    if is_directive_line(line) {
         return false;
     }
 
-    // Not a list item (- followed by space)
-    if input.starts_with("- ") {
+    // Not a list item or table row
+    if bullet_marker(line).is_some() || ordered_marker(line).is_some() || line.starts_with('|') {
         return false;
     }
 
     true
 }
 
+/// `@name:` (optionally followed by inline content) or `@end`
+pub(crate) fn is_directive_line(line: &str) -> bool {
+    let Some(rest) = line.trim_end().strip_prefix('@') else {
+        return false;
+    };
+    let name_len = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .unwrap_or(rest.len());
+    name_len > 0 && (rest == "end" || rest[name_len..].starts_with(':'))
+}
+
+/// Length of a bullet marker ("- ", "* ", "+ ") at the start of a line
+pub(crate) fn bullet_marker(line: &str) -> Option<usize> {
+    let mut chars = line.chars();
+    match (chars.next(), chars.next()) {
+        (Some('-' | '*' | '+'), Some(' ' | '\t')) => Some(2),
+        _ => None,
+    }
+}
+
+/// Length of an ordered-list marker ("1. ", "12) ") at the start of a line
+pub(crate) fn ordered_marker(line: &str) -> Option<usize> {
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 || digits > 9 {
+        return None;
+    }
+    let mut rest = line[digits..].chars();
+    match (rest.next(), rest.next()) {
+        (Some('.' | ')'), Some(' ' | '\t')) => Some(digits + 2),
+        _ => None,
+    }
+}
+
+/// End of a line, or of the input: the last line need not end in a newline
+fn line_end(input: &str) -> IResult<&str, &str> {
+    alt((line_ending, eof))(input)
+}
+
 /// Parse a paragraph line (not a heading or structural element)
 fn paragraph_line(input: &str) -> IResult<&str, &str> {
     // Check if this looks like a paragraph line
@@ -319,7 +518,7 @@ fn paragraph_line(input: &str) -> IResult<&str, &str> {
 
 /// Parse a paragraph
 fn paragraph(input: &str) -> IResult<&str, ContentBlock> {
-    let (input, lines) = many1(terminated(paragraph_line, line_ending))(input)?;
+    let (input, lines) = many1(terminated(paragraph_line, line_end))(input)?;
 
     // Join lines and trim
     let text = lines.join("\n").trim().to_string();
@@ -338,27 +537,67 @@ fn bullet_list(input: &str) -> IResult<&str, ContentBlock> {
     Ok((input, ContentBlock::BulletList(items)))
 }
 
-/// Parse a single list item: - Item text
+/// Parse a single list item: - Item text (also `*` and `+` markers)
 fn list_item(input: &str) -> IResult<&str, String> {
-    let (input, _) = char('-')(input)?;
-    let (input, _) = space1(input)?;
-    let (input, text) = not_line_ending(input)?;
-    let (input, _) = line_ending(input)?;
+    let (input, line) = terminated(not_line_ending, line_end)(input)?;
+    match bullet_marker(line) {
+        Some(len) => Ok((input, line[len..].trim().to_string())),
+        None => Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Char))),
+    }
+}
+
+/// Parse an ordered list
+fn ordered_list(input: &str) -> IResult<&str, ContentBlock> {
+    let (input, items) = many1(ordered_item)(input)?;
+    Ok((input, ContentBlock::OrderedList(items)))
+}
 
-    Ok((input, text.trim().to_string()))
+/// Parse a single ordered item: 1. Item text
+fn ordered_item(input: &str) -> IResult<&str, OrderedItem> {
+    let (input, line) = terminated(not_line_ending, line_end)(input)?;
+    match ordered_marker(line) {
+        Some(len) => Ok((
+            input,
+            OrderedItem { marker: line[..len - 1].to_string(), text: line[len..].trim().to_string() },
+        )),
+        None => Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Digit))),
+    }
+}
+
+/// Parse a pipe table with a `|---|` separator after the header row
+fn table(input: &str) -> IResult<&str, ContentBlock> {
+    let (input, headers) = table_row(input)?;
+    let (input, _) = verify(table_row, |cells: &Vec<String>| {
+        cells.iter().all(|c| !c.is_empty() && c.chars().all(|ch| matches!(ch, '-' | ':' | ' ')))
+    })(input)?;
+    let (input, rows) = many0(table_row)(input)?;
+
+    Ok((input, ContentBlock::Table { headers, rows }))
+}
+
+/// Parse a table row: | a | b |
+fn table_row(input: &str) -> IResult<&str, Vec<String>> {
+    let (input, _) = char('|')(input)?;
+    let (input, line) = terminated(not_line_ending, line_end)(input)?;
+    let line = line.trim_end();
+    let line = line.strip_suffix('|').unwrap_or(line);
+
+    Ok((input, line.split('|').map(|cell| cell.trim().to_string()).collect()))
 }
 
 /// Parse a horizontal rule: ---
 fn horizontal_rule(input: &str) -> IResult<&str, ContentBlock> {
     let (input, _) = tag("---")(input)?;
-    let (input, _) = line_ending(input)?;
+    let (input, _) = line_end(input)?;
     Ok((input, ContentBlock::HorizontalRule))
 }
 
 /// Parse a code block: ```language ... ```
 fn code_block(input: &str) -> IResult<&str, ContentBlock> {
     let (input, _) = tag("```")(input)?;
-    let (input, language) = opt(map(not_line_ending, |s: &str| s.trim().to_string()))(input)?;
+    let (input, language) = map(not_line_ending, |s: &str| {
+        Some(s.trim().to_string()).filter(|l| !l.is_empty())
+    })(input)?;
     let (input, _) = line_ending(input)?;
     let (input, code) = take_until("```")(input)?;
     let (input, _) = tag("```")(input)?;
@@ -381,7 +620,8 @@ fn extract_attestations(blocks: &[ContentBlock]) -> Vec<Attestation> {
                 // Extract attestation text (simplified)
                 let parts: Vec<&str> = text.split("Attestation:").collect();
                 if parts.len() > 1 {
-                    let attestation_text = parts[1].trim();
+                    // Skip the closing `**` of "**Attestation:**"
+                    let attestation_text = parts[1].trim().trim_start_matches("**").trim_start();
 
                     // Parse out "Must/Should/Could"
                     let requirement = if attestation_text.starts_with("*Must*") {
@@ -518,6 +758,80 @@ This is a paragraph.
         assert!(doc.abstract_text.is_some());
         assert_eq!(doc.requirements.len(), 1);
         assert_eq!(doc.references.len(), 1);
-        assert!(doc.sections.len() > 0);
+        assert!(!doc.sections.is_empty());
+        assert_eq!(doc.raw, a2ml);
+    }
+
+    #[test]
+    fn test_parse_lists_and_tables() {
+        let input = "## Rates\n\n1. First\n2. Second\n\n| Role | Rate |\n|------|------|\n| Writer | 350 |\n\n* Starred\n";
+        let doc = parse_a2ml_string(input).unwrap();
+        let content = &doc.sections[0].content;
+
+        assert_eq!(
+            content[0],
+            ContentBlock::OrderedList(vec![
+                OrderedItem { marker: "1.".into(), text: "First".into() },
+                OrderedItem { marker: "2.".into(), text: "Second".into() },
+            ])
+        );
+        assert_eq!(
+            content[1],
+            ContentBlock::Table {
+                headers: vec!["Role".into(), "Rate".into()],
+                rows: vec![vec!["Writer".into(), "350".into()]],
+            }
+        );
+        assert_eq!(content[2], ContentBlock::BulletList(vec!["Starred".into()]));
+    }
+
+    #[test]
+    fn test_unrecognised_content_is_an_error() {
+        let result = parse_a2ml_string("## Terms\n\nText.\n\n@unknown:\nvalue\n@end\n");
+        match result {
+            Err(PolicyError::ParseError(msg)) => assert!(msg.contains("line 5"), "{}", msg),
+            other => panic!("Expected parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_content_before_first_heading_is_kept() {
+        let doc = parse_a2ml_string("Fee schedule: 500 GBP per day\n\n- Cover note\n\n# Title\n\nBody.\n").unwrap();
+        assert_eq!(
+            doc.preamble,
+            [
+                ContentBlock::Paragraph("Fee schedule: 500 GBP per day".into()),
+                ContentBlock::BulletList(vec!["Cover note".into()]),
+            ]
+        );
+        assert_eq!(doc.sections.len(), 1);
+
+        let altered = parse_a2ml_string("Fee schedule: 5 GBP per day\n\n- Cover note\n\n# Title\n\nBody.\n").unwrap();
+        assert_ne!(doc, altered);
+    }
+
+    #[test]
+    fn test_missing_final_newline() {
+        let doc = parse_a2ml_string("# Title\n\nBody.").unwrap();
+        assert_eq!(doc.sections[0].content, [ContentBlock::Paragraph("Body.".into())]);
+
+        let doc = parse_a2ml_string("# T\n\n- a\n- b").unwrap();
+        assert_eq!(doc.sections[0].content, [ContentBlock::BulletList(vec!["a".into(), "b".into()])]);
+
+        for source in ["# Only a title", "## R\n\n| A |\n|---|\n| 1 |", "# T\n\n1. one\n2. two", "# T\n\n---"] {
+            assert!(parse_a2ml_string(source).is_ok(), "{:?}", source);
+        }
+    }
+
+    #[test]
+    fn test_parse_title_before_directives() {
+        let a2ml = "# Contract\n\n@abstract:\nA contract.\n@end\n\n---\n\n## 1. Pay\n\nNET 30.\n";
+
+        let doc = parse_a2ml_string(a2ml).unwrap();
+        assert_eq!(doc.abstract_text.as_deref(), Some("A contract."));
+        assert_eq!(doc.sections.len(), 2);
+        assert_eq!(doc.sections[1].heading, "1. Pay");
+        assert_eq!(doc.sections[1].line_number, 9);
+        assert!(doc.sections[1].text().contains("NET 30"));
     }
 }
@@ -0,0 +1,733 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! K9 contract predicates
+//!
+//! Evaluates the `contracts` block of a K9 wrapper, written as
+//!
+//! ```text
+//! kill_fee_minimum = fun contract =>
+//!   let pct = contract.payment.kill_fee_percent in
+//!   std.contract.from_predicate (fun p => p >= 50.0),
+//! ```
+//!
+//! The first `let` names the contract field the predicate is about; later
+//! `let`s are constants. The supported subset is comparisons, `&&`, `||`,
+//! `!`, string equality, field paths, and `std.string.contains`,
+//! `lowercase` and `uppercase`. Since values come from prose, string
+//! equality ignores case and accepts a leading phrase ("Freelancer retains
+//! copyright" == "freelancer").
+//!
+//! Field paths are bound to values in the A2ML contract: a `**Label:**`
+//! line whose label has the field's words (`kill_fee_percent` matches
+//! `**Kill Fee:** 75%`), else a line naming the field followed by a number
+//! (`net_days` matches "NET 30 days"). Sections whose headings match the
+//! rest of the path (`payment`) are searched first.
+
+use crate::parser::{A2mlDocument, ContentBlock, Section};
+use std::fmt;
+
+/// A value during evaluation
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Function(String, Box<Expr>, Env),
+}
+
+type Env = Vec<(String, Value)>;
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Number(n) => write!(f, "{}", n),
+            Value::String(s) => write!(f, "\"{}\"", s),
+            Value::Function(param, _, _) => write!(f, "fun {} => ...", param),
+        }
+    }
+}
+
+/// Expression syntax
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal(Value),
+    /// Variable or field path (`contract.payment.net_days`)
+    Path(Vec<String>),
+    Not(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+    Apply(Box<Expr>, Vec<Expr>),
+    Fun(String, Box<Expr>),
+    Let(String, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+/// Result of one predicate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Pass,
+    Fail,
+    /// The field was not found or the predicate could not be evaluated
+    Unknown,
+}
+
+/// A contract predicate checked against a contract
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PredicateCheck {
+    /// Entry name in the wrapper's `contracts` block
+    pub name: String,
+
+    /// Field path the predicate tests (`contract.payment.kill_fee_percent`)
+    pub field: Option<String>,
+
+    /// Predicate body as written (`p >= 50.0`)
+    pub condition: Option<String>,
+
+    /// Value found in the contract, as written there
+    pub value: Option<String>,
+
+    /// Heading of the section the value came from
+    pub section: Option<String>,
+
+    pub outcome: Outcome,
+
+    /// Why the outcome is unknown
+    pub reason: Option<String>,
+}
+
+/// A named predicate from a wrapper's `contracts` block
+#[derive(Debug, Clone)]
+pub struct Predicate {
+    name: String,
+    source: String,
+    parsed: Result<Parsed, String>,
+}
+
+#[derive(Debug, Clone)]
+struct Parsed {
+    field: Vec<String>,
+    constants: Vec<(String, Expr)>,
+    predicate: Expr,
+    condition: String,
+}
+
+impl Predicate {
+    /// Parse a predicate; errors surface as an unknown outcome when checked
+    pub fn new(name: &str, source: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            source: source.to_string(),
+            parsed: parse(source).and_then(Parsed::from_expr).map(|mut parsed| {
+                parsed.condition = condition_text(source);
+                parsed
+            }),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The expression as written
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Evaluate against a contract
+    pub fn check(&self, contract: &A2mlDocument) -> PredicateCheck {
+        let mut check = PredicateCheck {
+            name: self.name.clone(),
+            field: None,
+            condition: None,
+            value: None,
+            section: None,
+            outcome: Outcome::Unknown,
+            reason: None,
+        };
+        let parsed = match &self.parsed {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                check.reason = Some(e.clone());
+                return check;
+            }
+        };
+        check.field = Some(parsed.field.join("."));
+        check.condition = Some(parsed.condition.clone());
+
+        let Some(binding) = field_value(contract, &parsed.field) else {
+            check.reason = Some("field not found in the contract".to_string());
+            return check;
+        };
+        check.value = Some(binding.text);
+        check.section = Some(binding.section);
+
+        match parsed.evaluate(binding.value) {
+            Ok(true) => check.outcome = Outcome::Pass,
+            Ok(false) => check.outcome = Outcome::Fail,
+            Err(e) => check.reason = Some(e),
+        }
+        check
+    }
+}
+
+impl Parsed {
+    /// Take apart `fun contract => let field = contract.x in ... predicate`
+    fn from_expr(expr: Expr) -> Result<Self, String> {
+        let Expr::Fun(param, mut body) = expr else {
+            return Err("expected `fun contract => ...`".to_string());
+        };
+        let mut field = None;
+        let mut constants = Vec::new();
+        while let Expr::Let(name, value, rest) = *body {
+            match *value {
+                Expr::Path(path) if field.is_none() && path.first() == Some(&param) => field = Some(path[1..].to_vec()),
+                value => constants.push((name, value)),
+            }
+            body = rest;
+        }
+        let field = field.ok_or_else(|| format!("no `let` binding a field of `{}`", param))?;
+        Ok(Self {
+            field: std::iter::once(param).chain(field).collect(),
+            constants,
+            predicate: *body,
+            condition: String::new(),
+        })
+    }
+
+    fn evaluate(&self, subject: Value) -> Result<bool, String> {
+        let mut env = Env::new();
+        for (name, value) in &self.constants {
+            let value = eval(value, &env)?;
+            env.push((name.clone(), value));
+        }
+        match eval(&self.predicate, &env)? {
+            Value::Function(param, body, mut closure) => {
+                closure.push((param, subject));
+                truthy(eval(&body, &closure)?)
+            }
+            other => truthy(other),
+        }
+    }
+}
+
+/// The body of the last `fun x => ...` in a predicate, for display
+fn condition_text(source: &str) -> String {
+    let body = source.rsplit("=>").next().unwrap_or(source);
+    let body: String = body.lines().map(|line| line.split('#').next().unwrap_or("").trim()).collect::<Vec<_>>().join(" ");
+    let body = body.trim();
+    body.strip_suffix(')').map(str::trim).unwrap_or(body).to_string()
+}
+
+fn truthy(value: Value) -> Result<bool, String> {
+    match value {
+        Value::Bool(b) => Ok(b),
+        other => Err(format!("predicate gave {}, not a boolean", other)),
+    }
+}
+
+// ============================================================================
+// Evaluation
+// ============================================================================
+
+fn eval(expr: &Expr, env: &Env) -> Result<Value, String> {
+    match expr {
+        Expr::Literal(value) => Ok(value.clone()),
+        Expr::Path(path) => lookup(path, env),
+        Expr::Not(inner) => Ok(Value::Bool(!truthy(eval(inner, env)?)?)),
+        Expr::Binary(Op::And, left, right) => {
+            Ok(Value::Bool(truthy(eval(left, env)?)? && truthy(eval(right, env)?)?))
+        }
+        Expr::Binary(Op::Or, left, right) => {
+            Ok(Value::Bool(truthy(eval(left, env)?)? || truthy(eval(right, env)?)?))
+        }
+        Expr::Binary(op, left, right) => compare(*op, eval(left, env)?, eval(right, env)?),
+        Expr::Fun(param, body) => Ok(Value::Function(param.clone(), body.clone(), env.clone())),
+        Expr::Let(name, value, body) => {
+            let mut inner = env.clone();
+            inner.push((name.clone(), eval(value, env)?));
+            eval(body, &inner)
+        }
+        Expr::Apply(function, args) => {
+            let args = args.iter().map(|arg| eval(arg, env)).collect::<Result<Vec<_>, _>>()?;
+            match function.as_ref() {
+                Expr::Path(path) if path.first().is_some_and(|p| p == "std") => builtin(&path.join("."), args),
+                function => args.into_iter().try_fold(eval(function, env)?, apply),
+            }
+        }
+    }
+}
+
+fn lookup(path: &[String], env: &Env) -> Result<Value, String> {
+    let name = path.join(".");
+    env.iter()
+        .rev()
+        .find(|(bound, _)| *bound == name)
+        .map(|(_, value)| value.clone())
+        .ok_or_else(|| format!("unknown name `{}`", name))
+}
+
+fn apply(function: Value, arg: Value) -> Result<Value, String> {
+    match function {
+        Value::Function(param, body, mut closure) => {
+            closure.push((param, arg));
+            eval(&body, &closure)
+        }
+        other => Err(format!("{} is not a function", other)),
+    }
+}
+
+fn builtin(name: &str, args: Vec<Value>) -> Result<Value, String> {
+    match (name, args.as_slice()) {
+        ("std.contract.from_predicate", [predicate]) => Ok(predicate.clone()),
+        ("std.string.contains", [Value::String(needle), Value::String(haystack)]) => {
+            Ok(Value::Bool(haystack.contains(needle.as_str())))
+        }
+        ("std.string.lowercase", [Value::String(s)]) => Ok(Value::String(s.to_lowercase())),
+        ("std.string.uppercase", [Value::String(s)]) => Ok(Value::String(s.to_uppercase())),
+        _ => Err(format!("unsupported call to `{}` with {} argument(s)", name, args.len())),
+    }
+}
+
+fn compare(op: Op, left: Value, right: Value) -> Result<Value, String> {
+    if let (Value::String(a), Value::String(b), Op::Eq | Op::Ne) = (&left, &right, op) {
+        return Ok(Value::Bool(prose_eq(a, b) == (op == Op::Eq)));
+    }
+    let ordering = match (&left, &right) {
+        (Value::Number(a), Value::Number(b)) => a.partial_cmp(b),
+        (Value::String(a), Value::String(b)) => Some(a.trim().to_lowercase().cmp(&b.trim().to_lowercase())),
+        (Value::Bool(a), Value::Bool(b)) if matches!(op, Op::Eq | Op::Ne) => Some(a.cmp(b)),
+        (Value::Null, Value::Null) => Some(std::cmp::Ordering::Equal),
+        _ if matches!(op, Op::Eq | Op::Ne) => None,
+        _ => return Err(format!("cannot compare {} with {}", left, right)),
+    };
+    let result = match (op, ordering) {
+        (Op::Eq, ordering) => ordering.is_some_and(|o| o.is_eq()),
+        (Op::Ne, ordering) => !ordering.is_some_and(|o| o.is_eq()),
+        (_, None) => return Err(format!("cannot compare {} with {}", left, right)),
+        (Op::Lt, Some(o)) => o.is_lt(),
+        (Op::Le, Some(o)) => o.is_le(),
+        (Op::Gt, Some(o)) => o.is_gt(),
+        (Op::Ge, Some(o)) => o.is_ge(),
+        (Op::And | Op::Or, _) => unreachable!("boolean operators are evaluated lazily"),
+    };
+    Ok(Value::Bool(result))
+}
+
+/// Strings are equal ignoring case, or when one is a phrase the other starts
+/// with: "Freelancer retains copyright" equals "freelancer"
+pub fn prose_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.trim().to_lowercase(), b.trim().to_lowercase());
+    let (long, short) = if a.len() >= b.len() { (&a, &b) } else { (&b, &a) };
+    !short.is_empty()
+        && long.starts_with(short.as_str())
+        && !long[short.len()..].starts_with(|c: char| c.is_alphanumeric() || c == '-')
+}
+
+// ============================================================================
+// Binding fields to the contract
+// ============================================================================
+
+/// A contract value found for a field path
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binding {
+    pub value: Value,
+    /// The value as written
+    pub text: String,
+    /// Heading of the section it is in
+    pub section: String,
+}
+
+/// Unit words dropped from field names before matching (`kill_fee_percent`)
+const UNIT_SUFFIXES: &[&str] = &["percent", "pct", "days", "weeks", "months", "hours", "gbp", "eur", "usd"];
+
+/// Find the value of a field path (`contract.payment.kill_fee_percent`)
+pub fn field_value(contract: &A2mlDocument, path: &[String]) -> Option<Binding> {
+    let path: Vec<&str> = path.iter().map(String::as_str).skip_while(|s| *s == "contract").collect();
+    let (field, scope) = path.split_last()?;
+    let key = field.to_lowercase();
+    let words: Vec<&str> = key.split('_').filter(|w| !w.is_empty()).collect();
+    let stem: Vec<&str> = match words.split_last() {
+        Some((last, rest)) if !rest.is_empty() && UNIT_SUFFIXES.contains(last) => rest.to_vec(),
+        _ => words.clone(),
+    };
+
+    let scope_words: Vec<String> = scope.iter().flat_map(|s| s.split(['_', '-'])).map(str::to_lowercase).collect();
+    let in_scope = |section: &&Section| {
+        let heading = section.heading.to_lowercase();
+        scope_words.iter().any(|word| heading.split(|c: char| !c.is_alphanumeric()).any(|w| w == word))
+    };
+    let scoped = contract.sections.iter().filter(in_scope);
+    let ordered = scoped.chain(contract.sections.iter());
+
+    let sections: Vec<&Section> = ordered.collect();
+    sections
+        .iter()
+        .find_map(|section| labelled(section, &stem))
+        .or_else(|| sections.iter().find_map(|section| mentioned(section, &stem)))
+}
+
+fn lines(section: &Section) -> impl Iterator<Item = &str> {
+    section.content.iter().flat_map(|block| match block {
+        ContentBlock::Paragraph(text) => text.lines().collect::<Vec<_>>(),
+        ContentBlock::BulletList(items) => items.iter().map(String::as_str).collect(),
+        ContentBlock::OrderedList(items) => items.iter().map(|item| item.text.as_str()).collect(),
+        _ => Vec::new(),
+    })
+}
+
+/// The `**Label:** value` in a clause's section whose label names the clause
+/// (`kill-fee` finds `**Kill Fee:** 75% of agreed fee`), as written
+pub fn clause_value(section: &Section, clause: &str) -> Option<String> {
+    let clause = clause.to_lowercase();
+    let stem: Vec<&str> = clause.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+    if stem.is_empty() {
+        return None;
+    }
+    labelled(section, &stem).map(|binding| binding.text)
+}
+
+/// `**Kill Fee:** 75% of agreed fee`; every word of the field (less any
+/// unit) must be in the label
+fn labelled(section: &Section, stem: &[&str]) -> Option<Binding> {
+    lines(section).find_map(|line| {
+        let rest = line.trim().strip_prefix("**")?;
+        let (label, text) = rest.split_once(":**")?;
+        let label: Vec<String> = label
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        if !stem.iter().all(|word| label.iter().any(|w| w == word)) {
+            return None;
+        }
+        let text = text.trim().trim_end_matches('.').to_string();
+        let first = text.split(|c: char| !c.is_alphanumeric()).next().unwrap_or("").to_lowercase();
+        let value = match leading_number(&text) {
+            Some(number) => Value::Number(number),
+            // "**Kill Fee:** None." is a zero for numeric predicates
+            None if matches!(first.as_str(), "none" | "nil" | "zero") => Value::Number(0.0),
+            None => Value::String(text.clone()),
+        };
+        Some(Binding { value, text, section: section.heading.clone() })
+    })
+}
+
+/// "Payment due NET 30 days": the field's words, then a number
+fn mentioned(section: &Section, stem: &[&str]) -> Option<Binding> {
+    lines(section).find_map(|line| {
+        let line_words = words_at(line);
+        if !stem.iter().all(|word| line_words.iter().any(|(_, w)| w == word)) {
+            return None;
+        }
+        let first = line_words.iter().find(|(_, w)| stem.contains(&w.as_str()))?.0;
+        let after = &line[first..];
+        let start = after.find(|c: char| c.is_ascii_digit())?;
+        let number = leading_number(&after[start..])?;
+        Some(Binding {
+            value: Value::Number(number),
+            text: line.trim().to_string(),
+            section: section.heading.clone(),
+        })
+    })
+}
+
+/// Lowercased words of a line with their byte offsets in the line as
+/// written; lowercasing can change lengths ("\u{212A}" is three bytes, "k" one)
+fn words_at(line: &str) -> Vec<(usize, String)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        match (start, c.is_alphanumeric()) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                words.push((s, line[s..i].to_lowercase()));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+/// Number at the start of text, after any currency sign: "£1,250.50 per day"
+fn leading_number(text: &str) -> Option<f64> {
+    let text = text.trim_start_matches(['£', '$', '€', ' ']);
+    let digits: String = text
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
+        .filter(|c| *c != ',')
+        .collect();
+    digits.trim_end_matches('.').parse().ok()
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    String(String),
+    Ident(String),
+    Op(&'static str),
+}
+
+/// Parse a predicate expression
+pub fn parse(source: &str) -> Result<Expr, String> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.expr()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(format!("unexpected {:?}", token)),
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    const OPS: &[&str] = &["=>", "==", "!=", "<=", ">=", "&&", "||", "<", ">", "!", "(", ")", "=", "."];
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if c == '#' {
+            rest = rest.find('\n').map_or("", |end| &rest[end..]);
+        } else if c == '"' {
+            let end = rest[1..].find('"').ok_or("unterminated string")? + 1;
+            tokens.push(Token::String(rest[1..end].to_string()));
+            rest = &rest[end + 1..];
+        } else if c.is_ascii_digit() {
+            let end = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+            let number = rest[..end].parse().map_err(|_| format!("bad number `{}`", &rest[..end]))?;
+            tokens.push(Token::Number(number));
+            rest = &rest[end..];
+        } else if c.is_alphabetic() || c == '_' {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-' || c == '\''))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Ident(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else if let Some(op) = OPS.iter().find(|op| rest.starts_with(**op)) {
+            tokens.push(Token::Op(op));
+            rest = &rest[op.len()..];
+        } else {
+            return Err(format!("unsupported character `{}`", c));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat(&mut self, op: &str) -> bool {
+        if self.peek() == Some(&Token::Op(op_static(op))) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn keyword(&mut self, word: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(w)) if w == word) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Ident(name)) => {
+                self.pos += 1;
+                Ok(name.clone())
+            }
+            other => Err(format!("expected a name, found {:?}", other)),
+        }
+    }
+
+    fn expect(&mut self, op: &str) -> Result<(), String> {
+        if self.eat(op) {
+            Ok(())
+        } else {
+            Err(format!("expected `{}`, found {:?}", op, self.peek()))
+        }
+    }
+
+    /// `fun x => e`, `let x = e in e`, or an operator expression
+    fn expr(&mut self) -> Result<Expr, String> {
+        if self.keyword("fun") {
+            let param = self.ident()?;
+            self.expect("=>")?;
+            return Ok(Expr::Fun(param, Box::new(self.expr()?)));
+        }
+        if self.keyword("let") {
+            let name = self.ident()?;
+            self.expect("=")?;
+            let value = self.expr()?;
+            if !self.keyword("in") {
+                return Err("expected `in`".to_string());
+            }
+            return Ok(Expr::Let(name, Box::new(value), Box::new(self.expr()?)));
+        }
+        self.or()
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.eat("||") {
+            left = Expr::Binary(Op::Or, Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.comparison()?;
+        while self.eat("&&") {
+            left = Expr::Binary(Op::And, Box::new(left), Box::new(self.comparison()?));
+        }
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let left = self.unary()?;
+        let ops = [("==", Op::Eq), ("!=", Op::Ne), ("<=", Op::Le), (">=", Op::Ge), ("<", Op::Lt), (">", Op::Gt)];
+        for (symbol, op) in ops {
+            if self.eat(symbol) {
+                return Ok(Expr::Binary(op, Box::new(left), Box::new(self.unary()?)));
+            }
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat("!") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        let function = self.atom()?;
+        let mut args = Vec::new();
+        while self.starts_atom() {
+            args.push(self.atom()?);
+        }
+        Ok(if args.is_empty() { function } else { Expr::Apply(Box::new(function), args) })
+    }
+
+    fn starts_atom(&self) -> bool {
+        match self.peek() {
+            Some(Token::Number(_) | Token::String(_)) => true,
+            Some(Token::Op(op)) => *op == "(",
+            Some(Token::Ident(word)) => !matches!(word.as_str(), "in" | "fun" | "let"),
+            None => false,
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expr, String> {
+        let token = self.tokens.get(self.pos).cloned().ok_or("unexpected end of expression")?;
+        self.pos += 1;
+        match token {
+            Token::Number(n) => Ok(Expr::Literal(Value::Number(n))),
+            Token::String(s) => Ok(Expr::Literal(Value::String(s))),
+            Token::Op("(") => {
+                let inner = self.expr()?;
+                self.expect(")")?;
+                Ok(inner)
+            }
+            Token::Ident(word) => match word.as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                _ => {
+                    let mut path = vec![word];
+                    while self.eat(".") {
+                        path.push(self.ident()?);
+                    }
+                    Ok(Expr::Path(path))
+                }
+            },
+            other => Err(format!("unexpected {:?}", other)),
+        }
+    }
+}
+
+fn op_static(op: &str) -> &'static str {
+    ["=>", "==", "!=", "<=", ">=", "&&", "||", "<", ">", "!", "(", ")", "=", "."]
+        .into_iter()
+        .find(|known| *known == op)
+        .unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTRACT: &str = "# Commission\n\n## 2. Payment Terms\n\n- Payment due NET 45 days from invoice date\n\n**Late Payment Penalty:** 5% per month\n\n## 3. Kill Fee\n\n**Kill Fee:** 75% of agreed fee\n\n## 4. Copyright\n\n**Copyright Ownership:** Freelancer retains copyright\n";
+
+    fn check(source: &str) -> PredicateCheck {
+        let contract = crate::parse(CONTRACT).unwrap();
+        Predicate::new("test", source).check(&contract)
+    }
+
+    #[test]
+    fn test_wrapper_predicates() {
+        let kill_fee = check("fun contract =>\n  let pct = contract.payment.kill_fee_percent in\n  std.contract.from_predicate (fun p => p >= 50.0)");
+        assert_eq!(kill_fee.outcome, Outcome::Pass);
+        assert_eq!(kill_fee.section.as_deref(), Some("3. Kill Fee"));
+        assert_eq!(kill_fee.condition.as_deref(), Some("p >= 50.0"));
+
+        let net = check("fun contract => let d = contract.payment.terms.net_days in std.contract.from_predicate (fun d => d <= 30)");
+        assert_eq!(net.outcome, Outcome::Fail);
+        assert_eq!(net.value.as_deref(), Some("Payment due NET 45 days from invoice date"));
+
+        let penalty = check("fun contract => let p = contract.payment.late_penalty_percent in let min = 5 in # minimum\n std.contract.from_predicate (fun p => p >= min && !(p > 20))");
+        assert_eq!(penalty.outcome, Outcome::Pass);
+
+        let copyright = check("fun contract => let o = contract.ip_rights.copyright_ownership in std.contract.from_predicate (fun o => o == \"freelancer\" || o == \"first-publication-only\")");
+        assert_eq!(copyright.outcome, Outcome::Pass);
+
+        let acas = check("fun contract => let p = contract.copyright.copyright_ownership in std.contract.from_predicate (fun p => std.string.contains \"ACAS\" p)");
+        assert_eq!(acas.outcome, Outcome::Fail);
+    }
+
+    #[test]
+    fn test_unknown_outcomes() {
+        let missing = check("fun contract => let r = contract.payment.day_rate_gbp in std.contract.from_predicate (fun r => r >= 350)");
+        assert_eq!(missing.outcome, Outcome::Unknown);
+        assert_eq!(missing.reason.as_deref(), Some("field not found in the contract"));
+
+        let type_error = check("fun contract => let o = contract.copyright.copyright_ownership in std.contract.from_predicate (fun o => o >= 5)");
+        assert_eq!(type_error.outcome, Outcome::Unknown);
+
+        assert_eq!(check("fun contract => contract.x + 1").outcome, Outcome::Unknown);
+    }
+
+    #[test]
+    fn test_mentioned_value_after_non_ascii_text() {
+        // The Kelvin sign lowercases to a one-byte "k"
+        let contract = crate::parse("## Fees\n\nThe \u{212A} kill fee is 50 percent.\n").unwrap();
+        let binding = field_value(&contract, &["kill_fee_percent".to_string()]).unwrap();
+        assert_eq!(binding.value, Value::Number(50.0));
+        assert_eq!(binding.section, "Fees");
+    }
+}
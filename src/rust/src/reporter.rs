@@ -16,6 +16,7 @@ pub struct GrievanceGenerator {
 }
 
 impl GrievanceGenerator {
+    /// A generator for a union's letters, optionally from a template file
     pub fn new(union: Option<String>, template_path: Option<&Path>) -> Result<Self> {
         let template = if let Some(path) = template_path {
             Some(fs::read_to_string(path)?)
@@ -34,7 +35,7 @@ impl GrievanceGenerator {
     ) -> Result<String> {
         log::info!("Generating grievance for: {}", violation);
 
-        let template = self.template.as_ref().ok_or_else(|| {
+        let _template = self.template.as_ref().ok_or_else(|| {
             PolicyError::TemplateError("No template provided".to_string())
         })?;
 
@@ -57,8 +58,8 @@ impl GrievanceGenerator {
             Errors found:\n{}\n",
             violation,
             self.union.as_deref().unwrap_or("N/A"),
-            validation_report.contract_path,
-            validation_report.schema_path,
+            validation_report.contract.path,
+            validation_report.schema.path,
             self.format_errors(&validation_report.errors),
         ))
     }
@@ -79,8 +80,11 @@ impl ReportRenderer {
     /// Render validation report as JSON
     pub fn render_json(report: &ValidationReport) -> Result<String> {
         serde_json::to_string_pretty(&serde_json::json!({
-            "contract": report.contract_path,
-            "schema": report.schema_path,
+            "contract": report.contract,
+            "schema": report.schema,
+            "union": report.union,
+            "tool_version": report.tool_version,
+            "timestamp": report.timestamp,
             "valid": report.valid,
             "errors": report.errors.iter().map(|e| serde_json::json!({
                 "kind": format!("{:?}", e.kind),
@@ -96,6 +100,12 @@ impl ReportRenderer {
                 "present": c.present,
                 "value": c.value,
                 "expected": c.expected,
+                "section": c.section,
+            })).collect::<Vec<_>>(),
+            "red_flags": report.red_flags.iter().map(|f| serde_json::json!({
+                "pattern": f.pattern,
+                "excerpt": f.excerpt,
+                "location": f.location,
             })).collect::<Vec<_>>(),
         }))
         .map_err(|e| e.into())
@@ -106,8 +116,26 @@ impl ReportRenderer {
         let mut md = String::new();
 
         md.push_str("# Validation Report\n\n");
-        md.push_str(&format!("**Contract:** `{}`\n", report.contract_path));
-        md.push_str(&format!("**Schema:** `{}`\n\n", report.schema_path));
+        md.push_str(&format!("**Contract:** `{}`\n", report.contract.path));
+        if let Some(sha256) = &report.contract.sha256 {
+            md.push_str(&format!("**Contract SHA-256:** `{}`\n", sha256));
+        }
+        md.push_str(&format!("**Schema:** `{}`", report.schema.path));
+        if let Some(name) = &report.schema.name {
+            md.push_str(&format!(" ({}", name));
+            if let Some(version) = &report.schema.version {
+                md.push_str(&format!(", version {}", version));
+            }
+            md.push(')');
+        }
+        md.push('\n');
+        if let Some(union) = &report.union {
+            md.push_str(&format!("**Union:** {}\n", union.to_uppercase()));
+        }
+        md.push_str(&format!(
+            "**Checked:** {} by union-policy-parser {}\n\n",
+            report.timestamp, report.tool_version
+        ));
 
         if report.valid {
             md.push_str("## ✅ VALID\n\n");
@@ -123,7 +151,7 @@ impl ReportRenderer {
                     md.push_str(&format!("  - Location: {}\n", loc));
                 }
             }
-            md.push_str("\n");
+            md.push('\n');
         }
 
         if !report.warnings.is_empty() {
@@ -131,7 +159,19 @@ impl ReportRenderer {
             for warning in &report.warnings {
                 md.push_str(&format!("- {}\n", warning.message));
             }
-            md.push_str("\n");
+            md.push('\n');
+        }
+
+        if !report.red_flags.is_empty() {
+            md.push_str("### Red Flags\n\n");
+            for flag in &report.red_flags {
+                md.push_str(&format!("- \"{}\"", flag.excerpt));
+                if let Some(loc) = &flag.location {
+                    md.push_str(&format!(" in {}", loc));
+                }
+                md.push('\n');
+            }
+            md.push('\n');
         }
 
         if !report.required_clauses.is_empty() {
@@ -148,22 +188,12 @@ impl ReportRenderer {
         Ok(md)
     }
 
-    /// Render validation report as HTML
+    /// Render validation report as a self-contained HTML page
     pub fn render_html(report: &ValidationReport) -> Result<String> {
-        let md = Self::render_markdown(report)?;
-        // TODO: Convert Markdown to HTML
-        // Options: pulldown-cmark, comrak
-        Ok(format!("<pre>{}</pre>", html_escape(&md)))
+        Ok(crate::html::render_report(report))
     }
 }
 
-fn html_escape(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! SARIF 2.1.0 output
+//!
+//! Every finding becomes a result whose `ruleId` is the union plus the clause
+//! (e.g., `nuj/source-protection`), with its level taken from the MUST/SHOULD/
+//! COULD requirement and its line from the parser. Code-scanning UIs use the
+//! locations to annotate contract diffs.
+
+use crate::error::Result;
+use crate::validator::{Finding, Severity, ValidationReport};
+use serde_json::{json, Value};
+
+const SCHEMA_URI: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const INFORMATION_URI: &str = "https://github.com/hyperpolymath/union-policy-parser";
+
+/// Render one or more reports as a single-run SARIF log
+pub fn render_sarif(reports: &[ValidationReport]) -> Result<String> {
+    let mut rules: Vec<Finding> = Vec::new();
+    let mut results = Vec::new();
+
+    for report in reports {
+        for finding in report.findings() {
+            let index = match rules.iter().position(|r| r.rule == finding.rule) {
+                Some(index) => index,
+                None => {
+                    rules.push(finding.clone());
+                    rules.len() - 1
+                }
+            };
+            results.push(result(report, &finding, index));
+        }
+    }
+
+    // Hashes tie the results to the exact contract versions checked
+    let artifacts: Vec<_> = reports
+        .iter()
+        .map(|report| {
+            let mut artifact = json!({ "location": { "uri": artifact_uri(&report.contract.path) } });
+            if let Some(sha256) = &report.contract.sha256 {
+                artifact["hashes"] = json!({ "sha-256": sha256 });
+            }
+            artifact
+        })
+        .collect();
+
+    let log = json!({
+        "$schema": SCHEMA_URI,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "union-policy-parser",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": INFORMATION_URI,
+                    "rules": rules.iter().map(rule).collect::<Vec<_>>(),
+                }
+            },
+            "artifacts": artifacts,
+            "results": results,
+        }]
+    });
+
+    Ok(serde_json::to_string_pretty(&log)?)
+}
+
+/// SARIF level for a requirement strength
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Must => "error",
+        Severity::Should => "warning",
+        Severity::Could => "note",
+    }
+}
+
+fn rule(finding: &Finding) -> Value {
+    let mut rule = json!({
+        "id": finding.rule,
+        "shortDescription": { "text": rule_description(finding) },
+        "defaultConfiguration": { "level": level(finding.severity) },
+        "properties": { "requirement": finding.severity.as_str() },
+    });
+    if let Some(help) = &finding.help {
+        rule["help"] = json!({ "text": help, "markdown": help });
+    }
+    rule
+}
+
+/// Rule description independent of the particular result
+fn rule_description(finding: &Finding) -> String {
+    match finding.rule.split_once("/red-flag/") {
+        Some(_) => format!("Exploitative term: {}", finding.rule.rsplit('/').next().unwrap_or("")),
+        None => finding.message.clone(),
+    }
+}
+
+fn result(report: &ValidationReport, finding: &Finding, rule_index: usize) -> Value {
+    let mut physical = json!({
+        "artifactLocation": { "uri": artifact_uri(&report.contract.path) },
+    });
+    if let Some(line) = finding.line {
+        physical["region"] = json!({ "startLine": line });
+    }
+
+    let mut location = json!({ "physicalLocation": physical });
+    if let Some(section) = &finding.location {
+        location["logicalLocations"] = json!([{ "name": section, "kind": "section" }]);
+    }
+
+    json!({
+        "ruleId": finding.rule,
+        "ruleIndex": rule_index,
+        "level": level(finding.severity),
+        "message": { "text": finding.message },
+        "locations": [location],
+    })
+}
+
+/// Relative URI with forward slashes, as code-scanning UIs expect
+fn artifact_uri(path: &str) -> String {
+    path.trim_start_matches("./").replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_a2ml_string;
+    use crate::schemas::Union;
+    use crate::validator::{ValidationMode, Validator};
+
+    #[test]
+    fn test_sarif_rules_levels_and_locations() {
+        let contract = parse_a2ml_string(
+            "# Contract\n\n## 1. Copyright\n\nThe client takes all rights.\n\n## 2. Pay\n\nNET 30.\n",
+        )
+        .unwrap();
+        let validator = Validator::new(Default::default(), ValidationMode::Checked).with_union(Union::Nuj);
+        let mut report = validator.validate(&contract, &["source-protection".to_string()]);
+        report.contract.path = "./contracts/bad.a2ml".to_string();
+
+        let sarif: Value = serde_json::from_str(&render_sarif(&[report]).unwrap()).unwrap();
+        let run = &sarif["runs"][0];
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["name"], "union-policy-parser");
+
+        let results = run["results"].as_array().unwrap();
+        let flag = results
+            .iter()
+            .find(|r| r["ruleId"] == "nuj/red-flag/all-rights")
+            .expect("red flag result");
+        assert_eq!(flag["level"], "error");
+        let location = &flag["locations"][0];
+        assert_eq!(location["physicalLocation"]["artifactLocation"]["uri"], "contracts/bad.a2ml");
+        assert_eq!(location["physicalLocation"]["region"]["startLine"], 5);
+        assert_eq!(location["logicalLocations"][0]["name"], "1. Copyright");
+
+        let missing = results.iter().find(|r| r["ruleId"] == "nuj/source-protection").unwrap();
+        assert_eq!(missing["level"], "error");
+        let recommended = results.iter().find(|r| r["ruleId"] == "nuj/diversity").unwrap();
+        assert_eq!(recommended["level"], "warning");
+
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        let index = flag["ruleIndex"].as_u64().unwrap() as usize;
+        assert_eq!(rules[index]["id"], "nuj/red-flag/all-rights");
+    }
+}
@@ -2,7 +2,9 @@
 //! Union-specific schema definitions and helpers
 
 use crate::error::{PolicyError, Result};
+use crate::parser::{parse_a2ml_string, A2mlDocument};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 /// Known unions with schema mappings
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -15,8 +17,11 @@ pub enum Union {
     Ucu,
 }
 
-impl Union {
-    pub fn from_str(s: &str) -> Result<Self> {
+/// Case-insensitive: "nuj", "NUJ"
+impl std::str::FromStr for Union {
+    type Err = PolicyError;
+
+    fn from_str(s: &str) -> Result<Self> {
         match s.to_lowercase().as_str() {
             "nuj" => Ok(Union::Nuj),
             "iww" => Ok(Union::Iww),
@@ -24,7 +29,10 @@ impl Union {
             _ => Err(PolicyError::UnknownUnion(s.to_string())),
         }
     }
+}
 
+impl Union {
+    /// Lower-case short name, as used in rule IDs ("nuj")
     pub fn as_str(&self) -> &'static str {
         match self {
             Union::Nuj => "nuj",
@@ -33,7 +41,8 @@ impl Union {
         }
     }
 
-    /// Get default schema path for this union
+    /// Where this union's schema lives in a checkout ("schemas/nuj-code-of-ethics.a2ml");
+    /// a file there takes precedence over the embedded copy
     pub fn default_schema_path(&self) -> &'static str {
         match self {
             Union::Nuj => "schemas/nuj-code-of-ethics.a2ml",
@@ -42,6 +51,12 @@ impl Union {
         }
     }
 
+    /// This union's schema as shipped with the tool
+    pub fn embedded_schema(&self) -> &'static EmbeddedSchema {
+        let file_name = self.default_schema_path().trim_start_matches("schemas/");
+        EmbeddedSchema::find(file_name).expect("every union's schema is embedded")
+    }
+
     /// Get required clauses for this union
     pub fn required_clauses(&self) -> Vec<&'static str> {
         match self {
@@ -125,13 +140,85 @@ impl Union {
     }
 }
 
-/// Union-specific validation rules
-pub struct UnionRules {
+/// Prefix that names an embedded schema in place of a path
+/// ("embedded:nuj-code-of-ethics.a2ml")
+pub const EMBEDDED_PREFIX: &str = "embedded:";
+
+/// A schema compiled into the tool, so it works outside a checkout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmbeddedSchema {
+    /// File name under `schemas/` ("nuj-code-of-ethics.a2ml")
+    pub file_name: &'static str,
+    /// A2ML source
+    pub source: &'static str,
+}
+
+macro_rules! embed {
+    ($($file:literal),* $(,)?) => {
+        &[$(EmbeddedSchema { file_name: $file, source: include_str!(concat!("../../../schemas/", $file)) }),*]
+    };
+}
+
+/// Every schema shipped in `schemas/`, by file name
+pub const EMBEDDED_SCHEMAS: &[EmbeddedSchema] = embed![
+    "bectu-media-rights.a2ml",
+    "equity-performers-rights.a2ml",
+    "gmb-general-workers-rights.a2ml",
+    "iww-freelancer-rights.a2ml",
+    "nuj-code-of-ethics.a2ml",
+    "nuj-pr-comms-guidance.a2ml",
+    "ucu-academic-standards.a2ml",
+];
+
+impl EmbeddedSchema {
+    /// Look up by file name, with or without `.a2ml` or the `embedded:` prefix
+    pub fn find(name: &str) -> Option<&'static EmbeddedSchema> {
+        let name = name.strip_prefix(EMBEDDED_PREFIX).unwrap_or(name);
+        let name = name.strip_suffix(".a2ml").unwrap_or(name);
+        EMBEDDED_SCHEMAS.iter().find(|schema| schema.name() == name)
+    }
+
+    /// The embedded schema a path names, if it uses the `embedded:` prefix
+    pub fn from_path(path: &Path) -> Option<&'static EmbeddedSchema> {
+        path.to_str().filter(|p| p.starts_with(EMBEDDED_PREFIX)).and_then(Self::find)
+    }
+
+    /// File name without `.a2ml` ("nuj-code-of-ethics")
+    pub fn name(&self) -> &'static str {
+        self.file_name.trim_end_matches(".a2ml")
+    }
+
+    /// The path reports record for it ("embedded:nuj-code-of-ethics.a2ml")
+    pub fn path(&self) -> PathBuf {
+        PathBuf::from(format!("{}{}", EMBEDDED_PREFIX, self.file_name))
+    }
+
+    /// Unions whose default schema this is
+    pub fn unions(&self) -> Vec<Union> {
+        [Union::Nuj, Union::Iww, Union::Ucu]
+            .into_iter()
+            .filter(|union| union.default_schema_path().ends_with(self.file_name))
+            .collect()
+    }
+
+    /// Parse the schema; the document records its `embedded:` path
+    pub fn parse(&self) -> Result<A2mlDocument> {
+        let mut doc = parse_a2ml_string(self.source)?;
+        doc.path = Some(self.path().to_string_lossy().to_string());
+        Ok(doc)
+    }
+}
+
+/// What a union expects of a contract: required and recommended clauses,
+/// red flags, its default schema, and acceptable clause values
+#[derive(Debug, Clone)]
+pub struct UnionProfile {
     union: Union,
     custom_rules: HashMap<String, String>,
 }
 
-impl UnionRules {
+impl UnionProfile {
+    /// The union's built-in profile
     pub fn new(union: Union) -> Self {
         Self {
             union,
@@ -139,8 +226,38 @@ impl UnionRules {
         }
     }
 
+    /// Require an exact value (case-insensitive) for a clause, in place of
+    /// the union's built-in check, e.g. a branch's agreed kill fee
+    pub fn with_clause_value(mut self, clause: &str, expected: &str) -> Self {
+        self.custom_rules.insert(clause.to_string(), expected.to_string());
+        self
+    }
+
+    /// The union this profile is for
+    pub fn union(&self) -> Union {
+        self.union
+    }
+
+    /// Clause IDs a contract must contain
+    pub fn required_clauses(&self) -> Vec<String> {
+        self.union.required_clauses().iter().map(|s| s.to_string()).collect()
+    }
+
+    /// Clause IDs a contract should contain
+    pub fn recommended_clauses(&self) -> Vec<String> {
+        self.union.recommended_clauses().iter().map(|s| s.to_string()).collect()
+    }
+
+    /// Exploitative phrases to flag
+    pub fn red_flag_patterns(&self) -> Vec<String> {
+        self.union.red_flag_patterns().iter().map(|s| s.to_string()).collect()
+    }
+
     /// Check if a clause value meets union standards
     pub fn check_clause_value(&self, clause: &str, value: &str) -> Result<bool> {
+        if let Some(expected) = self.custom_rules.get(clause) {
+            return Ok(value.eq_ignore_ascii_case(expected));
+        }
         match self.union {
             Union::Nuj => self.check_nuj_clause(clause, value),
             Union::Iww => self.check_iww_clause(clause, value),
@@ -213,16 +330,27 @@ mod tests {
 
     #[test]
     fn test_union_from_str() {
-        assert_eq!(Union::from_str("nuj").unwrap(), Union::Nuj);
-        assert_eq!(Union::from_str("NUJ").unwrap(), Union::Nuj);
-        assert!(Union::from_str("unknown").is_err());
+        assert_eq!("nuj".parse::<Union>().unwrap(), Union::Nuj);
+        assert_eq!("NUJ".parse::<Union>().unwrap(), Union::Nuj);
+        assert!("unknown".parse::<Union>().is_err());
+    }
+
+    #[test]
+    fn test_embedded_schemas_parse() {
+        for schema in EMBEDDED_SCHEMAS {
+            let doc = schema.parse().unwrap();
+            assert_eq!(doc.path, Some(format!("embedded:{}", schema.file_name)));
+        }
+        assert_eq!(Union::Nuj.embedded_schema().name(), "nuj-code-of-ethics");
+        assert_eq!(EmbeddedSchema::find("embedded:nuj-code-of-ethics.a2ml").unwrap().unions(), [Union::Nuj]);
+        assert_eq!(EmbeddedSchema::from_path(Path::new("schemas/nuj-code-of-ethics.a2ml")), None);
     }
 
     #[test]
     fn test_nuj_required_clauses() {
         let clauses = Union::Nuj.required_clauses();
         assert!(clauses.contains(&"source-protection"));
-        assert!(clauses.contains(&"editorial-independence"));
+        assert!(clauses.contains(&"independence"));
     }
 
     #[test]
@@ -234,15 +362,19 @@ mod tests {
 
     #[test]
     fn test_nuj_clause_check() {
-        let rules = UnionRules::new(Union::Nuj);
+        let rules = UnionProfile::new(Union::Nuj);
         assert!(rules.check_clause_value("source-protection", "guaranteed").unwrap());
         assert!(!rules.check_clause_value("source-protection", "optional").unwrap());
     }
 
     #[test]
     fn test_iww_clause_check() {
-        let rules = UnionRules::new(Union::Iww);
+        let rules = UnionProfile::new(Union::Iww);
         assert!(rules.check_clause_value("payment-terms.net-days", "30").unwrap());
         assert!(!rules.check_clause_value("payment-terms.net-days", "60").unwrap());
+
+        let branch = rules.with_clause_value("kill-fee", "100%");
+        assert!(branch.check_clause_value("kill-fee", "100%").unwrap());
+        assert!(!branch.check_clause_value("kill-fee", "50%").unwrap());
     }
 }
@@ -0,0 +1,289 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Ed25519 signatures over contracts and audit reports
+//!
+//! A contract is signed byte for byte as written, with `\r\n` line endings
+//! read as `\n`, so any edit breaks the signature: including text the parser
+//! does not model, list numbering and layout. A JSON report is signed with
+//! sorted keys and no whitespace. The
+//! signed message is `union-policy-parser:<kind>:<checksum>`, where the
+//! checksum is the SHA-256 of the canonical form, so a contract signature
+//! cannot pass for a report's.
+//!
+//! Keys are one line of text: `ed25519-secret <base64 seed>` for a secret
+//! key and `ed25519 <base64 key>` for a public one. `signed_by` holds the
+//! signer's public key in the latter form; it travels with the signature, so
+//! verifying always checks it against keys the verifier already trusts.
+
+use crate::error::{PolicyError, Result};
+use crate::formatter::format_a2ml;
+use crate::parser::A2mlDocument;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ed25519_dalek::{Signer, SigningKey, Verifier, VerifyingKey};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Extension of a detached signature (`contract.a2ml.sig`)
+pub const SIGNATURE_EXTENSION: &str = "sig";
+
+const SECRET_PREFIX: &str = "ed25519-secret";
+const PUBLIC_PREFIX: &str = "ed25519";
+
+/// What was signed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignedKind {
+    /// An A2ML contract
+    Contract,
+    /// A JSON audit report
+    Report,
+}
+
+impl SignedKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SignedKind::Contract => "contract",
+            SignedKind::Report => "report",
+        }
+    }
+}
+
+/// A signature with what it covers; the content of a detached `.sig` file
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Signature {
+    pub kind: SignedKind,
+
+    /// SHA-256 of the canonical form (hex)
+    pub checksum: String,
+
+    /// Ed25519 signature (base64)
+    pub signature: String,
+
+    /// Signer's public key (`ed25519 <base64>`)
+    pub signed_by: String,
+}
+
+impl Signature {
+    /// Check the signature against a document's canonical form, made by one
+    /// of the `trusted` keys
+    pub fn verify(&self, canonical: &str, trusted: &[PublicKey]) -> Result<()> {
+        let signer = PublicKey::from_text(&self.signed_by)?;
+        if trusted.is_empty() {
+            return Err(PolicyError::SignatureError(format!("signed by {}, but no signer keys are trusted", signer)));
+        }
+        if !trusted.contains(&signer) {
+            return Err(PolicyError::SignatureError(format!("signed by {}, which is not a trusted key", signer)));
+        }
+        let checksum = checksum(canonical);
+        if checksum != self.checksum {
+            return Err(PolicyError::SignatureError(format!(
+                "{} has changed since it was signed (checksum {}, signed {})",
+                self.kind.as_str(),
+                checksum,
+                self.checksum
+            )));
+        }
+        let bytes = BASE64
+            .decode(self.signature.trim())
+            .map_err(|e| PolicyError::SignatureError(format!("signature is not base64: {}", e)))?;
+        let signature = ed25519_dalek::Signature::from_slice(&bytes)
+            .map_err(|_| PolicyError::SignatureError("signature is not 64 bytes".to_string()))?;
+        signer
+            .0
+            .verify(message(self.kind, &checksum).as_bytes(), &signature)
+            .map_err(|_| PolicyError::SignatureError("signature does not match".to_string()))
+    }
+
+    /// Read a detached signature
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Err(PolicyError::FileNotFound(path.to_path_buf()));
+        }
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+}
+
+/// A secret signing key
+pub struct SecretKey(SigningKey);
+
+impl SecretKey {
+    /// The key for a 32-byte seed; callers supply the randomness
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self(SigningKey::from_bytes(&seed))
+    }
+
+    /// Parse `ed25519-secret <base64 seed>`
+    pub fn from_text(text: &str) -> Result<Self> {
+        let seed = key_bytes(text, SECRET_PREFIX)?;
+        Ok(Self::from_seed(seed))
+    }
+
+    pub fn to_text(&self) -> String {
+        format!("{} {}\n", SECRET_PREFIX, BASE64.encode(self.0.to_bytes()))
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(self.0.verifying_key())
+    }
+
+    /// Sign a document's canonical form
+    pub fn sign(&self, kind: SignedKind, canonical: &str) -> Signature {
+        let checksum = checksum(canonical);
+        let signature = self.0.sign(message(kind, &checksum).as_bytes());
+        Signature {
+            kind,
+            checksum,
+            signature: BASE64.encode(signature.to_bytes()),
+            signed_by: self.public_key().to_string(),
+        }
+    }
+}
+
+/// A public key, to verify with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey(VerifyingKey);
+
+impl PublicKey {
+    /// Parse `ed25519 <base64 key>`; a trailing comment is ignored
+    pub fn from_text(text: &str) -> Result<Self> {
+        let bytes = key_bytes(text, PUBLIC_PREFIX)?;
+        VerifyingKey::from_bytes(&bytes)
+            .map(Self)
+            .map_err(|_| PolicyError::SignatureError("not a valid Ed25519 public key".to_string()))
+    }
+
+    /// Read a public key file (`ed25519 <base64 key>`)
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Err(PolicyError::FileNotFound(path.to_path_buf()));
+        }
+        Self::from_text(&std::fs::read_to_string(path)?)
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", PUBLIC_PREFIX, BASE64.encode(self.0.as_bytes()))
+    }
+}
+
+fn key_bytes(text: &str, prefix: &str) -> Result<[u8; 32]> {
+    let mut words = text.split_whitespace();
+    let (Some(found), Some(key)) = (words.next(), words.next()) else {
+        return Err(PolicyError::SignatureError(format!("expected `{} <base64>`", prefix)));
+    };
+    if found != prefix {
+        return Err(PolicyError::SignatureError(format!("expected a `{}` key, found `{}`", prefix, found)));
+    }
+    BASE64
+        .decode(key)
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or_else(|| PolicyError::SignatureError("key is not 32 bytes of base64".to_string()))
+}
+
+fn message(kind: SignedKind, checksum: &str) -> String {
+    format!("union-policy-parser:{}:{}", kind.as_str(), checksum)
+}
+
+/// SHA-256 of a canonical form (hex)
+pub fn checksum(canonical: &str) -> String {
+    hex::encode(Sha256::digest(canonical.as_bytes()))
+}
+
+/// Canonical form of a contract: its source with `\n` line endings; a
+/// document built in memory has no source, so its `fmt` layout
+pub fn canonical_contract(contract: &A2mlDocument) -> String {
+    if contract.raw.is_empty() {
+        return format_a2ml(contract);
+    }
+    contract.raw.replace("\r\n", "\n")
+}
+
+/// Canonical form of a JSON report: keys sorted, no whitespace
+pub fn canonical_json(value: &Value) -> String {
+    fn sorted(value: &Value) -> Value {
+        match value {
+            Value::Object(fields) => {
+                let mut keys: Vec<&String> = fields.keys().collect();
+                keys.sort();
+                let fields: Map<String, Value> = keys.into_iter().map(|k| (k.clone(), sorted(&fields[k]))).collect();
+                Value::Object(fields)
+            }
+            Value::Array(items) => Value::Array(items.iter().map(sorted).collect()),
+            other => other.clone(),
+        }
+    }
+    sorted(value).to_string()
+}
+
+/// Where a file's detached signature lives
+pub fn detached_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(SIGNATURE_EXTENSION);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_sign_and_verify_contract() {
+        let key = SecretKey::from_seed([7; 32]);
+        let contract = crate::parse("# Commission\n\n## Kill Fee\n\n**Kill Fee:** 50%\n").unwrap();
+        let signature = key.sign(SignedKind::Contract, &canonical_contract(&contract));
+        assert!(signature.signed_by.starts_with("ed25519 "));
+
+        // Line endings don't matter; any other change breaks the signature
+        let trusted = [key.public_key()];
+        let crlf = crate::parse("# Commission\r\n\r\n## Kill Fee\r\n\r\n**Kill Fee:** 50%\r\n").unwrap();
+        signature.verify(&canonical_contract(&crlf), &trusted).unwrap();
+        let altered = crate::parse("# Commission\n\n## Kill Fee\n\n**Kill Fee:** 5%\n").unwrap();
+        assert!(signature.verify(&canonical_contract(&altered), &trusted).is_err());
+
+        // A valid signature by a key nobody trusts is no signature at all
+        let other = SecretKey::from_seed([8; 32]).public_key();
+        assert!(signature.verify(&canonical_contract(&contract), &[other]).is_err());
+        assert!(signature.verify(&canonical_contract(&contract), &[]).is_err());
+
+        let restored = SecretKey::from_text(&key.to_text()).unwrap();
+        assert_eq!(PublicKey::from_text(&restored.public_key().to_string()).unwrap(), key.public_key());
+    }
+
+    #[test]
+    fn test_preamble_and_numbering_are_signed() {
+        let key = SecretKey::from_seed([3; 32]);
+        let source = "The Writer grants first rights only.\n\n# Commission\n\n## Schedule\n\n3) Draft\n4) Final\n";
+        let signature = key.sign(SignedKind::Contract, &canonical_contract(&crate::parse(source).unwrap()));
+
+        for edited in [
+            source.replace("first rights only", "all rights in perpetuity"),
+            source.replace("3) Draft\n4) Final", "1) Draft\n2) Final"),
+            source.replace("\n\n## Schedule", "\n\n\n## Schedule"),
+        ] {
+            let contract = crate::parse(&edited).unwrap();
+            assert!(signature.verify(&canonical_contract(&contract), &[key.public_key()]).is_err(), "{}", edited);
+        }
+    }
+
+    #[test]
+    fn test_report_signature_is_order_independent() {
+        let key = SecretKey::from_seed([1; 32]);
+        let report = json!({"valid": true, "errors": [], "contract": {"path": "a.a2ml"}});
+        let signature = key.sign(SignedKind::Report, &canonical_json(&report));
+        let trusted = [key.public_key()];
+
+        let reordered = json!({"contract": {"path": "a.a2ml"}, "errors": [], "valid": true});
+        signature.verify(&canonical_json(&reordered), &trusted).unwrap();
+        assert!(signature.verify(&canonical_json(&json!({"valid": false})), &trusted).is_err());
+
+        // The kind is part of what is signed
+        let relabelled = Signature { kind: SignedKind::Contract, ..signature };
+        assert!(relabelled.verify(&canonical_json(&reordered), &trusted).is_err());
+    }
+}
@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! `-` as standard input or output for file arguments
+//!
+//! Contracts piped from converters are read with [`parse_a2ml_string`], the
+//! same path the file reader takes after loading. Stdin can only be consumed
+//! once, so a second `-` input in one command is an error rather than an
+//! empty document.
+//!
+//! Schema arguments may also name a built-in schema
+//! (`embedded:nuj-code-of-ethics.a2ml`), which is read from the binary.
+
+use crate::error::{PolicyError, Result};
+use crate::parser::{parse_a2ml_file, parse_a2ml_string, A2mlDocument};
+use crate::schemas::EmbeddedSchema;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Name reports use for a contract read from stdin without `--name`
+pub const STDIN_NAME: &str = "<stdin>";
+
+static STDIN_READ: AtomicBool = AtomicBool::new(false);
+
+/// Whether a path argument means stdin or stdout
+pub fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Contents of a file, or of stdin for `-`
+pub fn read_to_string(path: &Path) -> Result<String> {
+    if let Some(schema) = EmbeddedSchema::from_path(path) {
+        return Ok(schema.source.to_string());
+    }
+    if !is_stdio(path) {
+        return fs::read_to_string(path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => PolicyError::FileNotFound(path.to_path_buf()),
+            _ => PolicyError::IoError(e),
+        });
+    }
+
+    if STDIN_READ.swap(true, Ordering::SeqCst) {
+        return Err(PolicyError::IoError(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "only one input can be read from stdin (-)",
+        )));
+    }
+    let mut contents = String::new();
+    std::io::stdin().read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+/// Parse an A2ML file, or stdin for `-`
+pub fn parse(path: &Path) -> Result<A2mlDocument> {
+    if is_stdio(path) {
+        let mut doc = parse_a2ml_string(&read_to_string(path)?)?;
+        doc.path = Some(STDIN_NAME.to_string());
+        Ok(doc)
+    } else if let Some(schema) = EmbeddedSchema::from_path(path) {
+        schema.parse()
+    } else {
+        parse_a2ml_file(path)
+    }
+}
+
+/// Write to a file, or to stdout for `-`
+pub fn write(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    if !is_stdio(path) {
+        return Ok(fs::write(path, contents)?);
+    }
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(contents.as_ref())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Where an output went, for messages
+pub fn describe(path: &Path) -> String {
+    if is_stdio(path) {
+        "stdout".to_string()
+    } else {
+        format!("{:?}", path)
+    }
+}
+
+/// ID for a contract in reports: `--name`, else the path, else `<stdin>`
+pub fn contract_name(path: &Path, name: Option<&str>) -> String {
+    match name {
+        Some(name) => name.to_string(),
+        None if is_stdio(path) => STDIN_NAME.to_string(),
+        None => path.to_string_lossy().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_and_files() {
+        assert!(is_stdio(Path::new("-")));
+        assert!(!is_stdio(Path::new("./-")));
+        assert_eq!(contract_name(Path::new("-"), None), STDIN_NAME);
+        assert_eq!(contract_name(Path::new("-"), Some("acme-2025")), "acme-2025");
+        assert_eq!(contract_name(Path::new("a/b.a2ml"), None), "a/b.a2ml");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("c.a2ml");
+        write(&path, "# Contract\n\n## Pay\n\nNET 30.\n").unwrap();
+        assert!(parse(&path).unwrap().sections.iter().any(|s| s.heading == "Pay"));
+        assert!(matches!(parse(&dir.path().join("missing.a2ml")), Err(PolicyError::FileNotFound(_))));
+    }
+}
@@ -0,0 +1,32 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Text helpers shared by the validator, diff and renderers
+
+/// URL-fragment slug for a heading ("2. Payment Terms" -> "2-payment-terms")
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-').to_string();
+    if slug.is_empty() {
+        "section".to_string()
+    } else {
+        slug
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("2. Payment Terms"), "2-payment-terms");
+        assert_eq!(slugify("Health & Safety"), "health-safety");
+        assert_eq!(slugify("***"), "section");
+    }
+}
@@ -2,11 +2,17 @@
 //! Contract validation logic
 
 use crate::error::{PolicyError, Result};
-use crate::parser::{A2mlDocument, Section};
-use std::collections::HashSet;
+use crate::parser::{A2mlDocument, ContentBlock, Section};
+use crate::cst::{NodeKind, SyntaxTree};
+use crate::predicate::{self, Outcome, Predicate, PredicateCheck};
+use crate::schemas::Union;
+use crate::signing::{canonical_contract, PublicKey, Signature};
+use aho_corasick::AhoCorasick;
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
 
 /// Validation modes
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum ValidationMode {
     /// Parse syntax only
     Lax,
@@ -16,14 +22,115 @@ pub enum ValidationMode {
     Attested,
 }
 
+/// Case-insensitive: "lax", "checked", "attested"
+impl std::str::FromStr for ValidationMode {
+    type Err = PolicyError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "lax" => Ok(ValidationMode::Lax),
+            "checked" => Ok(ValidationMode::Checked),
+            "attested" => Ok(ValidationMode::Attested),
+            _ => Err(PolicyError::ValidationError(format!(
+                "Unknown mode: {} (expected lax, checked or attested)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Requirement strength, as written in A2ML attestations (*Must*, *Should*, *Could*)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize)]
+pub enum Severity {
+    Must,
+    Should,
+    Could,
+}
+
+impl Severity {
+    /// Parse an attestation requirement ("MUST", "SHOULD", "COULD")
+    pub fn from_requirement(requirement: &str) -> Option<Self> {
+        match requirement.to_uppercase().as_str() {
+            "MUST" => Some(Severity::Must),
+            "SHOULD" => Some(Severity::Should),
+            "COULD" => Some(Severity::Could),
+            _ => None,
+        }
+    }
+
+    /// "MUST", "SHOULD" or "COULD"
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Must => "MUST",
+            Severity::Should => "SHOULD",
+            Severity::Could => "COULD",
+        }
+    }
+}
+
+/// Identity of a document, so a report traces to the exact version checked
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DocumentInfo {
+    /// Path, or the name given to piped input
+    pub path: String,
+
+    /// SHA-256 of the source text (hex)
+    pub sha256: Option<String>,
+
+    /// Title: the first top-level heading
+    pub name: Option<String>,
+
+    /// Declared version (a `Version:` line)
+    pub version: Option<String>,
+}
+
+impl DocumentInfo {
+    /// A document known only by its path
+    pub fn from_path(path: String) -> Self {
+        Self {
+            path,
+            ..Default::default()
+        }
+    }
+
+    /// Identity of a parsed document
+    pub fn of(doc: &A2mlDocument) -> Self {
+        Self {
+            path: doc.path.clone().unwrap_or_default(),
+            sha256: Some(hex::encode(Sha256::digest(doc.raw.as_bytes()))),
+            name: doc.sections.iter().find(|s| s.level == 1).map(|s| s.heading.clone()),
+            version: declared_version(&doc.raw),
+        }
+    }
+}
+
+/// First `Version: x` line, bold or not (`**Version:** 1.2`)
+fn declared_version(raw: &str) -> Option<String> {
+    raw.lines().find_map(|line| {
+        let line = line.replace("**", "");
+        let (key, value) = line.split_once(':')?;
+        let value = value.trim();
+        (key.trim().eq_ignore_ascii_case("version") && !value.is_empty()).then(|| value.to_string())
+    })
+}
+
 /// Validation report
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ValidationReport {
     /// Contract being validated
-    pub contract_path: String,
+    pub contract: DocumentInfo,
 
     /// Schema used for validation
-    pub schema_path: String,
+    pub schema: DocumentInfo,
+
+    /// Union whose rules were applied (e.g., "nuj")
+    pub union: Option<String>,
+
+    /// Version of union-policy-parser that produced the report
+    pub tool_version: String,
+
+    /// When the validation ran (RFC 3339, UTC)
+    pub timestamp: String,
 
     /// Overall validation result
     pub valid: bool,
@@ -36,9 +143,17 @@ pub struct ValidationReport {
 
     /// Required clauses checked
     pub required_clauses: Vec<ClauseCheck>,
+
+    /// Red-flag (exploitative) phrases found in the contract
+    pub red_flags: Vec<RedFlag>,
+
+    /// K9 contract predicates checked
+    #[serde(default)]
+    pub predicates: Vec<PredicateCheck>,
 }
 
-#[derive(Debug, Clone)]
+/// A structural problem with the contract
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ValidationError {
     /// Error type
     pub kind: ErrorKind,
@@ -50,7 +165,8 @@ pub struct ValidationError {
     pub location: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Kind of a validation error; the rule ID is `a2ml/<kind>`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum ErrorKind {
     MissingClause,
     InvalidValue,
@@ -59,7 +175,8 @@ pub enum ErrorKind {
     AttestationFailure,
 }
 
-#[derive(Debug, Clone)]
+/// A structural problem that does not make the contract invalid
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ValidationWarning {
     /// Warning message
     pub message: String,
@@ -68,7 +185,8 @@ pub struct ValidationWarning {
     pub location: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+/// Whether the contract contains one required or recommended clause
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ClauseCheck {
     /// Clause name (e.g., "source-protection")
     pub clause: String,
@@ -76,25 +194,93 @@ pub struct ClauseCheck {
     /// Present in contract?
     pub present: bool,
 
-    /// Value (if applicable)
+    /// The section's `**Label:** value` line naming the clause, if it has one
     pub value: Option<String>,
 
     /// Expected value
     pub expected: Option<String>,
+
+    /// Heading of the section that satisfied the clause
+    pub section: Option<String>,
+
+    /// MUST for required clauses, SHOULD for recommended ones, unless the
+    /// schema's attestation says otherwise
+    pub severity: Severity,
+
+    /// Guidance from the matching schema section
+    pub help: Option<String>,
+}
+
+/// An exploitative phrase found in the contract
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RedFlag {
+    /// Pattern that matched (e.g., "all rights")
+    pub pattern: String,
+
+    /// Contract text at the match, with line breaks as spaces
+    pub excerpt: String,
+
+    /// Heading of the section containing the match
+    pub location: Option<String>,
+
+    /// 1-based source line of the match
+    pub line: Option<usize>,
+}
+
+/// A single finding, flattened for machine-readable formats (SARIF, CSV, ...)
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Finding {
+    /// Rule ID, `<union>/<clause>` (e.g., "nuj/source-protection")
+    pub rule: String,
+
+    /// MUST findings make a contract invalid
+    pub severity: Severity,
+
+    /// Human-readable message
+    pub message: String,
+
+    /// Heading of the section the finding refers to
+    pub location: Option<String>,
+
+    /// 1-based source line
+    pub line: Option<usize>,
+
+    /// Guidance on how to fix it, from the schema
+    pub help: Option<String>,
+}
+
+impl ErrorKind {
+    /// Kebab-case rule name (e.g., "missing-clause")
+    pub fn rule_name(&self) -> &'static str {
+        match self {
+            ErrorKind::MissingClause => "missing-clause",
+            ErrorKind::InvalidValue => "invalid-value",
+            ErrorKind::UnresolvedReference => "unresolved-reference",
+            ErrorKind::StructureError => "structure-error",
+            ErrorKind::AttestationFailure => "attestation-failure",
+        }
+    }
 }
 
 impl ValidationReport {
+    /// An empty, valid report, stamped with the tool version and current time
     pub fn new(contract_path: String, schema_path: String) -> Self {
         Self {
-            contract_path,
-            schema_path,
+            contract: DocumentInfo::from_path(contract_path),
+            schema: DocumentInfo::from_path(schema_path),
+            union: None,
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            timestamp: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
             valid: true,
             errors: Vec::new(),
             warnings: Vec::new(),
             required_clauses: Vec::new(),
+            red_flags: Vec::new(),
+            predicates: Vec::new(),
         }
     }
 
+    /// Record an error; the contract is no longer valid
     pub fn add_error(&mut self, kind: ErrorKind, message: String, location: Option<String>) {
         self.valid = false;
         self.errors.push(ValidationError {
@@ -104,49 +290,433 @@ impl ValidationReport {
         });
     }
 
+    /// Record a warning; validity is unchanged
     pub fn add_warning(&mut self, message: String, location: Option<String>) {
         self.warnings.push(ValidationWarning { message, location });
     }
 
+    /// Record a clause check; a missing MUST clause makes the contract invalid
     pub fn add_clause_check(&mut self, check: ClauseCheck) {
-        if !check.present {
+        if !check.present && check.severity == Severity::Must {
             self.valid = false;
         }
         self.required_clauses.push(check);
     }
+
+    /// Record a red flag; the contract is no longer valid
+    pub fn add_red_flag(&mut self, flag: RedFlag) {
+        self.valid = false;
+        self.red_flags.push(flag);
+    }
+
+    /// Record a contract predicate; a failed one makes the contract invalid
+    pub fn add_predicate_check(&mut self, check: PredicateCheck) {
+        if check.outcome == Outcome::Fail {
+            self.valid = false;
+        }
+        self.predicates.push(check);
+    }
+
+    /// Prefix for rule IDs: the union, or "a2ml" when none was given
+    pub fn rule_prefix(&self) -> &str {
+        self.union.as_deref().unwrap_or("a2ml")
+    }
+
+    /// Every problem in the report as a flat list of findings
+    pub fn findings(&self) -> Vec<Finding> {
+        let prefix = self.rule_prefix();
+        let mut findings = Vec::new();
+
+        for error in &self.errors {
+            findings.push(Finding {
+                rule: format!("a2ml/{}", error.kind.rule_name()),
+                severity: Severity::Must,
+                message: error.message.clone(),
+                location: error.location.clone(),
+                line: None,
+                help: None,
+            });
+        }
+
+        for warning in &self.warnings {
+            findings.push(Finding {
+                rule: "a2ml/structure".to_string(),
+                severity: Severity::Should,
+                message: warning.message.clone(),
+                location: warning.location.clone(),
+                line: None,
+                help: None,
+            });
+        }
+
+        for check in self.required_clauses.iter().filter(|c| !c.present) {
+            let kind = if check.severity == Severity::Must { "required" } else { "recommended" };
+            findings.push(Finding {
+                rule: format!("{}/{}", prefix, check.clause),
+                severity: check.severity,
+                message: format!("Missing {} clause: {}", kind, check.clause),
+                location: None,
+                line: None,
+                help: check.help.clone(),
+            });
+        }
+
+        for flag in &self.red_flags {
+            findings.push(Finding {
+                rule: format!("{}/red-flag/{}", prefix, crate::text::slugify(&flag.pattern)),
+                severity: Severity::Must,
+                message: format!("Red flag: \"{}\"", flag.excerpt),
+                location: flag.location.clone(),
+                line: flag.line,
+                help: Some(format!("\"{}\" is an exploitative term; negotiate its removal.", flag.pattern)),
+            });
+        }
+
+        for check in &self.predicates {
+            let field = check.field.as_deref().unwrap_or("?");
+            let condition = check.condition.as_deref().unwrap_or("?");
+            let (severity, message) = match check.outcome {
+                Outcome::Pass => continue,
+                Outcome::Fail => (
+                    Severity::Must,
+                    format!(
+                        "Contract predicate {} failed: {} is {}, needs {}",
+                        check.name,
+                        field,
+                        check.value.as_deref().unwrap_or("?"),
+                        condition
+                    ),
+                ),
+                Outcome::Unknown => (
+                    Severity::Should,
+                    format!(
+                        "Contract predicate {} not checked: {}",
+                        check.name,
+                        check.reason.as_deref().unwrap_or("unknown")
+                    ),
+                ),
+            };
+            findings.push(Finding {
+                rule: format!("k9/{}", check.name),
+                severity,
+                message,
+                location: check.section.clone(),
+                line: None,
+                help: (check.outcome == Outcome::Unknown && check.field.is_some())
+                    .then(|| format!("State {} in the contract, e.g. as a **Label:** line.", field)),
+            });
+        }
+
+        findings
+    }
+
+    /// Errors located in the section with the given heading
+    pub fn errors_at<'a>(&'a self, heading: &'a str) -> impl Iterator<Item = &'a ValidationError> {
+        self.errors.iter().filter(move |e| e.location.as_deref() == Some(heading))
+    }
+
+    /// Warnings located in the section with the given heading
+    pub fn warnings_at<'a>(&'a self, heading: &'a str) -> impl Iterator<Item = &'a ValidationWarning> {
+        self.warnings.iter().filter(move |w| w.location.as_deref() == Some(heading))
+    }
+
+    /// Red flags found in the section with the given heading
+    pub fn red_flags_at<'a>(&'a self, heading: &'a str) -> impl Iterator<Item = &'a RedFlag> {
+        self.red_flags.iter().filter(move |f| f.location.as_deref() == Some(heading))
+    }
+}
+
+/// Does a section heading satisfy a clause ID?
+///
+/// Clause IDs are kebab-case (`source-protection`); every word of the ID must
+/// appear in the heading, so "5. Source Protection" and "Protection of
+/// Sources" both match, while "Sources" alone does not. A heading containing
+/// the ID as written ("Fairness-Policy" for `fairness`) still matches.
+pub fn clause_matches(heading: &str, clause: &str) -> bool {
+    if heading.to_lowercase().contains(&clause.to_lowercase()) {
+        return true;
+    }
+
+    let words: Vec<String> = heading
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+
+    clause
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .all(|part| {
+            let part = part.to_lowercase();
+            words.iter().any(|w| *w == part || w.trim_end_matches('s') == part)
+        })
+}
+
+/// Find exploitative phrases in a contract, located by line and section
+pub fn find_red_flags(contract: &A2mlDocument, patterns: &[String], case_insensitive: bool) -> Vec<RedFlag> {
+    match red_flag_matcher(patterns, case_insensitive) {
+        Some(matcher) => scan_red_flags(contract, &matcher, patterns),
+        None => Vec::new(),
+    }
+}
+
+fn red_flag_matcher(patterns: &[String], case_insensitive: bool) -> Option<AhoCorasick> {
+    if patterns.is_empty() {
+        return None;
+    }
+
+    AhoCorasick::builder()
+        .ascii_case_insensitive(case_insensitive)
+        .build(patterns)
+        .map_err(|e| log::warn!("Could not build red-flag matcher: {}", e))
+        .ok()
+}
+
+fn scan_red_flags(contract: &A2mlDocument, matcher: &AhoCorasick, patterns: &[String]) -> Vec<RedFlag> {
+    // Scan the source so every match has a line number; documents built in
+    // memory have no source, so scan their canonical layout without lines
+    let in_memory = contract.raw.is_empty();
+    let source = if in_memory { crate::formatter::format_a2ml(contract) } else { contract.raw.clone() };
+
+    let mut flags = Vec::new();
+    let mut location: Option<String> = None;
+    for node in SyntaxTree::parse(&source).nodes() {
+        match &node.kind {
+            NodeKind::Heading { .. } => location = node.heading().map(str::to_string),
+            NodeKind::Paragraph | NodeKind::BulletList | NodeKind::OrderedList | NodeKind::Table => {}
+            NodeKind::Directive { name } if name == "abstract" => {}
+            // Code, comments, @refs and @requires are not contract prose
+            _ => continue,
+        }
+
+        let (text, offsets) = prose_text(&node.text);
+        for m in matcher.find_iter(&text) {
+            let line = node.line + node.text[..offsets[m.start()]].matches('\n').count();
+            flags.push(RedFlag {
+                pattern: patterns[m.pattern().as_usize()].clone(),
+                excerpt: text[m.start()..m.end()].to_string(),
+                location: location.clone(),
+                line: (!in_memory).then_some(line),
+            });
+        }
+    }
+
+    flags
+}
+
+/// Node text with `<!-- comments -->` removed and whitespace runs collapsed
+/// to one space, so phrases wrapped across lines match; and the byte offset
+/// in `text` of each byte of the result
+fn prose_text(text: &str) -> (String, Vec<usize>) {
+    let mut prose = String::with_capacity(text.len());
+    let mut offsets = Vec::with_capacity(text.len());
+    let mut push = |c: char, at: usize, prose: &mut String| {
+        if c.is_whitespace() && (prose.is_empty() || prose.ends_with(' ')) {
+            return;
+        }
+        let c = if c.is_whitespace() { ' ' } else { c };
+        prose.push(c);
+        offsets.extend(std::iter::repeat_n(at, c.len_utf8()));
+    };
+
+    let mut rest = 0;
+    while let Some(start) = text[rest..].find("<!--").map(|i| rest + i) {
+        for (i, c) in text[rest..start].char_indices() {
+            push(c, rest + i, &mut prose);
+        }
+        push(' ', start, &mut prose);
+        rest = text[start..].find("-->").map_or(text.len(), |end| start + end + 3);
+    }
+    for (i, c) in text[rest..].char_indices() {
+        push(c, rest + i, &mut prose);
+    }
+    offsets.push(text.len());
+    (prose, offsets)
+}
+
+/// Severity and guidance of one schema section
+struct SchemaRule {
+    heading: String,
+    severity: Option<Severity>,
+    help: Option<String>,
+}
+
+/// Schema rules and red-flag matcher, built once per validator
+struct Compiled {
+    rules: Vec<SchemaRule>,
+    red_flags: Option<AhoCorasick>,
 }
 
 /// Validator for contracts against schemas
+///
+/// The schema and red-flag patterns are compiled on first use; a validator
+/// is `Sync`, so one instance can be shared by every batch worker.
 pub struct Validator {
     schema: A2mlDocument,
     mode: ValidationMode,
+    union: Option<Union>,
+    red_flags: Vec<String>,
+    recommended_clauses: Vec<String>,
+    predicates: Vec<Predicate>,
+    /// `Some` when a signature is required: the one found, if any, and the
+    /// keys it may be made with
+    required_signature: Option<(Option<Signature>, Vec<PublicKey>)>,
+    compiled: OnceLock<Compiled>,
 }
 
 impl Validator {
+    /// A validator for a parsed schema, with no union rules yet
     pub fn new(schema: A2mlDocument, mode: ValidationMode) -> Self {
-        Self { schema, mode }
+        Self {
+            schema,
+            mode,
+            union: None,
+            red_flags: Vec::new(),
+            recommended_clauses: Vec::new(),
+            predicates: Vec::new(),
+            required_signature: None,
+            compiled: OnceLock::new(),
+        }
     }
 
-    /// Validate a contract against the loaded schema
-    pub fn validate(&self, contract: &A2mlDocument, required_clauses: &[String]) -> ValidationReport {
-        log::info!("Validating contract (mode: {:?})", self.mode);
+    /// Apply a union's red flags and recommended clauses, and prefix rule IDs with it
+    pub fn with_union(mut self, union: Union) -> Self {
+        self.union = Some(union);
+        self.red_flags
+            .extend(union.red_flag_patterns().iter().map(|s| s.to_string()));
+        self.recommended_clauses
+            .extend(union.recommended_clauses().iter().map(|s| s.to_string()));
+        self.compiled = OnceLock::new();
+        self
+    }
 
-        let mut report = ValidationReport::new(
-            "contract".to_string(),  // TODO: Get actual path
-            "schema".to_string(),
-        );
+    /// Also report these clauses as SHOULD findings when missing
+    pub fn with_recommended_clauses(mut self, clauses: Vec<String>) -> Self {
+        self.recommended_clauses.extend(clauses);
+        self
+    }
 
-        // Check required clauses
-        for clause in required_clauses {
-            let present = self.has_clause(contract, clause);
+    /// Also check these K9 contract predicates
+    pub fn with_predicates(mut self, predicates: Vec<Predicate>) -> Self {
+        self.predicates.extend(predicates);
+        self
+    }
+
+    /// Require a valid signature over the contract by one of the `trusted`
+    /// keys (K9 'Hunt trust level); `None` means none was found
+    pub fn with_required_signature(mut self, signature: Option<Signature>, trusted: Vec<PublicKey>) -> Self {
+        self.required_signature = Some((signature, trusted));
+        self
+    }
+
+    /// Also scan the contract for these exploitative phrases (case-insensitive)
+    pub fn with_red_flags(mut self, patterns: Vec<String>) -> Self {
+        self.red_flags.extend(patterns);
+        self.compiled = OnceLock::new();
+        self
+    }
+
+    /// The schema contracts are checked against
+    pub fn schema(&self) -> &A2mlDocument {
+        &self.schema
+    }
+
+    /// SHA-256 over the schema and every setting that affects findings, so
+    /// cached results are reused only under the same rules
+    pub fn fingerprint(&self, required_clauses: &[String]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(format!("{:?}", self.schema));
+        let predicates: Vec<_> = self.predicates.iter().map(|p| (p.name(), p.source())).collect();
+        hasher.update(format!(
+            "{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}",
+            self.mode,
+            self.union,
+            self.red_flags,
+            self.recommended_clauses,
+            required_clauses,
+            predicates,
+            self.required_signature
+        ));
+        hex::encode(hasher.finalize())
+    }
+
+    fn compiled(&self) -> &Compiled {
+        self.compiled.get_or_init(|| Compiled {
+            rules: self
+                .schema
+                .sections
+                .iter()
+                .map(|section| SchemaRule {
+                    heading: section.heading.clone(),
+                    severity: section
+                        .attestations
+                        .first()
+                        .and_then(|a| Severity::from_requirement(&a.requirement)),
+                    help: schema_guidance(section),
+                })
+                .collect(),
+            red_flags: red_flag_matcher(&self.red_flags, true),
+        })
+    }
+
+    /// Validate a contract against the loaded schema
+    pub fn validate(&self, contract: &A2mlDocument, required_clauses: &[String]) -> ValidationReport {
+        log::debug!("Validating contract (mode: {:?})", self.mode);
+        let compiled = self.compiled();
+
+        let mut report = ValidationReport::new(String::new(), String::new());
+        report.contract = DocumentInfo::of(contract);
+        report.schema = DocumentInfo::of(&self.schema);
+
+        report.union = self.union.map(|u| u.as_str().to_string());
+
+        // Check required, then recommended, clauses
+        let clauses = required_clauses
+            .iter()
+            .map(|c| (c, Severity::Must))
+            .chain(self.recommended_clauses.iter().map(|c| (c, Severity::Should)));
+        for (clause, default_severity) in clauses {
+            let section = self.find_clause(contract, clause);
+            let rule = compiled.rules.iter().find(|r| clause_matches(&r.heading, clause));
             report.add_clause_check(ClauseCheck {
                 clause: clause.clone(),
-                present,
-                value: None,  // TODO: Extract actual value
-                expected: None,  // TODO: Get from schema
+                present: section.is_some(),
+                value: section.and_then(|s| predicate::clause_value(s, clause)),
+                expected: None,
+                section: section.map(|s| s.heading.clone()),
+                severity: rule.and_then(|r| r.severity).unwrap_or(default_severity),
+                help: rule.and_then(|r| r.help.clone()),
             });
         }
 
+        if let Some(matcher) = &compiled.red_flags {
+            for flag in scan_red_flags(contract, matcher, &self.red_flags) {
+                report.add_red_flag(flag);
+            }
+        }
+
+        for predicate in &self.predicates {
+            report.add_predicate_check(predicate.check(contract));
+        }
+
+        match &self.required_signature {
+            None => {}
+            Some((None, _)) => report.add_error(
+                ErrorKind::AttestationFailure,
+                "Contract is not signed; its K9 trust level requires a signature".to_string(),
+                None,
+            ),
+            Some((Some(signature), trusted)) => {
+                if let Err(e) = signature.verify(&canonical_contract(contract), trusted) {
+                    let reason = match e {
+                        PolicyError::SignatureError(reason) => reason,
+                        e => e.to_string(),
+                    };
+                    report.add_error(ErrorKind::AttestationFailure, format!("Contract signature is invalid: {}", reason), None);
+                }
+            }
+        }
+
         // Mode-specific validation
         match self.mode {
             ValidationMode::Lax => {
@@ -164,12 +734,9 @@ impl Validator {
         report
     }
 
-    fn has_clause(&self, contract: &A2mlDocument, clause: &str) -> bool {
-        // TODO: Implement clause detection
-        // For now, just check if section heading matches
-        contract.sections.iter().any(|s| {
-            s.heading.to_lowercase().contains(&clause.to_lowercase())
-        })
+    fn find_clause<'a>(&self, contract: &'a A2mlDocument, clause: &str) -> Option<&'a Section> {
+        // TODO: Match clause bodies as well as headings
+        contract.sections.iter().find(|s| clause_matches(&s.heading, clause))
     }
 
     fn validate_structure(&self, contract: &A2mlDocument, report: &mut ValidationReport) {
@@ -193,7 +760,7 @@ impl Validator {
         // TODO: More structural checks
     }
 
-    fn validate_attestations(&self, contract: &A2mlDocument, report: &mut ValidationReport) {
+    fn validate_attestations(&self, _contract: &A2mlDocument, _report: &mut ValidationReport) {
         // TODO: Verify attestations against external sources
         // This is the "attested" mode - checks legal compliance
 
@@ -210,10 +777,37 @@ impl Validator {
     }
 }
 
+/// First paragraph of a schema section (its principle), for help text
+fn schema_guidance(section: &Section) -> Option<String> {
+    section.content.iter().find_map(|block| match block {
+        ContentBlock::Paragraph(text) => Some(text.clone()),
+        _ => None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_report_identifies_documents() {
+        let source = "# Pay Agreement\n\n## Pay\n\nNET 30.\n";
+        let mut contract = crate::parser::parse_a2ml_string(source).unwrap();
+        contract.path = Some("contracts/pay.a2ml".to_string());
+        let mut schema = crate::parser::parse_a2ml_string("# NUJ Code\n\n**Version:** 1.2\n\n## Pay\n\nPaid on time.\n").unwrap();
+        schema.path = Some("schemas/nuj.a2ml".to_string());
+
+        let report = Validator::new(schema, ValidationMode::Checked).validate(&contract, &[]);
+        assert_eq!(report.contract.path, "contracts/pay.a2ml");
+        assert_eq!(report.contract.sha256, Some(hex::encode(Sha256::digest(source))));
+        assert_eq!(report.contract.name.as_deref(), Some("Pay Agreement"));
+        assert_eq!(report.schema.path, "schemas/nuj.a2ml");
+        assert_eq!(report.schema.name.as_deref(), Some("NUJ Code"));
+        assert_eq!(report.schema.version.as_deref(), Some("1.2"));
+        assert_eq!(report.tool_version, env!("CARGO_PKG_VERSION"));
+        assert!(chrono::DateTime::parse_from_rfc3339(&report.timestamp).is_ok());
+    }
+
     #[test]
     fn test_validation_report() {
         let mut report = ValidationReport::new(
@@ -232,4 +826,126 @@ mod tests {
         assert!(!report.valid);
         assert_eq!(report.errors.len(), 1);
     }
+
+    #[test]
+    fn test_clause_matches_heading_words() {
+        assert!(clause_matches("5. Source Protection", "source-protection"));
+        assert!(clause_matches("Truth and Accuracy", "truth-accuracy"));
+        assert!(!clause_matches("Sources", "source-protection"));
+    }
+
+    #[test]
+    fn test_clause_matches_what_substring_matching_did() {
+        for (heading, clause) in [
+            ("Unfairness Complaints", "fairness"),
+            ("3. Kill-Fee Terms", "kill-fee"),
+            ("Editorial Independence", "independence"),
+            ("Payment-terms", "payment-terms"),
+        ] {
+            assert!(heading.to_lowercase().contains(clause));
+            assert!(clause_matches(heading, clause), "{} no longer matches {}", heading, clause);
+        }
+    }
+
+    #[test]
+    fn test_nuj_independence_clause_accepts_either_heading() {
+        let schema = crate::parser::parse_a2ml_string("# Schema\n").unwrap();
+        let clauses: Vec<String> = crate::schemas::Union::Nuj.required_clauses().iter().map(|c| c.to_string()).collect();
+        let validator = Validator::new(schema, ValidationMode::Lax);
+        for heading in ["2. Independence", "Editorial Independence"] {
+            let source = format!("## {}\n\nNo advertiser input.\n", heading);
+            let contract = crate::parser::parse_a2ml_string(&source).unwrap();
+            let report = validator.validate(&contract, &clauses);
+            let check = report.required_clauses.iter().find(|c| c.clause == "independence").unwrap();
+            assert!(check.present, "{} should satisfy the independence clause", heading);
+        }
+    }
+
+    #[test]
+    fn test_clause_value_from_labelled_line() {
+        let schema = crate::parser::parse_a2ml_string("# Schema\n").unwrap();
+        let contract = crate::parser::parse_a2ml_string(
+            "## 3. Kill Fee\n\n**Kill Fee:** 75% of agreed fee.\n\n## Payment\n\nWithin 30 days.\n",
+        )
+        .unwrap();
+        let clauses = vec!["kill-fee".to_string(), "payment".to_string()];
+        let report = Validator::new(schema, ValidationMode::Lax).validate(&contract, &clauses);
+        assert_eq!(report.required_clauses[0].value.as_deref(), Some("75% of agreed fee"));
+        assert_eq!(report.required_clauses[1].value, None);
+    }
+
+    #[test]
+    fn test_red_flags_located_by_section() {
+        let schema = crate::parser::parse_a2ml_string("# Schema\n").unwrap();
+        let contract = crate::parser::parse_a2ml_string(
+            "## 4. Copyright\n\nThe client acquires ALL RIGHTS in perpetuity.\n",
+        )
+        .unwrap();
+
+        let validator = Validator::new(schema, ValidationMode::Lax)
+            .with_red_flags(vec!["all rights".to_string()]);
+        let report = validator.validate(&contract, &[]);
+
+        assert_eq!(report.red_flags.len(), 1);
+        assert_eq!(report.red_flags[0].excerpt, "ALL RIGHTS");
+        assert_eq!(report.red_flags[0].line, Some(3));
+        assert_eq!(report.red_flags_at("4. Copyright").count(), 1);
+        assert!(!report.valid, "red flags are MUST findings");
+        assert_eq!(report.findings()[0].severity, Severity::Must);
+    }
+
+    #[test]
+    fn test_red_flags_scan_prose_only() {
+        let contract = crate::parser::parse_a2ml_string(
+            "## Copyright\n\nThe client acquires all\nrights <!-- all rights -->in the work.\n\n\
+            ```\nall rights\n```\n\n<!-- all rights reserved -->\n\n\
+            @refs:\n[1] All Rights Reserved, a history\n@end\n",
+        )
+        .unwrap();
+        let flags = find_red_flags(&contract, &["all rights".to_string()], true);
+
+        assert_eq!(flags.len(), 1, "{:?}", flags);
+        assert_eq!(flags[0].excerpt, "all rights");
+        assert_eq!(flags[0].line, Some(3));
+        assert_eq!(flags[0].location.as_deref(), Some("Copyright"));
+    }
+
+    #[test]
+    fn test_required_signature_must_be_by_a_trusted_key() {
+        use crate::signing::{SecretKey, SignedKind};
+        let contract = crate::parser::parse_a2ml_string("## Fees\n\nNET 30.\n").unwrap();
+        let (union_key, employer_key) = (SecretKey::from_seed([1; 32]), SecretKey::from_seed([2; 32]));
+        let signed_by = |key: &SecretKey| Some(key.sign(SignedKind::Contract, &canonical_contract(&contract)));
+        let validate = |signature, trusted| {
+            let validator = Validator::new(Default::default(), ValidationMode::Lax).with_required_signature(signature, trusted);
+            validator.validate(&contract, &[])
+        };
+
+        assert!(validate(signed_by(&union_key), vec![union_key.public_key()]).valid);
+        assert!(!validate(signed_by(&employer_key), vec![union_key.public_key()]).valid);
+        assert!(!validate(signed_by(&union_key), Vec::new()).valid, "no trusted keys, nothing passes");
+        assert!(!validate(None, vec![union_key.public_key()]).valid);
+    }
+
+    #[test]
+    fn test_clause_severity_and_help_from_schema() {
+        let schema = crate::parser::parse_a2ml_string(
+            "## Best Practices\n\n### 9. Transparency\n\n**Principle:** Disclose interests.\n\n\
+            **Attestation:** *Should* disclose conflicts.\n",
+        )
+        .unwrap();
+        let contract = crate::parser::parse_a2ml_string("## 1. Pay\n\nNET 30.\n").unwrap();
+
+        let validator = Validator::new(schema, ValidationMode::Lax).with_union(Union::Nuj);
+        let report = validator.validate(&contract, &["transparency".to_string()]);
+
+        let check = &report.required_clauses[0];
+        assert_eq!(check.severity, Severity::Should);
+        assert_eq!(check.help.as_deref(), Some("**Principle:** Disclose interests."));
+        assert!(report.valid, "SHOULD clauses do not invalidate");
+
+        let findings = report.findings();
+        assert!(findings.iter().any(|f| f.rule == "nuj/transparency" && f.severity == Severity::Should));
+        assert!(findings.iter().any(|f| f.rule == "nuj/diversity"));
+    }
 }
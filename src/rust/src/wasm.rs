@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! WebAssembly bindings (`wasm32` targets)
+//!
+//! Parsing, validation and red-flag scanning for browser and Deno front ends,
+//! with no filesystem: contracts and schemas are passed in as strings (or a
+//! union's built-in schema is used), and results come back as JSON strings.
+//! Build with
+//!
+//! ```text
+//! cargo build --profile wasm-release --target wasm32-unknown-unknown --no-default-features
+//! wasm-bindgen --target web target/wasm32-unknown-unknown/wasm-release/union_policy_parser.wasm --out-dir pkg
+//! ```
+//!
+//! Errors are thrown as JS `Error`s carrying the message.
+
+use crate::error::PolicyError;
+use crate::reporter::ReportRenderer;
+use crate::schemas::Union;
+use crate::validator::{find_red_flags, ValidationMode, Validator};
+use wasm_bindgen::prelude::*;
+
+fn js_error(e: impl Into<PolicyError>) -> JsError {
+    JsError::new(&e.into().to_string())
+}
+
+/// Library version
+#[wasm_bindgen]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
+
+/// Parse A2ML source; the document as JSON
+#[wasm_bindgen]
+pub fn parse(source: &str) -> Result<String, JsError> {
+    let document = crate::parse(source).map_err(js_error)?;
+    serde_json::to_string(&document).map_err(js_error)
+}
+
+/// Validate a contract against a schema (both A2ML source); the report as
+/// JSON. `union` is "nuj", "iww" or "ucu"; without a schema, the union's
+/// built-in one is used. `mode` is "lax", "checked" (the default) or
+/// "attested".
+#[wasm_bindgen]
+pub fn validate(
+    contract: &str,
+    schema: Option<String>,
+    union: Option<String>,
+    mode: Option<String>,
+) -> Result<String, JsError> {
+    let mode = match mode {
+        Some(mode) => mode.parse::<ValidationMode>().map_err(js_error)?,
+        None => ValidationMode::Checked,
+    };
+    let union = union.map(|union| union.parse::<Union>()).transpose().map_err(js_error)?;
+    let contract = crate::parse(contract).map_err(js_error)?;
+    let schema = match (schema, union) {
+        (Some(schema), _) => crate::parse(&schema),
+        (None, Some(union)) => union.embedded_schema().parse(),
+        (None, None) => Err(PolicyError::SchemaError("no schema or union given".to_string())),
+    }
+    .map_err(js_error)?;
+
+    let mut validator = Validator::new(schema, mode);
+    let mut required_clauses = Vec::new();
+    if let Some(union) = union {
+        validator = validator.with_union(union);
+        required_clauses.extend(union.required_clauses().iter().map(|s| s.to_string()));
+    }
+
+    let report = validator.validate(&contract, &required_clauses);
+    ReportRenderer::render_json(&report).map_err(js_error)
+}
+
+/// Scan a contract for a union's red-flag phrases and any extra `patterns`
+/// (case-insensitive); the matches as a JSON array
+#[wasm_bindgen(js_name = redFlags)]
+pub fn red_flags(contract: &str, union: Option<String>, patterns: Option<Vec<String>>) -> Result<String, JsError> {
+    let contract = crate::parse(contract).map_err(js_error)?;
+    let mut all = patterns.unwrap_or_default();
+    if let Some(union) = union {
+        let union = union.parse::<Union>().map_err(js_error)?;
+        all.extend(union.red_flag_patterns().iter().map(|s| s.to_string()));
+    }
+    serde_json::to_string(&find_red_flags(&contract, &all, true)).map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    const SCHEMA: &str = "# Schema\n\n## Source Protection\n\n**Attestation:** *Must* protect sources.\n";
+    const CONTRACT: &str = "# Commission\n\n## Payment\n\nAll rights assigned to the publisher.\n";
+
+    #[wasm_bindgen_test]
+    fn test_validate_returns_json_report() {
+        let json = validate(CONTRACT, Some(SCHEMA.to_string()), Some("nuj".to_string()), None).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(report["valid"], false);
+
+        let json = validate(CONTRACT, None, Some("nuj".to_string()), None).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(report["schema"]["path"], "embedded:nuj-code-of-ethics.a2ml");
+
+        let flags: serde_json::Value = serde_json::from_str(&red_flags(CONTRACT, Some("nuj".to_string()), None).unwrap()).unwrap();
+        assert_eq!(flags[0]["pattern"], "all rights");
+        assert_eq!(flags[0]["location"], "Payment");
+    }
+
+    #[wasm_bindgen_test]
+    fn test_bad_input_is_an_error() {
+        let schema = || Some(SCHEMA.to_string());
+        assert!(validate(CONTRACT, schema(), Some("teamsters".to_string()), None).is_err());
+        assert!(validate(CONTRACT, schema(), None, Some("strict".to_string())).is_err());
+        assert!(validate(CONTRACT, None, None, None).is_err());
+    }
+}
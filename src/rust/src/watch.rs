@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Watch mode (`validate --watch`, `batch --watch`)
+//!
+//! Uses filesystem notifications, falling back to polling where they are
+//! unavailable (some network and container filesystems). Files are watched
+//! through their directory, so editors that save by renaming a temporary
+//! file are still seen. Bursts of events are debounced into one set of
+//! changed paths per save.
+//!
+//! Between runs, findings are compared by rule, message and section rather
+//! than line, so inserting a paragraph above a problem does not report it as
+//! fixed and re-introduced.
+
+use crate::error::{PolicyError, Result};
+use crate::validator::Finding;
+use notify::{Config, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher as _};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+/// Quiet period that ends a burst of events
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Poll interval when notifications are unavailable
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+type Events = Receiver<notify::Result<notify::Event>>;
+
+/// Watches files and directory trees for changes
+pub struct Watcher {
+    // Kept alive for as long as events are wanted
+    _watcher: Box<dyn notify::Watcher>,
+    events: Events,
+    files: BTreeSet<PathBuf>,
+    dirs: BTreeSet<PathBuf>,
+}
+
+impl Watcher {
+    /// Watch `paths`: files directly, directories recursively
+    pub fn new(paths: &[PathBuf]) -> Result<Self> {
+        let mut files = BTreeSet::new();
+        let mut dirs = BTreeSet::new();
+        for path in paths {
+            if path.is_dir() {
+                dirs.insert(absolute(path));
+            } else {
+                files.insert(absolute(path));
+            }
+        }
+
+        // Files are watched through their parent, non-recursively
+        let mut targets: Vec<(PathBuf, RecursiveMode)> =
+            dirs.iter().map(|dir| (dir.clone(), RecursiveMode::Recursive)).collect();
+        for parent in files.iter().filter_map(|file| file.parent()).collect::<BTreeSet<_>>() {
+            if !dirs.iter().any(|dir| parent.starts_with(dir)) {
+                targets.push((parent.to_path_buf(), RecursiveMode::NonRecursive));
+            }
+        }
+
+        let (tx, events) = channel();
+        let watcher: Box<dyn notify::Watcher> = match RecommendedWatcher::new(tx.clone(), Config::default())
+            .and_then(|watcher| start(watcher, &targets))
+        {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                log::warn!("Filesystem notifications unavailable ({}); polling every {:?}", e, POLL_INTERVAL);
+                let config = Config::default().with_poll_interval(POLL_INTERVAL);
+                PollWatcher::new(tx, config)
+                    .and_then(|watcher| start(watcher, &targets))
+                    .map_err(|e| PolicyError::ValidationError(format!("Cannot watch files: {}", e)))?
+            }
+        };
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            files,
+            dirs,
+        })
+    }
+
+    /// Block until something watched changes; returns the changed paths (absolute)
+    pub fn wait(&self) -> Result<BTreeSet<PathBuf>> {
+        loop {
+            let first = self.events.recv().map_err(|_| closed())?;
+            let mut changed = BTreeSet::new();
+            self.collect(first, &mut changed);
+
+            loop {
+                match self.events.recv_timeout(DEBOUNCE) {
+                    Ok(event) => self.collect(event, &mut changed),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return Err(closed()),
+                }
+            }
+
+            if !changed.is_empty() {
+                return Ok(changed);
+            }
+        }
+    }
+
+    fn collect(&self, event: notify::Result<notify::Event>, changed: &mut BTreeSet<PathBuf>) {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                log::warn!("Watch error: {}", e);
+                return;
+            }
+        };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        for path in event.paths {
+            let wanted = self.files.contains(&path) || self.dirs.iter().any(|dir| path.starts_with(dir));
+            if wanted {
+                changed.insert(path);
+            }
+        }
+    }
+}
+
+fn start<W: notify::Watcher + 'static>(
+    mut watcher: W,
+    targets: &[(PathBuf, RecursiveMode)],
+) -> notify::Result<Box<dyn notify::Watcher>> {
+    for (path, mode) in targets {
+        watcher.watch(path, *mode)?;
+    }
+    Ok(Box::new(watcher))
+}
+
+fn closed() -> PolicyError {
+    PolicyError::ValidationError("File watcher stopped".to_string())
+}
+
+/// Absolute, symlink-free form of a path that may not exist (yet, or any more),
+/// for matching against event paths
+pub fn absolute(path: &Path) -> PathBuf {
+    if let Ok(path) = path.canonicalize() {
+        return path;
+    }
+    let parent = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    match (parent.canonicalize(), path.file_name()) {
+        (Ok(parent), Some(name)) => parent.join(name),
+        _ => std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()),
+    }
+}
+
+/// Findings compared between two runs
+#[derive(Debug, Default)]
+pub struct FindingsDiff {
+    pub fixed: Vec<Finding>,
+    pub new: Vec<Finding>,
+    pub unchanged: Vec<Finding>,
+}
+
+impl FindingsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.fixed.is_empty() && self.new.is_empty()
+    }
+}
+
+/// Compare findings by rule, message and section; repeats count separately
+pub fn diff_findings(before: &[Finding], after: &[Finding]) -> FindingsDiff {
+    fn key(f: &Finding) -> (&str, &str, Option<&str>) {
+        (&f.rule, &f.message, f.location.as_deref())
+    }
+
+    let mut remaining: BTreeMap<_, usize> = BTreeMap::new();
+    for finding in before {
+        *remaining.entry(key(finding)).or_default() += 1;
+    }
+
+    let mut diff = FindingsDiff::default();
+    for finding in after {
+        match remaining.get_mut(&key(finding)) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                diff.unchanged.push(finding.clone());
+            }
+            _ => diff.new.push(finding.clone()),
+        }
+    }
+
+    // Whatever was not matched has gone
+    for finding in before.iter().rev() {
+        if let Some(count) = remaining.get_mut(&key(finding)) {
+            if *count > 0 {
+                *count -= 1;
+                diff.fixed.push(finding.clone());
+            }
+        }
+    }
+    diff.fixed.reverse();
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator::Severity;
+
+    fn finding(rule: &str, line: usize) -> Finding {
+        Finding {
+            rule: rule.to_string(),
+            severity: Severity::Must,
+            message: format!("Missing {}", rule),
+            location: None,
+            line: Some(line),
+            help: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_ignores_line_moves() {
+        let before = vec![finding("nuj/kill-fee", 3), finding("nuj/red-flag/all-rights", 10), finding("nuj/red-flag/all-rights", 20)];
+        let after = vec![finding("nuj/red-flag/all-rights", 12), finding("nuj/source-protection", 1)];
+
+        let diff = diff_findings(&before, &after);
+        let rules = |findings: &[Finding]| findings.iter().map(|f| f.rule.clone()).collect::<Vec<_>>();
+        assert_eq!(rules(&diff.fixed), ["nuj/kill-fee", "nuj/red-flag/all-rights"]);
+        assert_eq!(rules(&diff.new), ["nuj/source-protection"]);
+        assert_eq!(rules(&diff.unchanged), ["nuj/red-flag/all-rights"]);
+        assert!(diff_findings(&after, &after).is_empty());
+    }
+}
@@ -0,0 +1,239 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! The command-line interface as scripts and K9 recipes see it: exit codes,
+//! `--fail-on`, stdin and the `--output-format` records
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use assert_cmd::Command;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+const SCHEMA: &str = "# Schema\n";
+
+/// A contract with an abstract, one clause and (optionally) references
+fn contract(heading: &str, body: &str, refs: bool) -> String {
+    let refs = if refs { "\n@refs:\n[1] NUJ Code of Conduct\n@end\n" } else { "" };
+    format!("# Commission\n\n@abstract:\nA commission.\n@end\n\n## {}\n\n{}\n{}", heading, body, refs)
+}
+
+/// A directory holding the schema and a few contracts
+fn workspace() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let files = [
+        ("schema.a2ml", SCHEMA.to_string()),
+        ("clean.a2ml", contract("Payment", "**Net Days:** 30\n\nThe writer grants all rights.", true)),
+        ("missing.a2ml", contract("Rights", "First British serial rights.", true)),
+        ("no-refs.a2ml", contract("Payment", "**Net Days:** 30", false)),
+        ("broken.a2ml", "# Commission\n\n@abstract:\nnever closed\n".to_string()),
+    ];
+    for (name, text) in files {
+        fs::write(dir.path().join(name), text).unwrap();
+    }
+    dir
+}
+
+fn cli(dir: &Path) -> Command {
+    let mut cmd = assert_cmd::cargo_bin_cmd!("union-policy-parser");
+    cmd.current_dir(dir).env("RUST_LOG", "warn");
+    cmd
+}
+
+fn validate(dir: &Path, contract: &str) -> Command {
+    let mut cmd = cli(dir);
+    cmd.args(["validate", contract, "--schema", "schema.a2ml", "--required-clauses", "payment"]);
+    cmd
+}
+
+fn json(stdout: &[u8]) -> Value {
+    serde_json::from_slice(stdout).expect("stdout is one JSON object")
+}
+
+fn ndjson(stdout: &[u8]) -> Vec<Value> {
+    String::from_utf8_lossy(stdout).lines().map(|line| serde_json::from_str(line).expect("a JSON line")).collect()
+}
+
+#[test]
+fn test_exit_codes() {
+    let dir = workspace();
+    let dir = dir.path();
+
+    validate(dir, "clean.a2ml").assert().code(0);
+    validate(dir, "missing.a2ml").assert().code(1);
+    validate(dir, "broken.a2ml").assert().code(3);
+    validate(dir, "absent.a2ml").assert().code(4);
+    // clap's own usage status would be 2, which means SHOULD findings here
+    cli(dir).args(["validate", "--no-such-flag"]).assert().code(4);
+
+    // A missing @refs section is a SHOULD finding
+    validate(dir, "no-refs.a2ml").assert().code(0);
+    validate(dir, "no-refs.a2ml").arg("--fail-on=warning").assert().code(2);
+    validate(dir, "missing.a2ml").arg("--fail-on=never").assert().code(0);
+    // ...but not for errors that stop the command
+    validate(dir, "broken.a2ml").arg("--fail-on=never").assert().code(3);
+    // --strict is shorthand for --fail-on=warning
+    validate(dir, "no-refs.a2ml").arg("--strict").assert().code(2);
+    validate(dir, "no-refs.a2ml").args(["--strict", "--fail-on=error"]).assert().code(0);
+
+    // The most serious problem in a batch wins
+    cli(dir).args(["batch", ".", "--schema", "schema.a2ml", "--output", "report.json", "--no-cache"]).assert().code(3);
+}
+
+#[test]
+fn test_red_flags_fail_the_run() {
+    let dir = workspace();
+    let dir = dir.path();
+    let scan = |contract: &str| {
+        let mut cmd = cli(dir);
+        cmd.args(["scan-red-flags", contract, "--patterns", "all rights"]);
+        cmd
+    };
+
+    scan("clean.a2ml").assert().code(1);
+    scan("clean.a2ml").arg("--fail-on=never").assert().code(0);
+    scan("missing.a2ml").assert().code(0);
+}
+
+#[test]
+fn test_json_and_ndjson_records() {
+    let dir = workspace();
+    let dir = dir.path();
+
+    let output = validate(dir, "clean.a2ml").arg("--output-format=json").output().unwrap();
+    let result = json(&output.stdout);
+    assert_eq!(result["schema_version"], 1);
+    assert_eq!(result["command"], "validate");
+    assert_eq!(result["record"], "result");
+    assert_eq!(result["status"], "clean");
+    assert_eq!(result["valid"], true);
+    assert_eq!(result["required_clauses"][0]["section"], "Payment");
+
+    // Findings are streamed first, each with the same header
+    let output = validate(dir, "missing.a2ml").arg("--output-format=ndjson").output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let records = ndjson(&output.stdout);
+    let kinds: Vec<&str> = records.iter().map(|r| r["record"].as_str().unwrap()).collect();
+    assert_eq!(kinds, ["finding", "result"]);
+    assert!(records.iter().all(|r| r["schema_version"] == 1 && r["command"] == "validate"));
+    assert_eq!(records[0]["severity"], "MUST");
+    assert_eq!(records[1]["status"], "violations");
+    assert!(records[1].get("findings").is_none());
+
+    // An error that stops the command is a record too, with its exit code
+    let output = validate(dir, "broken.a2ml").arg("--output-format=json").output().unwrap();
+    let error = json(&output.stdout);
+    assert_eq!(error["record"], "error");
+    assert_eq!(error["exit_code"], 3);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_stdin_contract() {
+    let dir = workspace();
+    let dir = dir.path();
+    let source = fs::read_to_string(dir.join("missing.a2ml")).unwrap();
+
+    let output = validate(dir, "-").arg("--output-format=json").write_stdin(source.clone()).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(json(&output.stdout)["contract"], "<stdin>");
+
+    let output = validate(dir, "-")
+        .args(["--output-format=json", "--name", "acme/offer-v2"])
+        .write_stdin(source)
+        .output()
+        .unwrap();
+    assert_eq!(json(&output.stdout)["contract"], "acme/offer-v2");
+
+    // An empty contract parses; it is just missing everything
+    validate(dir, "-").write_stdin("").assert().code(1);
+}
+
+#[test]
+fn test_check_clause_values() {
+    let dir = workspace();
+    let dir = dir.path();
+    let check = |args: &[&str]| {
+        let mut cmd = cli(dir);
+        cmd.args(["check-clause", "clean.a2ml"]).args(args);
+        cmd
+    };
+
+    let output = check(&["--clause", "payment.net-days", "--max", "30", "--output-format=json"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let result = json(&output.stdout);
+    assert_eq!(result["value"], "30");
+    assert_eq!(result["problems"], serde_json::json!([]));
+
+    check(&["--clause", "payment.net-days", "--max", "14"]).assert().code(1);
+    check(&["--clause", "payment.net-days", "--min", "45"]).assert().code(1);
+    check(&["--clause", "payment.net-days", "--allowed", "30,60"]).assert().code(0);
+    check(&["--clause", "payment.net-days", "--expected", "60"]).assert().code(1);
+    // No value is a missing clause: clean unless told otherwise
+    check(&["--clause", "payment.late-fee", "--min", "1"]).assert().code(0);
+    check(&["--clause", "payment.late-fee", "--min", "1", "--warn-if-not"]).assert().code(2);
+    check(&["--clause", "payment.late-fee", "--min", "1", "--error-if-not"]).assert().code(1);
+}
+
+#[test]
+fn test_audit_logs_only_when_asked() {
+    let dir = workspace();
+    let dir = dir.path();
+    let audit = || {
+        let mut cmd = cli(dir);
+        cmd.args(["audit", "clean.a2ml", "--schema", "schema.a2ml", "--output", "report.json"]);
+        cmd
+    };
+
+    audit().assert().code(0);
+    assert!(!dir.join("union-policy-audit.jsonl").exists());
+
+    audit().args(["--log", "runs.jsonl"]).assert().code(0);
+    fs::write(dir.join("union-policy.toml"), "audit_log = \"audit.jsonl\"\n").unwrap();
+    audit().assert().code(0);
+    audit().arg("--no-log").assert().code(0);
+    for (log, runs) in [("runs.jsonl", 1), ("audit.jsonl", 1)] {
+        assert_eq!(fs::read_to_string(dir.join(log)).unwrap().lines().count(), runs, "{}", log);
+    }
+}
+
+#[test]
+fn test_clauses_found_by_heading_words() {
+    let dir = workspace();
+    let dir = dir.path();
+    let body = "1. Encrypt notes\n2. Never disclose\n\n| Party | Duty |\n|---|---|\n| Writer | Protect |";
+    fs::write(dir.join("sources.a2ml"), contract("5. Protection of Sources", body, true)).unwrap();
+
+    cli(dir).args(["check-clause", "sources.a2ml", "--clause", "source-protection", "--error-if-not"]).assert().code(0);
+    let output = cli(dir).args(["get-clause", "sources.a2ml", "--clause", "source-protection"]).output().unwrap();
+    let text = String::from_utf8_lossy(&output.stdout);
+    assert!(text.contains("Clause: 5. Protection of Sources"), "{}", text);
+    assert!(text.contains("2. Never disclose") && text.contains("| Writer | Protect |"), "{}", text);
+}
+
+#[test]
+fn test_verify_requires_a_trusted_signer() {
+    let dir = workspace();
+    let dir = dir.path();
+    for key in ["union", "publisher"] {
+        cli(dir).args(["keygen", key]).assert().code(0);
+    }
+    cli(dir).args(["sign", "clean.a2ml", "--key", "publisher"]).assert().code(0);
+
+    // Without a trusted key the signature proves nothing
+    cli(dir).args(["verify", "clean.a2ml"]).assert().code(1);
+    let output = cli(dir).args(["verify", "clean.a2ml", "--key", "union.pub", "--output-format=json"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let result = json(&output.stdout);
+    assert_eq!(result["valid"], false);
+    assert!(result["reason"].as_str().unwrap().contains("not a trusted key"));
+
+    cli(dir).args(["verify", "clean.a2ml", "--key", "publisher.pub"]).assert().code(0);
+    fs::write(dir.join("union-policy.toml"), "trusted_keys = [\"publisher.pub\"]\n").unwrap();
+    cli(dir).args(["verify", "clean.a2ml"]).assert().code(0);
+
+    // Any edit after signing breaks it
+    let source = fs::read_to_string(dir.join("clean.a2ml")).unwrap();
+    fs::write(dir.join("clean.a2ml"), source.replace("30", "90")).unwrap();
+    cli(dir).args(["verify", "clean.a2ml"]).assert().code(1);
+}